@@ -0,0 +1,168 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    memory::{buffer_reader::BufferReader, buffer_writer::BufferWriter},
+    register::RegisterType,
+};
+
+pub use proc::{Decode, Encode};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EncodingError {
+    /// The buffer ended before the value did
+    UnexpectedEnd,
+    /// A byte that is not one of the values of the type being decoded
+    InvalidTag(u8),
+    InvalidUtf8,
+}
+
+impl Display for EncodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "the buffer ended in the middle of a value"),
+            Self::InvalidTag(tag) => write!(f, "invalid tag byte: {}", tag),
+            Self::InvalidUtf8 => write!(f, "string is not valid utf8"),
+        }
+    }
+}
+
+impl Error for EncodingError {}
+
+/// A value with a little endian byte layout
+///
+/// Structs can derive it, their fields are written in declaration order. Sequences and
+/// strings are written with a u32 length first.
+pub trait Encode {
+    fn encode(&self, writer: &mut BufferWriter);
+
+    fn to_encoded_bytes(&self) -> Vec<u8> {
+        let mut writer = BufferWriter::new();
+        self.encode(&mut writer);
+        return writer.into_bytes();
+    }
+}
+
+/// Reads back what `Encode` writes
+///
+/// # Examples
+///
+/// ```
+/// use common::encoding::{Decode, Encode};
+/// #[derive(Debug, PartialEq, Encode, Decode)]
+/// struct Entry {
+///     hash: u64,
+///     name: String,
+///     offsets: Vec<u16>,
+/// }
+/// let entry = Entry { hash: 7, name: "main".to_string(), offsets: vec![3, 9] };
+/// let bytes = entry.to_encoded_bytes();
+/// assert_eq!(bytes.len(), 8 + 4 + 4 + 4 + 2 * 2);
+/// assert_eq!(Entry::from_encoded_bytes(&bytes), Ok(entry));
+/// ```
+pub trait Decode: Sized {
+    fn decode(reader: &mut BufferReader) -> Result<Self, EncodingError>;
+
+    fn from_encoded_bytes(bytes: &[u8]) -> Result<Self, EncodingError> {
+        return Self::decode(&mut BufferReader::new(bytes));
+    }
+}
+
+macro_rules! integer_encoding {
+    ($($ty:ty => $write:ident, $read:ident;)*) => {
+        $(
+            impl Encode for $ty {
+                fn encode(&self, writer: &mut BufferWriter) {
+                    writer.$write(*self);
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode(reader: &mut BufferReader) -> Result<Self, EncodingError> {
+                    return reader.$read().ok_or(EncodingError::UnexpectedEnd);
+                }
+            }
+        )*
+    };
+}
+
+integer_encoding! {
+    u8 => write_u8, read_u8;
+    u16 => write_u16, read_u16;
+    u32 => write_u32, read_u32;
+    u64 => write_u64, read_u64;
+    i64 => write_i64, read_i64;
+}
+
+impl Encode for bool {
+    fn encode(&self, writer: &mut BufferWriter) {
+        writer.write_u8(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(reader: &mut BufferReader) -> Result<Self, EncodingError> {
+        return match u8::decode(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(EncodingError::InvalidTag(tag)),
+        };
+    }
+}
+
+impl Encode for RegisterType {
+    fn encode(&self, writer: &mut BufferWriter) {
+        writer.write_u8(self.to_byte());
+    }
+}
+
+impl Decode for RegisterType {
+    fn decode(reader: &mut BufferReader) -> Result<Self, EncodingError> {
+        let byte = u8::decode(reader)?;
+        return RegisterType::from_byte(byte).map_err(|_| EncodingError::InvalidTag(byte));
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, writer: &mut BufferWriter) {
+        writer.write_u32(self.len() as u32);
+        writer.write_bytes(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, writer: &mut BufferWriter) {
+        self.as_str().encode(writer);
+    }
+}
+
+impl Decode for String {
+    fn decode(reader: &mut BufferReader) -> Result<Self, EncodingError> {
+        let len = u32::decode(reader)?;
+        let bytes = reader
+            .read_bytes(len as usize)
+            .ok_or(EncodingError::UnexpectedEnd)?;
+        return String::from_utf8(bytes.to_vec()).map_err(|_| EncodingError::InvalidUtf8);
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, writer: &mut BufferWriter) {
+        writer.write_u32(self.len() as u32);
+        for value in self.iter() {
+            value.encode(writer);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, writer: &mut BufferWriter) {
+        self.as_slice().encode(writer);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut BufferReader) -> Result<Self, EncodingError> {
+        let len = u32::decode(reader)?;
+        return (0..len).map(|_| T::decode(reader)).collect();
+    }
+}
@@ -0,0 +1,217 @@
+pub mod decoded;
+
+/// How an operand is written in assembly and encoded after the op code
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operand {
+    /// A general purpose register byte
+    Register,
+    /// The stack pointer, encoded as a register byte
+    Sp,
+    U16,
+    U32,
+    U64,
+    /// A type or field name hashed like section names, or the hash itself, encoded as a u64
+    Hash,
+    /// An integer sized to the register before it
+    Immediate,
+    /// The hash of any section
+    Section,
+    /// The hash of a procedure section
+    Procedure,
+    /// A label, encoded as the hash of the section it is in followed by a u16 offset
+    Jump,
+    /// Labels until the end of the line, encoded as the section hash, a u16 entry count and a
+    /// u16 offset per entry
+    JumpTable,
+    /// `[register]`, encoded as a register byte
+    Deref,
+    /// `[register + offset]` or `[register - offset]`, encoded as a register byte, a u32 offset
+    /// and a boolean that is true for `+`
+    DerefOffset,
+}
+
+/// One encoding of a mnemonic
+#[derive(Debug, PartialEq)]
+pub struct Form {
+    opcode: u16,
+    sub_opcode: Option<u8>,
+    operands: &'static [Operand],
+    syntax: Option<&'static [usize]>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    mnemonic: &'static str,
+    forms: &'static [Form],
+}
+
+impl Form {
+    const fn new(
+        opcode: u16,
+        sub_opcode: Option<u8>,
+        operands: &'static [Operand],
+        syntax: Option<&'static [usize]>,
+    ) -> Self {
+        Self {
+            opcode,
+            sub_opcode,
+            operands,
+            syntax,
+        }
+    }
+
+    pub fn opcode(&self) -> u16 {
+        return self.opcode;
+    }
+
+    pub fn sub_opcode(&self) -> Option<u8> {
+        return self.sub_opcode;
+    }
+
+    /// Operands in encoding order
+    pub fn operands(&self) -> &'static [Operand] {
+        return self.operands;
+    }
+
+    /// The encoding index of each operand in the order they are written in assembly
+    pub fn syntax_order(&self) -> impl Iterator<Item = usize> + '_ {
+        return (0..self.operands.len())
+            .map(|i| self.syntax.map_or(i, |syntax| syntax[i]));
+    }
+}
+
+impl Instruction {
+    const fn new(mnemonic: &'static str, forms: &'static [Form]) -> Self {
+        Self { mnemonic, forms }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        return self.mnemonic;
+    }
+
+    /// Forms in the order the assembler tries them
+    pub fn forms(&self) -> &'static [Form] {
+        return self.forms;
+    }
+}
+
+/// Find an instruction by its mnemonic
+///
+/// # Examples
+///
+/// ```
+/// use common::isa::{instruction, opcodes::JMC_OPCODE};
+/// assert_eq!(instruction("jmc").unwrap().forms()[0].opcode(), JMC_OPCODE);
+/// assert!(instruction("jmq").is_none());
+/// ```
+pub fn instruction(mnemonic: &str) -> Option<&'static Instruction> {
+    return INSTRUCTIONS
+        .iter()
+        .find(|instruction| instruction.mnemonic == mnemonic);
+}
+
+/// Find the form of an op code and sub op code, and the instruction it belongs to
+pub fn form(opcode: u16, sub_opcode: Option<u8>) -> Option<(&'static Instruction, &'static Form)> {
+    return INSTRUCTIONS.iter().find_map(|instruction| {
+        instruction
+            .forms
+            .iter()
+            .find(|form| form.opcode == opcode && form.sub_opcode == sub_opcode)
+            .map(|form| (instruction, form))
+    });
+}
+
+/// Whether the first operand byte of the op code selects one of its forms
+pub fn has_sub_opcode(opcode: u16) -> bool {
+    return INSTRUCTIONS
+        .iter()
+        .flat_map(|instruction| instruction.forms.iter())
+        .any(|form| form.opcode == opcode && form.sub_opcode.is_some());
+}
+
+proc::isa! {
+    // Memory releate instructions
+    mov 16 {
+        MOV_REG2REG = 1 (Register, Register),
+        MOV_DEREF_REG2REG = 6 (Register, Deref),
+        MOV_NUM2REG = 3 (Register, Immediate),
+        MOV_ADD2SP = 4 (Sp, U64),
+        MOV_REG2SP = 5 (Sp, Register),
+        MOV_SECTION_ADDR_2REG = 7 (Register, Section),
+        MOV_NUM2DEREF_REG_WITH_OFFSET = 9 (DerefOffset, U64),
+        MOV_REG2DEREF_REG_WITH_OFFSET = 10 (DerefOffset, Register),
+        MOV_DEREF_REG_WITH_OFFSET2REG = 11 (Register, DerefOffset),
+        MOV_SECTION_ADDR2DEREF_REG_WITH_OFFSET = 12 (DerefOffset, Section),
+        MOV_REG2DEREF_REG = 2 (Register, Deref) syntax(1, 0),
+        MOV_NUM2DEREF_REG = 8 (Deref, U64),
+    }
+    push 17 (Register)
+    pop 18 (Register)
+    enter 19 (U64)
+    leave 20 ()
+    arg 21 {
+        ARG_NUM = 1 (U32, U64),
+        ARG_REG = 2 (U32, Register),
+    }
+    larg 22 (Register, U32)
+    savr 23 (Register)
+    restr 24 (Register)
+    mcpy 25 (Register, Register, Register)
+    mset 26 (Register, Register, Register)
+    mcmp 27 (Register, Register, Register)
+
+    // Arithmetic instructions
+    inc 30 (Register)
+    cmp 31 (Register, Register)
+    add 32 {
+        ADD_REG_W_REG = 1 (Register, Register),
+        ADD_REG_W_NUM = 2 (Register, U64),
+        ADD_SP_W_NUM = 3 (Sp, U64),
+    }
+    sub 33 {
+        SUB_REG_W_REG = 1 (Register, Register),
+        SUB_REG_W_NUM = 2 (Register, U64),
+        SUB_SP_W_NUM = 3 (Sp, U64),
+    }
+    mul 34 (Register, Register)
+    div 35 (Register, Register)
+
+    // Branching instructions
+    jmp 64 (Jump) | jmpr 76 (Register)
+    jmz 65 (Jump)
+    jmn 66 (Jump)
+    jacn 67 (Register, Register, Jump)
+    jacz 68 (Register, Register, Jump)
+    jacc 69 (Register, Register, Jump)
+    jace 70 (Register, Register, Jump)
+    jme 71 (Jump)
+    jmc 72 (Jump)
+    call 73 (Procedure) | callr 75 (Register)
+    ret 74 ()
+    jtab 77 (Register, JumpTable)
+
+    // Thread instructions
+    spawn 96 (Register, Procedure)
+    r#yield 97 ()
+    join 98 (Register, Register)
+    chan 99 (Register)
+    send 100 (Register, Register)
+    recv 101 (Register, Register)
+    close 102 (Register)
+
+    // Typed local instructions
+    local 103 (U16)
+    itylal 104 (U16)
+    ityl 105 (U16, Hash)
+    ftyll 106 (U16, Hash, U16)
+    clty 107 (U16)
+    rl 108 (U16)
+
+    // Cpu state releate instructions
+    exit 0xFFFE (Register)
+    halt 0xFFFF ()
+
+    // IO instructions
+    outc 128 (Register)
+    outs 129 (Register)
+}
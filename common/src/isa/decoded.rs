@@ -0,0 +1,324 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    encoding::Encode,
+    memory::{buffer_reader::BufferReader, buffer_writer::BufferWriter},
+    register::RegisterType,
+};
+
+use super::{form, has_sub_opcode, Form, Instruction, Operand};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecodeError {
+    InvalidInstructionLength(usize),
+    TruncatedInstruction(usize),
+    UnknownOpCode(u16),
+    UnknownSubOpCode(u16, u8),
+    TruncatedOperands(u16),
+    TrailingOperands(u16, usize),
+    InvalidRegister(u8),
+    InvalidBoolean(u8),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidInstructionLength(length) => {
+                write!(f, "invalid instruction length: {}", length)
+            }
+            Self::TruncatedInstruction(length) => write!(
+                f,
+                "instruction length {} goes past the end of the code",
+                length
+            ),
+            Self::UnknownOpCode(opcode) => write!(f, "unknown op code: {}", opcode),
+            Self::UnknownSubOpCode(opcode, sub_opcode) => write!(
+                f,
+                "unknown sub op code {} for op code {}",
+                sub_opcode, opcode
+            ),
+            Self::TruncatedOperands(opcode) => {
+                write!(f, "not enough operand bytes for op code {}", opcode)
+            }
+            Self::TrailingOperands(opcode, count) => write!(
+                f,
+                "{} unexpected trailing operand bytes for op code {}",
+                count, opcode
+            ),
+            Self::InvalidRegister(byte) => write!(f, "invalid register byte: {}", byte),
+            Self::InvalidBoolean(byte) => write!(f, "invalid boolean byte: {}", byte),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// An operand read from the encoding of an instruction
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Register(RegisterType),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Immediate(u64),
+    Section(u64),
+    Procedure(u64),
+    Jump(u64, u16),
+    JumpTable(u64, Vec<u16>),
+    Deref(RegisterType),
+    /// The register, the offset and whether it is added
+    DerefOffset(RegisterType, u32, bool),
+}
+
+/// An instruction with its operands in encoding order
+#[derive(Debug, PartialEq)]
+pub struct Decoded {
+    instruction: &'static Instruction,
+    form: &'static Form,
+    values: Vec<Value>,
+    length: usize,
+}
+
+impl Decoded {
+    /// An instruction of `form` with `values` in encoding order, they have to match the
+    /// operands of the form
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::encoding::Encode;
+    /// use common::isa::{decoded::{Decoded, Value}, instruction};
+    /// use common::register::RegisterType;
+    /// let form = &instruction("push").unwrap().forms()[0];
+    /// let push = Decoded::new(form, vec![Value::Register(RegisterType::A64)]);
+    /// assert_eq!(push.length(), 4);
+    /// assert_eq!(Decoded::decode(&push.to_encoded_bytes()).unwrap(), push);
+    /// ```
+    pub fn new(form: &'static Form, values: Vec<Value>) -> Self {
+        let (instruction, form) =
+            super::form(form.opcode(), form.sub_opcode()).expect("form is not in the table");
+        let mut decoded = Self {
+            instruction,
+            form,
+            values,
+            length: 0,
+        };
+        decoded.length = decoded.to_encoded_bytes().len();
+        return decoded;
+    }
+
+    /// Decode the instruction at the start of `code`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::isa::decoded::{Decoded, Value};
+    /// use common::isa::opcodes::{MOV_OPCODE, MOV_REG2DEREF_REG};
+    /// use common::register::RegisterType;
+    /// let code = [6, MOV_OPCODE as u8, 0, MOV_REG2DEREF_REG, 4, 8, 0xFF];
+    /// let decoded = Decoded::decode(&code).unwrap();
+    /// assert_eq!(decoded.length(), 6);
+    /// assert_eq!(decoded.values(), &[Value::Register(RegisterType::A64), Value::Deref(RegisterType::B64)]);
+    /// assert_eq!(decoded.to_string(), "mov [b64], a64");
+    /// ```
+    pub fn decode(code: &[u8]) -> Result<Self, DecodeError> {
+        let length = *code
+            .first()
+            .ok_or(DecodeError::InvalidInstructionLength(0))? as usize;
+        if length < 3 {
+            return Err(DecodeError::InvalidInstructionLength(length));
+        }
+        let bytes = code
+            .get(..length)
+            .ok_or(DecodeError::TruncatedInstruction(length))?;
+        let opcode = u16::from_le_bytes([bytes[1], bytes[2]]);
+        let mut reader = BufferReader::new(&bytes[3..]);
+        let truncated = DecodeError::TruncatedOperands(opcode);
+        let sub_opcode = if has_sub_opcode(opcode) {
+            Some(reader.read_u8().ok_or(truncated)?)
+        } else {
+            None
+        };
+        let (instruction, form) = form(opcode, sub_opcode).ok_or(match sub_opcode {
+            Some(sub_opcode) => DecodeError::UnknownSubOpCode(opcode, sub_opcode),
+            None => DecodeError::UnknownOpCode(opcode),
+        })?;
+
+        let register = |reader: &mut BufferReader| {
+            let byte = reader.read_u8().ok_or(truncated)?;
+            return RegisterType::from_byte(byte).map_err(|_| DecodeError::InvalidRegister(byte));
+        };
+        let mut values = Vec::new();
+        let mut last_register = None;
+        for operand in form.operands() {
+            let value = match operand {
+                Operand::Register | Operand::Sp => {
+                    let register = register(&mut reader)?;
+                    last_register = Some(register);
+                    Value::Register(register)
+                }
+                Operand::U16 => Value::U16(reader.read_u16().ok_or(truncated)?),
+                Operand::U32 => Value::U32(reader.read_u32().ok_or(truncated)?),
+                Operand::U64 | Operand::Hash => Value::U64(reader.read_u64().ok_or(truncated)?),
+                Operand::Immediate => {
+                    let size = last_register
+                        .map(|register| register.size().byte())
+                        .unwrap_or(8);
+                    let mut value = [0; 8];
+                    value[..size].copy_from_slice(reader.read_bytes(size).ok_or(truncated)?);
+                    Value::Immediate(u64::from_le_bytes(value))
+                }
+                Operand::Section => Value::Section(reader.read_u64().ok_or(truncated)?),
+                Operand::Procedure => Value::Procedure(reader.read_u64().ok_or(truncated)?),
+                Operand::Jump => Value::Jump(
+                    reader.read_u64().ok_or(truncated)?,
+                    reader.read_u16().ok_or(truncated)?,
+                ),
+                Operand::JumpTable => {
+                    let section = reader.read_u64().ok_or(truncated)?;
+                    let count = reader.read_u16().ok_or(truncated)?;
+                    let table = (0..count)
+                        .map(|_| reader.read_u16())
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or(truncated)?;
+                    Value::JumpTable(section, table)
+                }
+                Operand::Deref => Value::Deref(register(&mut reader)?),
+                Operand::DerefOffset => {
+                    let register = register(&mut reader)?;
+                    let offset = reader.read_u32().ok_or(truncated)?;
+                    let add = match reader.read_u8().ok_or(truncated)? {
+                        0 => false,
+                        1 => true,
+                        byte => return Err(DecodeError::InvalidBoolean(byte)),
+                    };
+                    Value::DerefOffset(register, offset, add)
+                }
+            };
+            values.push(value);
+        }
+
+        let remaining = bytes.len() - 3 - reader.get_read_pos();
+        if remaining != 0 {
+            return Err(DecodeError::TrailingOperands(opcode, remaining));
+        }
+        return Ok(Self {
+            instruction,
+            form,
+            values,
+            length,
+        });
+    }
+
+    pub fn instruction(&self) -> &'static Instruction {
+        return self.instruction;
+    }
+
+    pub fn form(&self) -> &'static Form {
+        return self.form;
+    }
+
+    pub fn values(&self) -> &[Value] {
+        return &self.values;
+    }
+
+    pub fn length(&self) -> usize {
+        return self.length;
+    }
+}
+
+impl Encode for Decoded {
+    fn encode(&self, writer: &mut BufferWriter) {
+        let mut operands = BufferWriter::new();
+        if let Some(sub_opcode) = self.form.sub_opcode() {
+            operands.write_u8(sub_opcode);
+        }
+        let mut last_register = None;
+        for value in self.values.iter() {
+            match value {
+                Value::Register(register) => {
+                    register.encode(&mut operands);
+                    last_register = Some(*register);
+                }
+                Value::U16(value) => value.encode(&mut operands),
+                Value::U32(value) => value.encode(&mut operands),
+                Value::U64(value) | Value::Section(value) | Value::Procedure(value) => {
+                    value.encode(&mut operands)
+                }
+                Value::Immediate(value) => {
+                    let size = last_register
+                        .map(|register| register.size().byte())
+                        .unwrap_or(8);
+                    operands.write_bytes(&value.to_le_bytes()[..size]);
+                }
+                Value::Jump(section, offset) => {
+                    section.encode(&mut operands);
+                    offset.encode(&mut operands);
+                }
+                Value::JumpTable(section, table) => {
+                    section.encode(&mut operands);
+                    operands.write_u16(table.len() as u16);
+                    table.iter().for_each(|offset| offset.encode(&mut operands));
+                }
+                Value::Deref(register) => register.encode(&mut operands),
+                Value::DerefOffset(register, offset, add) => {
+                    register.encode(&mut operands);
+                    offset.encode(&mut operands);
+                    add.encode(&mut operands);
+                }
+            }
+        }
+        writer.write_u8((operands.get_write_pos() + 3) as u8);
+        writer.write_u16(self.form.opcode());
+        writer.write_bytes(operands.bytes());
+    }
+}
+
+fn register_name(register: &RegisterType) -> String {
+    return match register {
+        RegisterType::Sp => "sp".to_string(),
+        RegisterType::Ip => "ip".to_string(),
+        register => register.to_string(),
+    };
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Register(register) => write!(f, "{}", register_name(register)),
+            Self::U16(value) => write!(f, "{}", value),
+            Self::U32(value) => write!(f, "{}", value),
+            Self::U64(value) | Self::Immediate(value) => write!(f, "{}", value),
+            Self::Section(hash) => write!(f, "section {:#018x}", hash),
+            Self::Procedure(hash) => write!(f, "{:#018x}", hash),
+            Self::Jump(section, offset) => write!(f, "{:#018x}+{}", section, offset),
+            Self::JumpTable(section, table) => {
+                write!(f, "{:#018x}", section)?;
+                for offset in table {
+                    write!(f, " +{}", offset)?;
+                }
+                Ok(())
+            }
+            Self::Deref(register) => write!(f, "[{}]", register_name(register)),
+            Self::DerefOffset(register, offset, add) => write!(
+                f,
+                "[{} {} {}]",
+                register_name(register),
+                if *add { "+" } else { "-" },
+                offset
+            ),
+        }
+    }
+}
+
+/// Disassembles to the assembly syntax, section hashes are printed where the source had names
+impl Display for Decoded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.instruction.mnemonic())?;
+        for (i, index) in self.form.syntax_order().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{}", separator, self.values[index])?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
+extern crate self as common;
+
 pub mod commands;
 pub mod constants;
+pub mod encoding;
+pub mod isa;
 pub mod memory;
 pub mod no_hash_hashmap;
 pub mod register;
@@ -1 +1,2 @@
 pub mod buffer_reader;
+pub mod buffer_writer;
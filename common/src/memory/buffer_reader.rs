@@ -14,10 +14,24 @@ impl<'a> BufferReader<'a> {
         }
     }
 
+    /// Read `length` bytes, the read position only moves when all of them are there
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::memory::buffer_reader::BufferReader;
+    /// let mut reader = BufferReader::new(&[1, 2, 3]);
+    /// assert_eq!(reader.read_bytes(4), None);
+    /// assert_eq!(reader.get_read_pos(), 0);
+    /// assert_eq!(reader.read_bytes(2), Some(&[1, 2][..]));
+    /// assert_eq!(reader.get_read_pos(), 2);
+    /// ```
     pub fn read_bytes(&mut self, length: usize) -> Option<&'a [u8]> {
-        let read_bytes = self.buffer.get(self.read_pos..self.read_pos + length);
+        let read_bytes = self
+            .buffer
+            .get(self.read_pos..self.read_pos.checked_add(length)?)?;
         self.read_pos += length;
-        return read_bytes;
+        return Some(read_bytes);
     }
 
     pub fn read_i64(&mut self) -> Option<i64> {
@@ -0,0 +1,63 @@
+/// Writes little endian values into a growing buffer, the counterpart of `BufferReader`
+#[derive(Debug, Default)]
+pub struct BufferWriter {
+    buffer: Vec<u8>,
+}
+
+impl BufferWriter {
+    pub fn new() -> Self {
+        Self { buffer: Vec::new() }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    /// Overwrite bytes that were already written, like a placeholder that is known later
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::memory::buffer_writer::BufferWriter;
+    /// let mut writer = BufferWriter::new();
+    /// writer.write_u16(0);
+    /// writer.write_u8(7);
+    /// writer.write_at(0, &3u16.to_le_bytes());
+    /// assert_eq!(writer.into_bytes(), vec![3, 0, 7]);
+    /// ```
+    pub fn write_at(&mut self, position: usize, bytes: &[u8]) {
+        self.buffer[position..position + bytes.len()].copy_from_slice(bytes);
+    }
+
+    pub fn get_write_pos(&self) -> usize {
+        return self.buffer.len();
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        return &self.buffer;
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        return self.buffer;
+    }
+}
@@ -4,12 +4,15 @@ use sections::SinSection;
 
 use crate::{
     constants::{MAGIC_1, MAGIC_2, MAGIC_3, MAGIC_4},
-    memory::buffer_reader::BufferReader,
+    encoding::Encode,
+    memory::{buffer_reader::BufferReader, buffer_writer::BufferWriter},
 };
 
+pub mod debug_info;
 pub mod sections;
+pub mod type_info;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SinError {
     InvalidSin,
     InvalidSection,
@@ -75,15 +78,15 @@ impl<'a> Sin<'a> {
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut buffer = Vec::new();
-        buffer.extend_from_slice(&[MAGIC_1, MAGIC_2, MAGIC_3, MAGIC_4]);
-        buffer.extend_from_slice(&(self.sections.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&self.data.len().to_le_bytes());
+        let mut writer = BufferWriter::new();
+        writer.write_bytes(&[MAGIC_1, MAGIC_2, MAGIC_3, MAGIC_4]);
+        writer.write_u32(self.sections.len() as u32);
+        writer.write_u64(self.data.len() as u64);
         self.sections
             .iter()
-            .for_each(|section| buffer.extend_from_slice(&section.to_bytes()));
-        buffer.extend_from_slice(self.data);
+            .for_each(|section| section.encode(&mut writer));
+        writer.write_bytes(self.data);
 
-        return buffer;
+        return writer.into_bytes();
     }
 }
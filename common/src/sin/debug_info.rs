@@ -0,0 +1,141 @@
+use crate::encoding::{Decode, Encode};
+
+use super::SinError;
+
+/// Name used to derive the hash of the debug section
+pub const DEBUG_SECTION_NAME: &str = "$debug";
+
+/// Debug information of every procedure in a sin file
+///
+/// Layout:
+/// "{procedure_count: u32}{procedure...}"
+///
+/// Procedure:
+/// "{hash: u64}{name: str}{file: str}{line_count: u32}{line...}"
+///
+/// Line:
+/// "{offset: u32}{line: u32}{column: u32}"
+///
+/// 'str' is a u32 byte length followed by utf8 bytes
+#[derive(Debug, Default, Clone, PartialEq, Encode, Decode)]
+pub struct DebugInfo {
+    procedures: Vec<ProcedureDebugInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct ProcedureDebugInfo {
+    hash: u64,
+    name: String,
+    file: String,
+    lines: Vec<LineEntry>,
+}
+
+/// Maps an instruction offset inside a procedure section to a source location
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct LineEntry {
+    offset: u32,
+    line: u32,
+    column: u32,
+}
+
+impl DebugInfo {
+    pub fn new() -> Self {
+        Self {
+            procedures: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.procedures.is_empty();
+    }
+
+    pub fn push(&mut self, procedure: ProcedureDebugInfo) {
+        self.procedures.push(procedure);
+    }
+
+    pub fn extend(&mut self, other: DebugInfo) {
+        self.procedures.extend(other.procedures);
+    }
+
+    pub fn procedure(&self, hash: u64) -> Option<&ProcedureDebugInfo> {
+        return self
+            .procedures
+            .iter()
+            .find(|procedure| procedure.hash == hash);
+    }
+
+    pub fn procedures(&self) -> &[ProcedureDebugInfo] {
+        return &self.procedures;
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SinError> {
+        return Self::from_encoded_bytes(data).map_err(|_| SinError::InvalidSection);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        return self.to_encoded_bytes();
+    }
+}
+
+impl ProcedureDebugInfo {
+    pub fn new(hash: u64, name: String, file: String) -> Self {
+        Self {
+            hash,
+            name,
+            file,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Add a line entry, entries must be pushed in increasing offset order
+    pub fn push_line(&mut self, offset: u32, line: u32, column: u32) {
+        self.lines.push(LineEntry {
+            offset,
+            line,
+            column,
+        });
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_file(&mut self, file: String) {
+        self.file = file;
+    }
+
+    pub fn hash(&self) -> u64 {
+        return self.hash;
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn file(&self) -> &str {
+        return &self.file;
+    }
+
+    pub fn lines(&self) -> &[LineEntry] {
+        return &self.lines;
+    }
+
+    /// Returns the line entry covering the instruction at `offset`
+    pub fn location(&self, offset: u32) -> Option<&LineEntry> {
+        return self.lines.iter().rev().find(|line| line.offset <= offset);
+    }
+}
+
+impl LineEntry {
+    pub fn offset(&self) -> u32 {
+        return self.offset;
+    }
+
+    pub fn line(&self) -> u32 {
+        return self.line;
+    }
+
+    pub fn column(&self) -> u32 {
+        return self.column;
+    }
+}
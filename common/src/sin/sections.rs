@@ -1,4 +1,7 @@
-use crate::memory::buffer_reader::BufferReader;
+use crate::{
+    encoding::{Decode, Encode, EncodingError},
+    memory::{buffer_reader::BufferReader, buffer_writer::BufferWriter},
+};
 
 use super::SinError;
 
@@ -6,8 +9,12 @@ use super::SinError;
 pub enum SectionType {
     Procedure,
     Constant,
+    Debug,
+    /// Struct layouts, see `TypeInfo`
+    Types,
 }
 
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub struct SinSection {
     section_type: SectionType,
     hash: u64,
@@ -20,6 +27,8 @@ impl SectionType {
         match data {
             1 => return Ok(Self::Procedure),
             2 => return Ok(Self::Constant),
+            3 => return Ok(Self::Debug),
+            4 => return Ok(Self::Types),
             ty => return Err(SinError::InvalidSectionType(ty)),
         }
     }
@@ -28,6 +37,8 @@ impl SectionType {
         match self {
             Self::Procedure => return 1,
             Self::Constant => return 2,
+            Self::Debug => return 3,
+            Self::Types => return 4,
         }
     }
 }
@@ -43,21 +54,14 @@ impl SinSection {
     }
 
     pub fn from_reader(reader: &mut BufferReader) -> Result<Self, SinError> {
-        let decoded_ty = SectionType::from_byte(reader.read_u8().ok_or(SinError::InvalidSin)?)?;
-        let decoded_hash = reader.read_u64().ok_or(SinError::InvalidSin)?;
-        let decoded_start = reader.read_u64().ok_or(SinError::InvalidSin)?;
-        let decoded_end = reader.read_u64().ok_or(SinError::InvalidSin)?;
-
-        if decoded_start > decoded_end {
+        let section = Self::decode(reader).map_err(|e| match e {
+            EncodingError::InvalidTag(ty) => SinError::InvalidSectionType(ty),
+            _ => SinError::InvalidSin,
+        })?;
+        if section.start > section.end {
             return Err(SinError::InvalidSin);
         }
-
-        return Ok(Self {
-            section_type: decoded_ty,
-            hash: decoded_hash,
-            start: decoded_start,
-            end: decoded_end,
-        });
+        return Ok(section);
     }
 
     pub fn hash(&self) -> u64 {
@@ -77,11 +81,19 @@ impl SinSection {
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut buffer = Vec::new();
-        buffer.push(self.section_type.to_byte());
-        buffer.extend_from_slice(&self.hash.to_le_bytes());
-        buffer.extend_from_slice(&self.start.to_le_bytes());
-        buffer.extend_from_slice(&self.end.to_le_bytes());
-        return buffer;
+        return self.to_encoded_bytes();
+    }
+}
+
+impl Encode for SectionType {
+    fn encode(&self, writer: &mut BufferWriter) {
+        writer.write_u8(self.to_byte());
+    }
+}
+
+impl Decode for SectionType {
+    fn decode(reader: &mut BufferReader) -> Result<Self, EncodingError> {
+        let byte = u8::decode(reader)?;
+        return Self::from_byte(byte).map_err(|_| EncodingError::InvalidTag(byte));
     }
 }
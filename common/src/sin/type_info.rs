@@ -0,0 +1,134 @@
+use crate::encoding::{Decode, Encode};
+
+use super::SinError;
+
+/// Name used to derive the hash of the type section
+pub const TYPE_SECTION_NAME: &str = "$types";
+
+/// Layouts of the struct types used by a sin file
+///
+/// Layout:
+/// "{type_count: u32}{type...}"
+///
+/// Type:
+/// "{hash: u64}{size: u64}{alignment: u64}{field_count: u32}{field...}"
+///
+/// Field:
+/// "{hash: u64}{offset: u64}{type_hash: u64}"
+#[derive(Debug, Default, Clone, PartialEq, Encode, Decode)]
+pub struct TypeInfo {
+    types: Vec<TypeEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct TypeEntry {
+    hash: u64,
+    size: u64,
+    alignment: u64,
+    fields: Vec<FieldEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct FieldEntry {
+    hash: u64,
+    offset: u64,
+    type_hash: u64,
+}
+
+impl TypeInfo {
+    pub fn new() -> Self {
+        Self { types: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.types.is_empty();
+    }
+
+    pub fn push(&mut self, ty: TypeEntry) {
+        self.types.push(ty);
+    }
+
+    /// Add the types of `other`, a type with the hash of an existing one replaces it
+    pub fn extend(&mut self, other: TypeInfo) {
+        for ty in other.types {
+            match self.types.iter_mut().find(|e| e.hash == ty.hash) {
+                Some(existing) => *existing = ty,
+                None => self.types.push(ty),
+            }
+        }
+    }
+
+    pub fn get(&self, hash: u64) -> Option<&TypeEntry> {
+        return self.types.iter().find(|ty| ty.hash == hash);
+    }
+
+    pub fn types(&self) -> &[TypeEntry] {
+        return &self.types;
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SinError> {
+        return Self::from_encoded_bytes(data).map_err(|_| SinError::InvalidSection);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        return self.to_encoded_bytes();
+    }
+}
+
+impl TypeEntry {
+    pub fn new(hash: u64, size: u64, alignment: u64) -> Self {
+        Self {
+            hash,
+            size,
+            alignment,
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn push_field(&mut self, hash: u64, offset: u64, type_hash: u64) {
+        self.fields.push(FieldEntry {
+            hash,
+            offset,
+            type_hash,
+        });
+    }
+
+    pub fn set_layout(&mut self, size: u64, alignment: u64) {
+        self.size = size;
+        self.alignment = alignment;
+    }
+
+    pub fn hash(&self) -> u64 {
+        return self.hash;
+    }
+
+    pub fn size(&self) -> u64 {
+        return self.size;
+    }
+
+    pub fn alignment(&self) -> u64 {
+        return self.alignment;
+    }
+
+    pub fn fields(&self) -> &[FieldEntry] {
+        return &self.fields;
+    }
+
+    pub fn field(&self, hash: u64) -> Option<&FieldEntry> {
+        return self.fields.iter().find(|field| field.hash == hash);
+    }
+}
+
+impl FieldEntry {
+    pub fn hash(&self) -> u64 {
+        return self.hash;
+    }
+
+    pub fn offset(&self) -> u64 {
+        return self.offset;
+    }
+
+    pub fn type_hash(&self) -> u64 {
+        return self.type_hash;
+    }
+}
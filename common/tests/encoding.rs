@@ -0,0 +1,198 @@
+use common::{
+    encoding::{Decode, Encode, EncodingError},
+    isa::{
+        decoded::{Decoded, Value},
+        Form, Operand, INSTRUCTIONS,
+    },
+    memory::buffer_reader::BufferReader,
+    register::RegisterType,
+    sin::{
+        debug_info::{DebugInfo, ProcedureDebugInfo},
+        sections::{SectionType, SinSection},
+        type_info::{TypeEntry, TypeInfo},
+        Sin,
+    },
+};
+use proptest::prelude::*;
+
+fn general_register() -> impl Strategy<Value = RegisterType> {
+    return (1u8..=16).prop_map(|byte| RegisterType::from_byte(byte).unwrap());
+}
+
+fn register() -> impl Strategy<Value = RegisterType> {
+    return prop_oneof![general_register(), Just(RegisterType::Sp)];
+}
+
+fn section() -> impl Strategy<Value = SinSection> {
+    let section_type = prop_oneof![
+        Just(SectionType::Procedure),
+        Just(SectionType::Constant),
+        Just(SectionType::Debug),
+        Just(SectionType::Types),
+    ];
+    return (section_type, any::<u64>(), any::<u64>(), any::<u64>()).prop_map(
+        |(section_type, hash, a, b)| SinSection::new(section_type, hash, a.min(b), a.max(b)),
+    );
+}
+
+fn debug_info() -> impl Strategy<Value = DebugInfo> {
+    let line = (any::<u32>(), any::<u32>(), any::<u32>());
+    let procedure = (any::<u64>(), ".*", ".*", prop::collection::vec(line, 0..8)).prop_map(
+        |(hash, name, file, lines)| {
+            let mut procedure = ProcedureDebugInfo::new(hash, name, file);
+            for (offset, line, column) in lines {
+                procedure.push_line(offset, line, column);
+            }
+            procedure
+        },
+    );
+    return prop::collection::vec(procedure, 0..6).prop_map(|procedures| {
+        let mut debug_info = DebugInfo::new();
+        procedures
+            .into_iter()
+            .for_each(|procedure| debug_info.push(procedure));
+        debug_info
+    });
+}
+
+fn type_info() -> impl Strategy<Value = TypeInfo> {
+    let field = (any::<u64>(), any::<u64>(), any::<u64>());
+    let ty = (
+        any::<u64>(),
+        any::<u64>(),
+        any::<u64>(),
+        prop::collection::vec(field, 0..8),
+    )
+        .prop_map(|(hash, size, alignment, fields)| {
+            let mut ty = TypeEntry::new(hash, size, alignment);
+            for (hash, offset, type_hash) in fields {
+                ty.push_field(hash, offset, type_hash);
+            }
+            ty
+        });
+    return prop::collection::vec(ty, 0..6).prop_map(|types| {
+        let mut type_info = TypeInfo::new();
+        types.into_iter().for_each(|ty| type_info.push(ty));
+        type_info
+    });
+}
+
+/// Operands of a form in encoding order, immediates fit the register before them
+fn values(form: &'static Form) -> BoxedStrategy<Vec<Value>> {
+    let mut strategy = Just(Vec::new()).boxed();
+    for operand in form.operands() {
+        strategy = match operand {
+            Operand::Immediate => strategy
+                .prop_flat_map(|values: Vec<Value>| {
+                    let bits = match values.last() {
+                        Some(Value::Register(register)) => register.size().byte() * 8,
+                        _ => 64,
+                    };
+                    let max = u64::MAX >> (64 - bits);
+                    (Just(values), 0..=max)
+                })
+                .prop_map(|(mut values, value)| {
+                    values.push(Value::Immediate(value));
+                    values
+                })
+                .boxed(),
+            operand => {
+                let value = match operand {
+                    Operand::Register => general_register().prop_map(Value::Register).boxed(),
+                    Operand::Sp => Just(Value::Register(RegisterType::Sp)).boxed(),
+                    Operand::U16 => any::<u16>().prop_map(Value::U16).boxed(),
+                    Operand::U32 => any::<u32>().prop_map(Value::U32).boxed(),
+                    Operand::U64 | Operand::Hash => any::<u64>().prop_map(Value::U64).boxed(),
+                    Operand::Section => any::<u64>().prop_map(Value::Section).boxed(),
+                    Operand::Procedure => any::<u64>().prop_map(Value::Procedure).boxed(),
+                    Operand::Jump => (any::<u64>(), any::<u16>())
+                        .prop_map(|(section, offset)| Value::Jump(section, offset))
+                        .boxed(),
+                    Operand::JumpTable => {
+                        (any::<u64>(), prop::collection::vec(any::<u16>(), 0..16))
+                            .prop_map(|(section, table)| Value::JumpTable(section, table))
+                            .boxed()
+                    }
+                    Operand::Deref => register().prop_map(Value::Deref).boxed(),
+                    Operand::DerefOffset => (register(), any::<u32>(), any::<bool>())
+                        .prop_map(|(register, offset, add)| {
+                            Value::DerefOffset(register, offset, add)
+                        })
+                        .boxed(),
+                    Operand::Immediate => unreachable!(),
+                };
+                (strategy, value)
+                    .prop_map(|(mut values, value)| {
+                        values.push(value);
+                        values
+                    })
+                    .boxed()
+            }
+        };
+    }
+    return strategy;
+}
+
+fn instruction() -> impl Strategy<Value = Decoded> {
+    let forms = INSTRUCTIONS
+        .iter()
+        .flat_map(|instruction| instruction.forms())
+        .collect::<Vec<_>>();
+    return prop::sample::select(forms)
+        .prop_flat_map(|form| values(form).prop_map(move |values| Decoded::new(form, values)));
+}
+
+proptest! {
+    #[test]
+    fn sin_round_trip(
+        sections in prop::collection::vec(section(), 0..8),
+        data in prop::collection::vec(any::<u8>(), 0..256),
+    ) {
+        let bytes = Sin::new(sections.clone(), &data).to_bytes();
+        let sin = Sin::from_bytes(&bytes).unwrap();
+        prop_assert_eq!(sin.sections(), sections.as_slice());
+        prop_assert_eq!(sin.data(), data.as_slice());
+    }
+
+    #[test]
+    fn debug_info_round_trip(debug_info in debug_info()) {
+        prop_assert_eq!(DebugInfo::from_bytes(&debug_info.to_bytes()).unwrap(), debug_info);
+    }
+
+    #[test]
+    fn type_info_round_trip(type_info in type_info()) {
+        prop_assert_eq!(TypeInfo::from_bytes(&type_info.to_bytes()).unwrap(), type_info);
+    }
+
+    #[test]
+    fn instruction_round_trip(instruction in instruction()) {
+        let bytes = instruction.to_encoded_bytes();
+        prop_assert_eq!(bytes.len(), instruction.length());
+        prop_assert_eq!(Decoded::decode(&bytes).unwrap(), instruction);
+    }
+
+    #[test]
+    fn truncated_values(values in prop::collection::vec(any::<u64>(), 1..8), cut in 1usize..8) {
+        let bytes = values.to_encoded_bytes();
+        let cut = bytes.len() - cut.min(bytes.len() - 4);
+        prop_assert_eq!(
+            Vec::<u64>::from_encoded_bytes(&bytes[..cut]),
+            Err(EncodingError::UnexpectedEnd)
+        );
+    }
+}
+
+#[test]
+fn reader_position() {
+    let mut reader = BufferReader::new(&[1, 0, 0]);
+    assert_eq!(u32::decode(&mut reader), Err(EncodingError::UnexpectedEnd));
+    assert_eq!(reader.get_read_pos(), 0);
+    assert_eq!(u16::decode(&mut reader), Ok(1));
+    assert_eq!(reader.get_read_pos(), 2);
+    assert_eq!(bool::decode(&mut reader), Ok(false));
+    assert_eq!(
+        RegisterType::decode(&mut BufferReader::new(&[200])),
+        Err(EncodingError::InvalidTag(200))
+    );
+    assert!(Sin::from_bytes(&Sin::new(Vec::new(), &[]).to_bytes()[..15]).is_err());
+}
@@ -0,0 +1,674 @@
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use common::{
+    constants::*,
+    encoding::Encode,
+    isa::{
+        self,
+        decoded::{Decoded, Value},
+    },
+    register::RegisterType,
+    sin::{
+        sections::{SectionType, SinSection},
+        Sin,
+    },
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::executor::{Executor, LoadError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssemblerError {
+    /// An instruction or data was added before any section was started
+    NoSection,
+    DuplicateSection(String),
+    /// The section and the label
+    DuplicateLabel(String, String),
+    UndefinedLabel(String, String),
+    /// A label past the offsets a jump can encode
+    LabelOutOfRange(String, String),
+    /// An immediate that does not fit the register it is moved into
+    ImmediateTooLarge(RegisterType, u64),
+    /// An instruction longer than its u8 length byte allows
+    InstructionTooLong(String, usize),
+    /// The program doesn't fit in the executor it is loaded into
+    Load(LoadError),
+}
+
+impl Display for AssemblerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSection => write!(f, "Code was added before any section was started"),
+            Self::DuplicateSection(name) => write!(f, "Section `{}` is defined twice", name),
+            Self::DuplicateLabel(section, label) => {
+                write!(f, "Label `{}` is defined twice in `{}`", label, section)
+            }
+            Self::UndefinedLabel(section, label) => {
+                write!(f, "Label `{}` is not defined in `{}`", label, section)
+            }
+            Self::LabelOutOfRange(section, label) => write!(
+                f,
+                "Label `{}` in `{}` is too far from the start of the section",
+                label, section
+            ),
+            Self::ImmediateTooLarge(register, value) => {
+                write!(f, "{} does not fit in {}", value, register)
+            }
+            Self::InstructionTooLong(mnemonic, length) => {
+                write!(f, "`{}` is {} bytes long", mnemonic, length)
+            }
+            Self::Load(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for AssemblerError {}
+
+impl From<LoadError> for AssemblerError {
+    fn from(value: LoadError) -> Self {
+        return Self::Load(value);
+    }
+}
+
+#[derive(Debug)]
+struct LabelReplace {
+    label: String,
+    pos: usize,
+}
+
+#[derive(Debug)]
+struct SectionBuilder {
+    name: String,
+    section_type: SectionType,
+    data: Vec<u8>,
+    labels: HashMap<String, usize>,
+    label_replaces: Vec<LabelReplace>,
+}
+
+/// Builds sin sections from Rust, the counterpart of the text assembler for tests and code
+/// generators
+///
+/// Instructions go to the section started last. Labels belong to their section and can be
+/// referenced before they are defined. Errors are kept until `finish`.
+///
+/// # Examples
+///
+/// ```
+/// use common::register::RegisterType::{A64, B64};
+/// use craion::{assembler::Assembler, executor::Executor};
+///
+/// let mut assembler = Assembler::new();
+/// assembler
+///     .procedure("count")
+///     .mov_reg_num(A64, 0)
+///     .mov_reg_num(B64, 5)
+///     .label("loop")
+///     .inc(A64)
+///     .jacn(A64, B64, "loop")
+///     .ret();
+/// let mut executor = Executor::new(0xFFFF);
+/// assembler.load(&mut executor).unwrap();
+/// assert_eq!(executor.call("count", &[]).unwrap(), 5);
+/// ```
+#[derive(Debug, Default)]
+pub struct Assembler {
+    sections: Vec<SectionBuilder>,
+    error: Option<AssemblerError>,
+}
+
+/// The sections and data an `Assembler` produced
+#[derive(Debug)]
+pub struct Program {
+    sections: Vec<SinSection>,
+    data: Vec<u8>,
+}
+
+impl Program {
+    pub fn sections(&self) -> &[SinSection] {
+        return &self.sections;
+    }
+
+    pub fn data(&self) -> &[u8] {
+        return &self.data;
+    }
+
+    pub fn sin(&self) -> Sin<'_> {
+        return Sin::new(self.sections.clone(), &self.data);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        return self.sin().to_bytes();
+    }
+
+    pub fn load(&self, executor: &mut Executor) -> Result<(), LoadError> {
+        for section in self.sections.iter() {
+            executor.load_section(section, &self.data)?;
+        }
+        return Ok(());
+    }
+}
+
+/// Splits a signed offset into the magnitude and direction a dereference encodes
+fn deref_offset(register: RegisterType, offset: i32) -> Value {
+    return Value::DerefOffset(register, offset.unsigned_abs(), offset >= 0);
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn fail(&mut self, error: AssemblerError) {
+        self.error.get_or_insert(error);
+    }
+
+    fn start_section(&mut self, name: &str, section_type: SectionType) -> &mut Self {
+        if self.sections.iter().any(|section| section.name == name) {
+            self.fail(AssemblerError::DuplicateSection(name.to_string()));
+        }
+        self.sections.push(SectionBuilder {
+            name: name.to_string(),
+            section_type,
+            data: Vec::new(),
+            labels: HashMap::new(),
+            label_replaces: Vec::new(),
+        });
+        return self;
+    }
+
+    /// Start a procedure section, its hash is the hash of `name`
+    pub fn procedure(&mut self, name: &str) -> &mut Self {
+        return self.start_section(name, SectionType::Procedure);
+    }
+
+    /// Start a constant section, fill it with `bytes`
+    pub fn constant(&mut self, name: &str) -> &mut Self {
+        return self.start_section(name, SectionType::Constant);
+    }
+
+    /// Append raw bytes to the current section
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        match self.sections.last_mut() {
+            Some(section) => section.data.extend_from_slice(bytes),
+            None => self.fail(AssemblerError::NoSection),
+        }
+        return self;
+    }
+
+    /// Define a label at the current offset of the current section
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        let Some(section) = self.sections.last_mut() else {
+            self.fail(AssemblerError::NoSection);
+            return self;
+        };
+        if section
+            .labels
+            .insert(label.to_string(), section.data.len())
+            .is_some()
+        {
+            let error = AssemblerError::DuplicateLabel(section.name.clone(), label.to_string());
+            self.fail(error);
+        }
+        return self;
+    }
+
+    /// Append an instruction of the table, `values` are in encoding order and `labels` are
+    /// patched into the last two bytes of each jump operand
+    fn emit(
+        &mut self,
+        opcode: u16,
+        sub_opcode: Option<u8>,
+        values: Vec<Value>,
+        labels: &[&str],
+    ) -> &mut Self {
+        let (instruction, form) = isa::form(opcode, sub_opcode).expect("form is not in the table");
+        let bytes = Decoded::new(form, values).to_encoded_bytes();
+        if bytes.len() > u8::MAX as usize {
+            self.fail(AssemblerError::InstructionTooLong(
+                instruction.mnemonic().to_string(),
+                bytes.len(),
+            ));
+            return self;
+        }
+        let Some(section) = self.sections.last_mut() else {
+            self.fail(AssemblerError::NoSection);
+            return self;
+        };
+        section.data.extend_from_slice(&bytes);
+        let end = section.data.len();
+        for (i, label) in labels.iter().enumerate() {
+            section.label_replaces.push(LabelReplace {
+                label: label.to_string(),
+                pos: end - (labels.len() - i) * 2,
+            });
+        }
+        return self;
+    }
+
+    fn current_hash(&self) -> u64 {
+        return self
+            .sections
+            .last()
+            .map_or(0, |section| xxh3_64(section.name.as_bytes()));
+    }
+
+    fn jump(&mut self, opcode: u16, mut values: Vec<Value>, label: &str) -> &mut Self {
+        values.push(Value::Jump(self.current_hash(), 0));
+        return self.emit(opcode, None, values, &[label]);
+    }
+
+    pub fn mov_reg_reg(&mut self, dst: RegisterType, src: RegisterType) -> &mut Self {
+        let values = vec![Value::Register(dst), Value::Register(src)];
+        return self.emit(MOV_OPCODE, Some(MOV_REG2REG), values, &[]);
+    }
+
+    pub fn mov_reg_num(&mut self, dst: RegisterType, value: u64) -> &mut Self {
+        let bits = dst.size().byte() * 8;
+        if bits < 64 && value >> bits != 0 {
+            self.fail(AssemblerError::ImmediateTooLarge(dst, value));
+        }
+        let values = vec![Value::Register(dst), Value::Immediate(value)];
+        return self.emit(MOV_OPCODE, Some(MOV_NUM2REG), values, &[]);
+    }
+
+    /// `mov dst, [address]`
+    pub fn mov_reg_deref(&mut self, dst: RegisterType, address: RegisterType) -> &mut Self {
+        let values = vec![Value::Register(dst), Value::Deref(address)];
+        return self.emit(MOV_OPCODE, Some(MOV_DEREF_REG2REG), values, &[]);
+    }
+
+    /// `mov [address], src`
+    pub fn mov_deref_reg(&mut self, address: RegisterType, src: RegisterType) -> &mut Self {
+        let values = vec![Value::Register(src), Value::Deref(address)];
+        return self.emit(MOV_OPCODE, Some(MOV_REG2DEREF_REG), values, &[]);
+    }
+
+    /// `mov [address], value`, stores all 8 bytes of the value
+    pub fn mov_deref_num(&mut self, address: RegisterType, value: u64) -> &mut Self {
+        let values = vec![Value::Deref(address), Value::U64(value)];
+        return self.emit(MOV_OPCODE, Some(MOV_NUM2DEREF_REG), values, &[]);
+    }
+
+    pub fn mov_sp_num(&mut self, value: u64) -> &mut Self {
+        let values = vec![Value::Register(RegisterType::Sp), Value::U64(value)];
+        return self.emit(MOV_OPCODE, Some(MOV_ADD2SP), values, &[]);
+    }
+
+    pub fn mov_sp_reg(&mut self, src: RegisterType) -> &mut Self {
+        let values = vec![Value::Register(RegisterType::Sp), Value::Register(src)];
+        return self.emit(MOV_OPCODE, Some(MOV_REG2SP), values, &[]);
+    }
+
+    /// Move the address of a section into a register
+    pub fn mov_reg_section(&mut self, dst: RegisterType, section: &str) -> &mut Self {
+        let values = vec![
+            Value::Register(dst),
+            Value::Section(xxh3_64(section.as_bytes())),
+        ];
+        return self.emit(MOV_OPCODE, Some(MOV_SECTION_ADDR_2REG), values, &[]);
+    }
+
+    /// `mov dst, [base + offset]`
+    pub fn mov_reg_offset(
+        &mut self,
+        dst: RegisterType,
+        base: RegisterType,
+        offset: i32,
+    ) -> &mut Self {
+        let values = vec![Value::Register(dst), deref_offset(base, offset)];
+        return self.emit(MOV_OPCODE, Some(MOV_DEREF_REG_WITH_OFFSET2REG), values, &[]);
+    }
+
+    /// `mov [base + offset], src`
+    pub fn mov_offset_reg(
+        &mut self,
+        base: RegisterType,
+        offset: i32,
+        src: RegisterType,
+    ) -> &mut Self {
+        let values = vec![deref_offset(base, offset), Value::Register(src)];
+        return self.emit(MOV_OPCODE, Some(MOV_REG2DEREF_REG_WITH_OFFSET), values, &[]);
+    }
+
+    /// `mov [base + offset], value`
+    pub fn mov_offset_num(&mut self, base: RegisterType, offset: i32, value: u64) -> &mut Self {
+        let values = vec![deref_offset(base, offset), Value::U64(value)];
+        return self.emit(MOV_OPCODE, Some(MOV_NUM2DEREF_REG_WITH_OFFSET), values, &[]);
+    }
+
+    /// `mov [base + offset], section`
+    pub fn mov_offset_section(
+        &mut self,
+        base: RegisterType,
+        offset: i32,
+        section: &str,
+    ) -> &mut Self {
+        let values = vec![
+            deref_offset(base, offset),
+            Value::Section(xxh3_64(section.as_bytes())),
+        ];
+        return self.emit(
+            MOV_OPCODE,
+            Some(MOV_SECTION_ADDR2DEREF_REG_WITH_OFFSET),
+            values,
+            &[],
+        );
+    }
+
+    pub fn push(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(PUSH_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    pub fn pop(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(POP_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    pub fn enter(&mut self, size: u64) -> &mut Self {
+        return self.emit(ENTER_OPCODE, None, vec![Value::U64(size)], &[]);
+    }
+
+    pub fn leave(&mut self) -> &mut Self {
+        return self.emit(LEAVE_OPCODE, None, Vec::new(), &[]);
+    }
+
+    pub fn arg_num(&mut self, index: u32, value: u64) -> &mut Self {
+        let values = vec![Value::U32(index), Value::U64(value)];
+        return self.emit(ARG_OPCODE, Some(ARG_NUM), values, &[]);
+    }
+
+    pub fn arg_reg(&mut self, index: u32, register: RegisterType) -> &mut Self {
+        let values = vec![Value::U32(index), Value::Register(register)];
+        return self.emit(ARG_OPCODE, Some(ARG_REG), values, &[]);
+    }
+
+    pub fn larg(&mut self, register: RegisterType, index: u32) -> &mut Self {
+        let values = vec![Value::Register(register), Value::U32(index)];
+        return self.emit(LARG_OPCODE, None, values, &[]);
+    }
+
+    pub fn savr(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(SAVR_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    pub fn restr(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(RESTR_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    fn three_registers(
+        &mut self,
+        opcode: u16,
+        a: RegisterType,
+        b: RegisterType,
+        c: RegisterType,
+    ) -> &mut Self {
+        let values = vec![Value::Register(a), Value::Register(b), Value::Register(c)];
+        return self.emit(opcode, None, values, &[]);
+    }
+
+    pub fn mcpy(&mut self, dst: RegisterType, src: RegisterType, len: RegisterType) -> &mut Self {
+        return self.three_registers(MCPY_OPCODE, dst, src, len);
+    }
+
+    pub fn mset(&mut self, dst: RegisterType, value: RegisterType, len: RegisterType) -> &mut Self {
+        return self.three_registers(MSET_OPCODE, dst, value, len);
+    }
+
+    pub fn mcmp(
+        &mut self,
+        left: RegisterType,
+        right: RegisterType,
+        len: RegisterType,
+    ) -> &mut Self {
+        return self.three_registers(MCMP_OPCODE, left, right, len);
+    }
+
+    fn two_registers(&mut self, opcode: u16, a: RegisterType, b: RegisterType) -> &mut Self {
+        let values = vec![Value::Register(a), Value::Register(b)];
+        return self.emit(opcode, None, values, &[]);
+    }
+
+    pub fn inc(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(INC_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    pub fn cmp(&mut self, a: RegisterType, b: RegisterType) -> &mut Self {
+        return self.two_registers(CMP_OPCODE, a, b);
+    }
+
+    pub fn add_reg_reg(&mut self, dst: RegisterType, src: RegisterType) -> &mut Self {
+        let values = vec![Value::Register(dst), Value::Register(src)];
+        return self.emit(ADD_OPCODE, Some(ADD_REG_W_REG), values, &[]);
+    }
+
+    pub fn add_reg_num(&mut self, dst: RegisterType, value: u64) -> &mut Self {
+        let values = vec![Value::Register(dst), Value::U64(value)];
+        return self.emit(ADD_OPCODE, Some(ADD_REG_W_NUM), values, &[]);
+    }
+
+    pub fn add_sp_num(&mut self, value: u64) -> &mut Self {
+        let values = vec![Value::Register(RegisterType::Sp), Value::U64(value)];
+        return self.emit(ADD_OPCODE, Some(ADD_SP_W_NUM), values, &[]);
+    }
+
+    pub fn sub_reg_reg(&mut self, dst: RegisterType, src: RegisterType) -> &mut Self {
+        let values = vec![Value::Register(dst), Value::Register(src)];
+        return self.emit(SUB_OPCODE, Some(SUB_REG_W_REG), values, &[]);
+    }
+
+    pub fn sub_reg_num(&mut self, dst: RegisterType, value: u64) -> &mut Self {
+        let values = vec![Value::Register(dst), Value::U64(value)];
+        return self.emit(SUB_OPCODE, Some(SUB_REG_W_NUM), values, &[]);
+    }
+
+    pub fn sub_sp_num(&mut self, value: u64) -> &mut Self {
+        let values = vec![Value::Register(RegisterType::Sp), Value::U64(value)];
+        return self.emit(SUB_OPCODE, Some(SUB_SP_W_NUM), values, &[]);
+    }
+
+    pub fn mul(&mut self, dst: RegisterType, src: RegisterType) -> &mut Self {
+        return self.two_registers(MUL_OPCODE, dst, src);
+    }
+
+    pub fn div(&mut self, dst: RegisterType, src: RegisterType) -> &mut Self {
+        return self.two_registers(DIV_OPCODE, dst, src);
+    }
+
+    pub fn jmp(&mut self, label: &str) -> &mut Self {
+        return self.jump(JMP_OPCODE, Vec::new(), label);
+    }
+
+    pub fn jmz(&mut self, label: &str) -> &mut Self {
+        return self.jump(JMZ_OPCODE, Vec::new(), label);
+    }
+
+    pub fn jmn(&mut self, label: &str) -> &mut Self {
+        return self.jump(JMN_OPCODE, Vec::new(), label);
+    }
+
+    pub fn jme(&mut self, label: &str) -> &mut Self {
+        return self.jump(JME_OPCODE, Vec::new(), label);
+    }
+
+    pub fn jmc(&mut self, label: &str) -> &mut Self {
+        return self.jump(JMC_OPCODE, Vec::new(), label);
+    }
+
+    pub fn jacn(&mut self, a: RegisterType, b: RegisterType, label: &str) -> &mut Self {
+        return self.jump(
+            JACN_OPCODE,
+            vec![Value::Register(a), Value::Register(b)],
+            label,
+        );
+    }
+
+    pub fn jacz(&mut self, a: RegisterType, b: RegisterType, label: &str) -> &mut Self {
+        return self.jump(
+            JACZ_OPCODE,
+            vec![Value::Register(a), Value::Register(b)],
+            label,
+        );
+    }
+
+    pub fn jacc(&mut self, a: RegisterType, b: RegisterType, label: &str) -> &mut Self {
+        return self.jump(
+            JACC_OPCODE,
+            vec![Value::Register(a), Value::Register(b)],
+            label,
+        );
+    }
+
+    pub fn jace(&mut self, a: RegisterType, b: RegisterType, label: &str) -> &mut Self {
+        return self.jump(
+            JACE_OPCODE,
+            vec![Value::Register(a), Value::Register(b)],
+            label,
+        );
+    }
+
+    /// Jump to the label at `index` in `labels`, fall through when it is out of range
+    pub fn jtab(&mut self, index: RegisterType, labels: &[&str]) -> &mut Self {
+        let values = vec![
+            Value::Register(index),
+            Value::JumpTable(self.current_hash(), vec![0; labels.len()]),
+        ];
+        return self.emit(JTAB_OPCODE, None, values, labels);
+    }
+
+    pub fn jmpr(&mut self, target: RegisterType) -> &mut Self {
+        return self.emit(JMPR_OPCODE, None, vec![Value::Register(target)], &[]);
+    }
+
+    pub fn call(&mut self, procedure: &str) -> &mut Self {
+        let values = vec![Value::Procedure(xxh3_64(procedure.as_bytes()))];
+        return self.emit(CALL_OPCODE, None, values, &[]);
+    }
+
+    pub fn callr(&mut self, target: RegisterType) -> &mut Self {
+        return self.emit(CALLR_OPCODE, None, vec![Value::Register(target)], &[]);
+    }
+
+    pub fn ret(&mut self) -> &mut Self {
+        return self.emit(RET_OPCODE, None, Vec::new(), &[]);
+    }
+
+    pub fn spawn(&mut self, argument: RegisterType, procedure: &str) -> &mut Self {
+        let values = vec![
+            Value::Register(argument),
+            Value::Procedure(xxh3_64(procedure.as_bytes())),
+        ];
+        return self.emit(SPAWN_OPCODE, None, values, &[]);
+    }
+
+    pub fn yield_thread(&mut self) -> &mut Self {
+        return self.emit(YIELD_OPCODE, None, Vec::new(), &[]);
+    }
+
+    pub fn join(&mut self, dst: RegisterType, thread: RegisterType) -> &mut Self {
+        return self.two_registers(JOIN_OPCODE, dst, thread);
+    }
+
+    pub fn chan(&mut self, dst: RegisterType) -> &mut Self {
+        return self.emit(CHAN_OPCODE, None, vec![Value::Register(dst)], &[]);
+    }
+
+    pub fn send(&mut self, channel: RegisterType, value: RegisterType) -> &mut Self {
+        return self.two_registers(SEND_OPCODE, channel, value);
+    }
+
+    pub fn recv(&mut self, dst: RegisterType, channel: RegisterType) -> &mut Self {
+        return self.two_registers(RECV_OPCODE, dst, channel);
+    }
+
+    pub fn close(&mut self, channel: RegisterType) -> &mut Self {
+        return self.emit(CLOSE_OPCODE, None, vec![Value::Register(channel)], &[]);
+    }
+
+    pub fn local(&mut self, count: u16) -> &mut Self {
+        return self.emit(LOCAL_OPCODE, None, vec![Value::U16(count)], &[]);
+    }
+
+    pub fn itylal(&mut self, index: u16) -> &mut Self {
+        return self.emit(ITYLAL_OPCODE, None, vec![Value::U16(index)], &[]);
+    }
+
+    pub fn ityl(&mut self, index: u16, ty: &str) -> &mut Self {
+        let values = vec![Value::U16(index), Value::U64(xxh3_64(ty.as_bytes()))];
+        return self.emit(ITYL_OPCODE, None, values, &[]);
+    }
+
+    pub fn ftyll(&mut self, target: u16, field: &str, source: u16) -> &mut Self {
+        let values = vec![
+            Value::U16(target),
+            Value::U64(xxh3_64(field.as_bytes())),
+            Value::U16(source),
+        ];
+        return self.emit(FTYLL_OPCODE, None, values, &[]);
+    }
+
+    pub fn clty(&mut self, index: u16) -> &mut Self {
+        return self.emit(CLTY_OPCODE, None, vec![Value::U16(index)], &[]);
+    }
+
+    pub fn rl(&mut self, index: u16) -> &mut Self {
+        return self.emit(RL_OPCODE, None, vec![Value::U16(index)], &[]);
+    }
+
+    pub fn outc(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(OUTC_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    pub fn outs(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(OUTS_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    pub fn exit(&mut self, register: RegisterType) -> &mut Self {
+        return self.emit(EXIT_OPCODE, None, vec![Value::Register(register)], &[]);
+    }
+
+    pub fn halt(&mut self) -> &mut Self {
+        return self.emit(HALT_OPCODE, None, Vec::new(), &[]);
+    }
+
+    /// Resolve the labels and lay the sections out one after another
+    pub fn finish(&mut self) -> Result<Program, AssemblerError> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        let mut sections = Vec::new();
+        let mut data = Vec::new();
+        for section in self.sections.iter_mut() {
+            for label_replace in section.label_replaces.iter() {
+                let offset = *section.labels.get(&label_replace.label).ok_or(
+                    AssemblerError::UndefinedLabel(
+                        section.name.clone(),
+                        label_replace.label.clone(),
+                    ),
+                )?;
+                let offset = u16::try_from(offset).map_err(|_| {
+                    AssemblerError::LabelOutOfRange(
+                        section.name.clone(),
+                        label_replace.label.clone(),
+                    )
+                })?;
+                section.data[label_replace.pos..label_replace.pos + 2]
+                    .copy_from_slice(&offset.to_le_bytes());
+            }
+            let start = data.len() as u64;
+            data.extend_from_slice(&section.data);
+            sections.push(SinSection::new(
+                section.section_type,
+                xxh3_64(section.name.as_bytes()),
+                start,
+                data.len() as u64,
+            ));
+        }
+        return Ok(Program { sections, data });
+    }
+
+    /// Finish and load every section into `executor`
+    pub fn load(&mut self, executor: &mut Executor) -> Result<Program, AssemblerError> {
+        let program = self.finish()?;
+        program.load(executor)?;
+        return Ok(program);
+    }
+}
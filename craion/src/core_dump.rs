@@ -0,0 +1,351 @@
+use std::{error::Error, fmt::Display};
+
+use common::{
+    encoding::{Decode, Encode, EncodingError},
+    memory::{buffer_reader::BufferReader, buffer_writer::BufferWriter},
+    register::RegisterType,
+    sin::{debug_info::DebugInfo, sections::SectionType},
+};
+
+use crate::{
+    executor::{
+        backtrace::{Backtrace, Frame},
+        registers::flags::Flags,
+        ExecutionError, Executor,
+    },
+    layout::Layout,
+    memory::{address::Address, Memory, MemoryError},
+    section_manager::{LoadedSection, SectionManager},
+};
+
+/// Bytes a core file starts with
+pub const CORE_MAGIC: [u8; 4] = *b"rcor";
+pub const CORE_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoreDumpError {
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    Encoding(EncodingError),
+    /// A page (address, size) that doesn't fit in the memory of the core
+    InvalidPage(u64, usize),
+}
+
+impl Display for CoreDumpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "Not a core file"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported core file version: {}", version)
+            }
+            Self::Encoding(error) => write!(f, "Invalid core file: {}", error),
+            Self::InvalidPage(address, size) => write!(
+                f,
+                "Page at {:#x} of {} bytes lies outside of the core memory",
+                address, size
+            ),
+        }
+    }
+}
+
+impl Error for CoreDumpError {}
+
+impl From<EncodingError> for CoreDumpError {
+    fn from(value: EncodingError) -> Self {
+        return Self::Encoding(value);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+struct CoreLayout {
+    memory_size: u64,
+    code_base: u64,
+    constant_base: u64,
+    stack_top: u64,
+    stack_size: u64,
+    thread_stack_size: u64,
+    heap_start: u64,
+    heap_end: u64,
+}
+
+/// A section that was loaded when the core was dumped, `end` is the last byte of it
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct CoreSection {
+    section_type: SectionType,
+    hash: u64,
+    start: u64,
+    end: u64,
+    /// Replaced or unloaded while code still ran in it
+    retired: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+struct CorePage {
+    address: u64,
+    data: Vec<u8>,
+}
+
+/// The state of an executor at a fatal error, to be inspected after the program is gone
+///
+/// Only allocated memory pages are kept. Devices are not part of the dump, their ranges read
+/// as zero.
+///
+/// # Examples
+///
+/// ```
+/// use common::register::RegisterType::A64;
+/// use craion::{assembler::Assembler, core_dump::CoreDump, executor::Executor};
+///
+/// let mut executor = Executor::new(0xFFFF);
+/// Assembler::new()
+///     .procedure("main")
+///     .mov_reg_num(A64, 0xFFFFFF)
+///     .mov_deref_num(A64, 1)
+///     .load(&mut executor)
+///     .unwrap();
+/// let error = executor.call("main", &[]).unwrap_err();
+/// let core = CoreDump::from_bytes(&CoreDump::capture(&executor, &error).to_bytes()).unwrap();
+/// assert_eq!(core.general_registers()[0], 0xFFFFFF);
+/// assert_eq!(core.error(), error.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct CoreDump {
+    error: String,
+    ip: u64,
+    /// a, b, c and d
+    registers: Vec<u64>,
+    sp: u64,
+    flags: u16,
+    saved_registers: Vec<Vec<u64>>,
+    ret_stack: Vec<u64>,
+    layout: CoreLayout,
+    sections: Vec<CoreSection>,
+    pages: Vec<CorePage>,
+    debug_info: DebugInfo,
+}
+
+const GENERAL_REGISTERS: [RegisterType; 4] = [
+    RegisterType::A64,
+    RegisterType::B64,
+    RegisterType::C64,
+    RegisterType::D64,
+];
+
+impl CoreSection {
+    pub fn section_type(&self) -> SectionType {
+        return self.section_type;
+    }
+
+    pub fn hash(&self) -> u64 {
+        return self.hash;
+    }
+
+    pub fn start(&self) -> Address {
+        return Address::new(self.start as usize);
+    }
+
+    pub fn end(&self) -> Address {
+        return Address::new(self.end as usize);
+    }
+
+    pub fn retired(&self) -> bool {
+        return self.retired;
+    }
+}
+
+impl CoreDump {
+    /// Capture the state of `executor` after `error` stopped it
+    pub fn capture(executor: &Executor, error: &ExecutionError) -> Self {
+        let registers = executor.registers_ref();
+        let layout = executor.layout();
+        let section_manager = executor.section_manager_ref();
+        let mut sections = section_manager
+            .sections()
+            .map(|section| (section, false))
+            .chain(section_manager.retired().map(|section| (section, true)))
+            .map(|((hash, section), retired)| CoreSection {
+                section_type: section.section_type(),
+                hash,
+                start: section.mem_start().get_raw() as u64,
+                end: section.mem_end().get_raw() as u64,
+                retired,
+            })
+            .collect::<Vec<_>>();
+        sections.sort_by_key(|section| section.start);
+        return Self {
+            error: error.to_string(),
+            ip: error.ip().unwrap_or(registers.get_ip()).get_raw() as u64,
+            registers: GENERAL_REGISTERS
+                .iter()
+                .map(|register| registers.get_general(register).unwrap_or(0))
+                .collect(),
+            sp: registers.get_sp().get_raw() as u64,
+            flags: registers.get_flags().bits(),
+            saved_registers: GENERAL_REGISTERS
+                .iter()
+                .map(|register| registers.saved_registers(&register.group()).to_vec())
+                .collect(),
+            ret_stack: executor
+                .ret_stack()
+                .frames()
+                .map(|address| address.get_raw() as u64)
+                .collect(),
+            layout: CoreLayout {
+                memory_size: layout.memory_size() as u64,
+                code_base: layout.code_base().get_raw() as u64,
+                constant_base: layout.constant_base().get_raw() as u64,
+                stack_top: layout.stack_top().get_raw() as u64,
+                stack_size: layout.stack_size() as u64,
+                thread_stack_size: layout.thread_stack_size() as u64,
+                heap_start: layout.heap_start().get_raw() as u64,
+                heap_end: layout.heap_end().get_raw() as u64,
+            },
+            sections,
+            // The last page can go past the end of memory
+            pages: executor
+                .memory_ref()
+                .pages()
+                .into_iter()
+                .map(|(address, data)| CorePage {
+                    address: address.get_raw() as u64,
+                    data: data[..data.len().min(layout.memory_size() - address.get_raw())].to_vec(),
+                })
+                .collect(),
+            debug_info: executor.debug_info().clone(),
+        };
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = BufferWriter::new();
+        writer.write_bytes(&CORE_MAGIC);
+        writer.write_u16(CORE_VERSION);
+        self.encode(&mut writer);
+        return writer.into_bytes();
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CoreDumpError> {
+        let mut reader = BufferReader::new(bytes);
+        if reader.read_bytes(CORE_MAGIC.len()) != Some(CORE_MAGIC.as_slice()) {
+            return Err(CoreDumpError::InvalidMagic);
+        }
+        let version = u16::decode(&mut reader)?;
+        if version != CORE_VERSION {
+            return Err(CoreDumpError::UnsupportedVersion(version));
+        }
+        let core = Self::decode(&mut reader)?;
+        for page in core.pages.iter() {
+            if (page.address)
+                .checked_add(page.data.len() as u64)
+                .is_none_or(|end| end > core.layout.memory_size)
+            {
+                return Err(CoreDumpError::InvalidPage(page.address, page.data.len()));
+            }
+        }
+        return Ok(core);
+    }
+
+    /// The error that stopped the program
+    pub fn error(&self) -> &str {
+        return &self.error;
+    }
+
+    /// Address of the instruction that failed
+    pub fn ip(&self) -> Address {
+        return Address::new(self.ip as usize);
+    }
+
+    /// a64, b64, c64 and d64
+    pub fn general_registers(&self) -> &[u64] {
+        return &self.registers;
+    }
+
+    pub fn sp(&self) -> Address {
+        return Address::new(self.sp as usize);
+    }
+
+    pub fn flags(&self) -> Flags {
+        return Flags::from_bits_retain(self.flags);
+    }
+
+    /// Values `savr` pushed for a, b, c and d
+    pub fn saved_registers(&self) -> &[Vec<u64>] {
+        return &self.saved_registers;
+    }
+
+    /// Return addresses from the most recent call to the oldest
+    pub fn ret_stack(&self) -> impl Iterator<Item = Address> + '_ {
+        return self
+            .ret_stack
+            .iter()
+            .map(|address| Address::new(*address as usize));
+    }
+
+    pub fn layout(&self) -> Layout {
+        let layout = &self.layout;
+        return Layout::new(layout.memory_size as usize)
+            .with_code_base(Address::new(layout.code_base as usize))
+            .with_constant_base(Address::new(layout.constant_base as usize))
+            .with_stack_top(Address::new(layout.stack_top as usize))
+            .with_stack_size(layout.stack_size as usize)
+            .with_thread_stack_size(layout.thread_stack_size as usize)
+            .with_heap(
+                Address::new(layout.heap_start as usize),
+                Address::new(layout.heap_end as usize),
+            );
+    }
+
+    pub fn sections(&self) -> &[CoreSection] {
+        return &self.sections;
+    }
+
+    pub fn debug_info(&self) -> &DebugInfo {
+        return &self.debug_info;
+    }
+
+    /// Rebuild the guest memory
+    pub fn memory(&self) -> Memory {
+        let mut memory = Memory::new(self.layout.memory_size as usize);
+        for page in self.pages.iter() {
+            memory
+                .mem_sets(Address::new(page.address as usize), &page.data)
+                .expect("pages are checked to lie in memory when the core is read");
+        }
+        return memory;
+    }
+
+    /// Read `size` bytes of the guest memory at `address`
+    pub fn read(&self, address: Address, size: usize) -> Result<Vec<u8>, MemoryError> {
+        return Ok(self.memory().mem_gets(address, size)?.into_owned());
+    }
+
+    fn section_manager(&self) -> SectionManager {
+        let mut section_manager = SectionManager::new(self.layout());
+        let (retired, loaded): (Vec<_>, Vec<_>) =
+            self.sections.iter().partition(|section| section.retired);
+        for section in retired.into_iter().chain(loaded) {
+            section_manager.set_section_hash(
+                section.hash,
+                LoadedSection::new(section.section_type, section.start(), section.end()),
+            );
+            if section.retired {
+                section_manager.unload_section(section.hash);
+            }
+        }
+        return section_manager;
+    }
+
+    /// Resolve `address` to a section and a source location like a backtrace frame
+    pub fn frame(&self, address: Address) -> Frame {
+        return Frame::resolve(address, &self.section_manager(), &self.debug_info);
+    }
+
+    /// The call chain at the failed instruction
+    pub fn backtrace(&self) -> Backtrace {
+        return Backtrace::new(
+            self.ip(),
+            self.ret_stack(),
+            &self.section_manager(),
+            &self.debug_info,
+        );
+    }
+}
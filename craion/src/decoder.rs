@@ -1,10 +1,11 @@
 use std::{error::Error, fmt::Display};
 
+use common::isa::decoded::{DecodeError, Decoded};
 use instruction::Instruction;
 
 use crate::{
     executor::{registers::RegisterFile, ExecutorState},
-    memory::{address::Address, argument_memory::ArgumentMemory, Memory, MemoryError}, ret_stack::RetStack, section_manager::SectionManager,
+    memory::{address::Address, Memory, MemoryError}, ret_stack::RetStack, section_manager::SectionManager,
 };
 
 use self::argument::Argument;
@@ -16,7 +17,8 @@ pub mod argument;
 pub enum DecoderError {
     InvalidOpCode(u16),
     InvalidIp(Address),
-    InvalidIl(Address, usize)
+    InvalidIl(Address, usize),
+    InvalidOperands(DecodeError),
 }
 
 impl Display for DecoderError {
@@ -25,7 +27,8 @@ impl Display for DecoderError {
             DecoderError::InvalidOpCode(opcode) => write!(f, "Trying to decode invalid op code: {}", opcode),
             DecoderError::InvalidIp(ip) => write!(f, "Trying to get instruction length from invalid instruction pointer: {}", ip),
             DecoderError::InvalidIl(ip, il) => 
-                write!(f, "Trying to get instruction data from invalid instruction length: {}, with instruction pointer: {}", il, ip)
+                write!(f, "Trying to get instruction data from invalid instruction length: {}, with instruction pointer: {}", il, ip),
+            DecoderError::InvalidOperands(decode_e) => write!(f, "Trying to decode invalid operands: {}", decode_e),
         }
     }
 }
@@ -33,20 +36,20 @@ impl Display for DecoderError {
 impl Error for DecoderError {}
 
 
-pub fn decode<'a>(memory: &'a mut Memory, register: &'a mut RegisterFile, argument_memory: &'a mut ArgumentMemory, ret_stack: &'a mut RetStack, 
+pub fn decode<'a>(memory: &'a mut Memory, register: &'a mut RegisterFile, ret_stack: &'a mut RetStack, 
     section_manager: &'a mut SectionManager, executor_state: &'a mut ExecutorState) -> Result<Instruction<'a>, DecoderError> {
-    let instruction_length = match memory.mem_get(register.get_ip()) {
-        Ok(il) => il as usize,
+    let instruction_length = match memory.mem_gets(register.get_ip(), 1) {
+        Ok(il) => il[0] as usize,
         Err(err) => match err {
             MemoryError::InvalidAddr(address) => return Err(DecoderError::InvalidIp(address)),
-            MemoryError::OutOfRange(address, _) => return Err(DecoderError::InvalidIp(address))
+            MemoryError::OutOfRange(address, _) | MemoryError::DeviceOverlap(address, _) | MemoryError::InvalidDevice(address, _) => return Err(DecoderError::InvalidIp(address))
         },
     };
     let instruction = match memory.mem_gets(register.get_ip(), instruction_length) {
         Ok(is) => is,
         Err(err) => match err {
             MemoryError::InvalidAddr(address) => return Err(DecoderError::InvalidIl(address, instruction_length)),
-            MemoryError::OutOfRange(address, _) => 
+            MemoryError::OutOfRange(address, _) | MemoryError::DeviceOverlap(address, _) | MemoryError::InvalidDevice(address, _) => 
                 return Err(DecoderError::InvalidIl(address, instruction_length))
         },
     };
@@ -54,7 +57,10 @@ pub fn decode<'a>(memory: &'a mut Memory, register: &'a mut RegisterFile, argume
         return Err(DecoderError::InvalidIl(register.get_ip(), instruction_length)); 
     }
     let opcode = u16::from_le_bytes([instruction[1], instruction[2]]);
-    let argument = &instruction[3..instruction_length];
-    argument_memory.set_arguement(argument);
-    return Ok(Instruction::decode(opcode, register, memory, Argument::new(argument_memory.get_argument()), ret_stack, section_manager, executor_state,instruction_length)?);
+    let decoded = match Decoded::decode(&instruction) {
+        Ok(decoded) => decoded,
+        Err(DecodeError::UnknownOpCode(opcode)) => return Err(DecoderError::InvalidOpCode(opcode)),
+        Err(err) => return Err(DecoderError::InvalidOperands(err))
+    };
+    return Ok(Instruction::decode(opcode, register, memory, Argument::new(decoded), ret_stack, section_manager, executor_state,instruction_length)?);
 }
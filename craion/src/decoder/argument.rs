@@ -4,8 +4,8 @@ use std::{
 };
 
 use common::{
-    memory::buffer_reader::BufferReader,
-    register::{RegisterParseError, RegisterType},
+    isa::decoded::{Decoded, Value},
+    register::RegisterType,
 };
 
 use crate::memory::address::Address;
@@ -13,87 +13,154 @@ use crate::memory::address::Address;
 #[derive(Debug)]
 pub enum ArgumentParseError {
     OutOfRange(usize),
-    RegisterParseError(RegisterParseError),
+    /// The operand at the index is not of the kind that was asked for
+    Mismatch(usize),
 }
 
 impl Display for ArgumentParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::OutOfRange(readpos) => {
-                write!(f, "Not enough argument. with readpos: {}", readpos)
+            Self::OutOfRange(index) => {
+                write!(f, "Not enough argument. with index: {}", index)
+            }
+            Self::Mismatch(index) => {
+                write!(f, "Argument {} is not of the expected kind", index)
             }
-            Self::RegisterParseError(parse_error) => write!(f, "{}", parse_error),
         }
     }
 }
 
-impl From<RegisterParseError> for ArgumentParseError {
-    fn from(value: RegisterParseError) -> Self {
-        Self::RegisterParseError(value)
-    }
-}
-
 impl Error for ArgumentParseError {}
 
+/// The operands of an instruction, decoded from the form in the isa table and read in
+/// encoding order
 #[derive(Debug)]
-pub struct Argument<'a> {
-    reader: BufferReader<'a>,
+pub struct Argument {
+    decoded: Decoded,
+    index: usize,
 }
 
-impl<'a> Argument<'a> {
-    pub fn new(data: &'a [u8]) -> Self {
-        Self {
-            reader: BufferReader::new(data),
-        }
+impl Argument {
+    pub fn new(decoded: Decoded) -> Self {
+        Self { decoded, index: 0 }
+    }
+
+    /// Number of operands that were read
+    pub fn read_count(&self) -> usize {
+        return self.index;
+    }
+
+    pub fn decoded(&self) -> &Decoded {
+        return &self.decoded;
+    }
+
+    /// Read the next operand with `read`, which returns `None` when it has another kind
+    fn next<T>(&mut self, read: impl FnOnce(&Value) -> Option<T>) -> Result<T, ArgumentParseError> {
+        let value = self
+            .decoded
+            .values()
+            .get(self.index)
+            .ok_or(ArgumentParseError::OutOfRange(self.index))?;
+        let value = read(value).ok_or(ArgumentParseError::Mismatch(self.index))?;
+        self.index += 1;
+        return Ok(value);
+    }
+
+    pub fn parse_sub_opcode(&self) -> Result<u8, ArgumentParseError> {
+        return self
+            .decoded
+            .form()
+            .sub_opcode()
+            .ok_or(ArgumentParseError::Mismatch(self.index));
     }
 
     pub fn parse_register(&mut self) -> Result<RegisterType, ArgumentParseError> {
-        let byte_read = match self.reader.read_bytes(1) {
-            Some(byte) => byte,
-            None => return Err(ArgumentParseError::OutOfRange(self.reader.get_read_pos())),
-        };
-        return Ok(RegisterType::from_byte(byte_read[0])?);
+        return self.next(|value| match *value {
+            Value::Register(register) => Some(register),
+            _ => None,
+        });
+    }
+
+    pub fn parse_deref(&mut self) -> Result<RegisterType, ArgumentParseError> {
+        return self.next(|value| match *value {
+            Value::Deref(register) => Some(register),
+            _ => None,
+        });
+    }
+
+    /// The register, the offset and whether it is added
+    pub fn parse_deref_offset(&mut self) -> Result<(RegisterType, u32, bool), ArgumentParseError> {
+        return self.next(|value| match *value {
+            Value::DerefOffset(register, offset, add) => Some((register, offset, add)),
+            _ => None,
+        });
     }
 
     pub fn parse_u64(&mut self) -> Result<u64, ArgumentParseError> {
-        let number_read = match self.reader.read_u64() {
-            Some(number) => number,
-            None => return Err(ArgumentParseError::OutOfRange(self.reader.get_read_pos())),
-        };
-        return Ok(number_read);
+        return self.next(|value| match *value {
+            Value::U64(value) => Some(value),
+            _ => None,
+        });
     }
 
     pub fn parse_u16(&mut self) -> Result<u16, ArgumentParseError> {
-        let number_read = match self.reader.read_u16() {
-            Some(number) => number,
-            None => return Err(ArgumentParseError::OutOfRange(self.reader.get_read_pos())),
-        };
-        return Ok(number_read);
+        return self.next(|value| match *value {
+            Value::U16(value) => Some(value),
+            _ => None,
+        });
     }
+
     pub fn parse_u32(&mut self) -> Result<u32, ArgumentParseError> {
-        let number_read = match self.reader.read_u32() {
-            Some(number) => number,
-            None => return Err(ArgumentParseError::OutOfRange(self.reader.get_read_pos())),
-        };
-        return Ok(number_read);
+        return self.next(|value| match *value {
+            Value::U32(value) => Some(value),
+            _ => None,
+        });
     }
-    pub fn parse_u8(&mut self) -> Result<u8, ArgumentParseError> {
-        let number_read = self
-            .reader
-            .read_u8()
-            .ok_or(ArgumentParseError::OutOfRange(self.reader.get_read_pos()))?;
-        return Ok(number_read);
+
+    /// An integer sized to the register before it
+    pub fn parse_immediate(&mut self) -> Result<u64, ArgumentParseError> {
+        return self.next(|value| match *value {
+            Value::Immediate(value) => Some(value),
+            _ => None,
+        });
     }
 
-    pub fn parse_boolean(&mut self) -> Result<bool, ArgumentParseError> {
-        return Ok(self.parse_u8()? == 1);
+    pub fn parse_section(&mut self) -> Result<u64, ArgumentParseError> {
+        return self.next(|value| match *value {
+            Value::Section(hash) => Some(hash),
+            _ => None,
+        });
+    }
+
+    pub fn parse_procedure(&mut self) -> Result<u64, ArgumentParseError> {
+        return self.next(|value| match *value {
+            Value::Procedure(hash) => Some(hash),
+            _ => None,
+        });
+    }
+
+    /// The section hash and the offset into it
+    pub fn parse_jump(&mut self) -> Result<(u64, u16), ArgumentParseError> {
+        return self.next(|value| match *value {
+            Value::Jump(section, offset) => Some((section, offset)),
+            _ => None,
+        });
+    }
+
+    /// The section hash and the offset of each entry
+    pub fn parse_jump_table(&mut self) -> Result<(u64, &[u16]), ArgumentParseError> {
+        let index = self.index;
+        let Some(Value::JumpTable(section, table)) = self.decoded.values().get(index) else {
+            return Err(match self.decoded.values().get(index) {
+                Some(_) => ArgumentParseError::Mismatch(index),
+                None => ArgumentParseError::OutOfRange(index),
+            });
+        };
+        self.index += 1;
+        return Ok((*section, table));
     }
 
     pub fn parse_address(&mut self) -> Result<Address, ArgumentParseError> {
-        let number_read = self
-            .reader
-            .read_u64()
-            .ok_or(ArgumentParseError::OutOfRange(self.reader.get_read_pos()))?;
-        return Ok(Address::new(number_read as usize));
+        return Ok(Address::new(self.parse_u64()? as usize));
     }
 }
@@ -1,14 +1,16 @@
 use std::{error::Error, fmt::Display};
 
 use common::{inline_if, register::RegisterType};
-use proc::collect_instruction;
 
 use crate::{
     executor::{
+        channel::ChannelError,
+        local::TypeError,
         registers::{RegisterFile, RegisterFileError},
+        thread::ThreadError,
         ExecutorState,
     },
-    memory::{Memory, MemoryError},
+    memory::{address::Address, Memory, MemoryError},
     ret_stack::RetStack,
     section_manager::{LoadedSection, SectionManager},
 };
@@ -20,26 +22,34 @@ use super::{
 
 macro_rules! parse_and_jump {
     ($args:expr) => {
-        let section_hash = $args.argument.parse_u64()?;
+        let (section_hash, offset) = $args.argument.parse_jump()?;
         let current_section = $args
             .section_manager
-            .get_section_hash(section_hash)
+            .jump_section($args.register.get_ip(), section_hash)
             .ok_or(super::InstructionError::InvalidSection(section_hash))?;
         $args
             .register
-            .set_ip(current_section.mem_start() + $args.argument.parse_u16()?.into());
+            .set_ip(current_section.mem_start() + offset.into());
     };
 }
 
 mod add;
 mod arg;
 mod call;
+mod callr;
+mod chan;
+mod close;
+mod clty;
 mod cmp;
 mod div;
 mod enter;
 mod exit;
+mod ftyll;
 mod halt;
 mod inc;
+mod join;
+mod ityl;
+mod itylal;
 mod jacc;
 mod jace;
 mod jacn;
@@ -48,18 +58,30 @@ mod jmc;
 mod jme;
 mod jmn;
 mod jmp;
+mod jmpr;
 mod jmz;
+mod jtab;
 mod larg;
 mod leave;
+mod local;
+mod mcmp;
+mod mcpy;
 mod mov;
+mod mset;
 mod mul;
 mod outc;
+mod outs;
 mod pop;
 mod push;
+mod recv;
 mod restr;
 mod ret;
+mod rl;
 mod savr;
+mod send;
+mod spawn;
 mod sub;
+mod r#yield;
 
 #[derive(Debug)]
 pub enum InstructionError {
@@ -67,12 +89,21 @@ pub enum InstructionError {
     RegisterFileError(RegisterFileError),
     AccessingMemoryError(MemoryError),
     InvalidUTF8,
+    DivisionByZero,
     AddressToRegisterError(usize),
     InvalidSubOpCode(u16, u8),
     InvalidSection(u64),
     EmptyRetStack,
     NotProcedureSection,
+    /// A register call to an address that is not the start of a procedure
+    InvalidCallTarget(Address),
+    /// A register jump to an address outside of every procedure
+    InvalidJumpTarget(Address),
     SavedNonGeneral,
+    UndefinedArgument(u32),
+    ThreadError(ThreadError),
+    ChannelError(ChannelError),
+    TypeError(TypeError),
 }
 
 impl Display for InstructionError {
@@ -90,6 +121,7 @@ impl Display for InstructionError {
             Self::InvalidUTF8 => {
                 write!(f, "Invalid UTF8")
             }
+            Self::DivisionByZero => write!(f, "Trying to divide by zero"),
             Self::InvalidSubOpCode(mainopcode, subopcode) => write!(
                 f,
                 "Trying to execute invalid sup op code. Main OP Code {}, Sub OP Code: {}",
@@ -103,7 +135,13 @@ impl Display for InstructionError {
             Self::InvalidSection(hash) => write!(f, "Trying to access invalid section with hash: {}", hash),
             Self::EmptyRetStack => write!(f, "Executing return insturction on an empty return stack"),
             Self::NotProcedureSection => write!(f, "Trying to call a section thats not a procedure"),
-            Self::SavedNonGeneral => write!(f, "Cannot save a register that is not general purpose")
+            Self::InvalidCallTarget(address) => write!(f, "Trying to call {} which is not the start of a procedure", address),
+            Self::InvalidJumpTarget(address) => write!(f, "Trying to jump to {} which is not inside a procedure", address),
+            Self::SavedNonGeneral => write!(f, "Cannot save a register that is not general purpose"),
+            Self::UndefinedArgument(index) => write!(f, "Trying to load argument {} which was not passed to the current call", index),
+            Self::ThreadError(thread_e) => write!(f, "{}", thread_e),
+            Self::ChannelError(channel_e) => write!(f, "{}", channel_e),
+            Self::TypeError(type_e) => write!(f, "{}", type_e),
         }
     }
 }
@@ -116,6 +154,24 @@ impl From<RegisterFileError> for InstructionError {
     }
 }
 
+impl From<ThreadError> for InstructionError {
+    fn from(value: ThreadError) -> Self {
+        Self::ThreadError(value)
+    }
+}
+
+impl From<ChannelError> for InstructionError {
+    fn from(value: ChannelError) -> Self {
+        Self::ChannelError(value)
+    }
+}
+
+impl From<TypeError> for InstructionError {
+    fn from(value: TypeError) -> Self {
+        Self::TypeError(value)
+    }
+}
+
 impl From<MemoryError> for InstructionError {
     fn from(value: MemoryError) -> Self {
         Self::AccessingMemoryError(value)
@@ -132,7 +188,7 @@ impl From<ArgumentParseError> for InstructionError {
 pub struct InstructionArgument<'a> {
     pub register: &'a mut RegisterFile,
     pub memory: &'a mut Memory,
-    pub argument: Argument<'a>,
+    pub argument: Argument,
     pub ret_stack: &'a mut RetStack,
     pub section_manager: &'a mut SectionManager,
     pub instruction_length: usize,
@@ -151,22 +207,30 @@ impl<'a> Instruction<'a> {
         op_code: u16,
         register: &'a mut RegisterFile,
         memory: &'a mut Memory,
-        argument: Argument<'a>,
+        argument: Argument,
         ret_stack: &'a mut RetStack,
         section_manager: &'a mut SectionManager,
         executor_state: &'a mut ExecutorState,
         instruction_length: usize,
     ) -> Result<Self, DecoderError> {
-        collect_instruction!(
-            op_code,
-            register,
-            memory,
-            argument,
-            instruction_length,
-            ret_stack,
-            section_manager,
-            executor_state
-        );
+        let instruction_executor: fn(&mut InstructionArgument) -> Result<(), InstructionError> =
+            common::instruction_handler!(
+                op_code,
+                other => return Err(DecoderError::InvalidOpCode(other))
+            );
+        return Ok(Self {
+            instruction_executor,
+            instruction_argument: InstructionArgument {
+                register,
+                memory,
+                argument,
+                ret_stack,
+                section_manager,
+                instruction_length,
+                executor_state,
+            },
+            opcode: op_code,
+        });
     }
 
     pub fn execute(&mut self) -> Result<(), InstructionError> {
@@ -184,9 +248,8 @@ impl<'a> InstructionArgument<'a> {
         &mut self,
         value: impl FnOnce(&mut InstructionArgument) -> Result<([u8; T], usize), InstructionError>,
     ) -> Result<(), InstructionError> {
-        let reg = self.argument.parse_register()?;
-        let offset = self.argument.parse_u32()? as usize;
-        let is_add = self.argument.parse_boolean()?;
+        let (reg, offset, is_add) = self.argument.parse_deref_offset()?;
+        let offset = offset as usize;
         let (value, size) = value(self)?;
         let address = match reg {
             RegisterType::Sp => self.register.get_sp(),
@@ -201,9 +264,8 @@ impl<'a> InstructionArgument<'a> {
 
     /// Parse the argument assuming it a dereference to a value with `size` input
     pub fn deref_offset_get<const T: usize>(&mut self) -> Result<[u8; T], InstructionError> {
-        let reg = self.argument.parse_register()?;
-        let offset = self.argument.parse_u32()? as usize;
-        let is_add = self.argument.parse_boolean()?;
+        let (reg, offset, is_add) = self.argument.parse_deref_offset()?;
+        let offset = offset as usize;
         let address = match reg {
             RegisterType::Sp => self.register.get_sp(),
             _ => self.register.get_general(&reg)?.into(),
@@ -211,13 +273,13 @@ impl<'a> InstructionArgument<'a> {
 
         return Ok(self
             .memory
-            .mem_gets(inline_if!(is_add, address + offset, address - offset), T)?
+            .mem_reads(inline_if!(is_add, address + offset, address - offset), T)?
             .try_into()
             .unwrap_or([0; T]));
     }
 
     pub fn parse_section(&mut self) -> Result<&LoadedSection, InstructionError> {
-        let section_hash = self.argument.parse_u64()?;
+        let section_hash = self.argument.parse_section()?;
         return Ok(self
             .section_manager
             .get_section_hash(section_hash)
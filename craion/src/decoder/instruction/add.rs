@@ -1,15 +1,13 @@
 use common::constants::{ADD_OPCODE, ADD_REG_W_NUM, ADD_REG_W_REG, ADD_SP_W_NUM};
-use proc::instruction;
 
 use crate::memory::address::Address;
 
 use super::InstructionArgument;
 
-#[instruction(ADD_OPCODE, "crate::decoder::instruction::add::add")]
 pub fn add(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     args.register.inc_ip(args.instruction_length);
 
-    match args.argument.parse_u8()? {
+    match args.argument.parse_sub_opcode()? {
         ADD_REG_W_REG => {
             let reg1 = args.argument.parse_register()?;
             let reg2 = args.argument.parse_register()?;
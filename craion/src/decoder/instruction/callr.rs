@@ -0,0 +1,28 @@
+use common::sin::sections::SectionType;
+
+use crate::memory::address::Address;
+
+use super::{InstructionArgument, InstructionError};
+
+/// Call the procedure whose start address is in a register, like one loaded with
+/// `mov reg, section`
+pub fn callr(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    let reg = args.argument.parse_register()?;
+    let target = Address::new(args.register.get_general(&reg)? as usize);
+    args.register.inc_ip(args.instruction_length);
+    let (_, section) = args
+        .section_manager
+        .find_section(target)
+        .ok_or(InstructionError::InvalidCallTarget(target))?;
+
+    if section.section_type() != SectionType::Procedure {
+        return Err(InstructionError::NotProcedureSection);
+    }
+    if section.mem_start() != target {
+        return Err(InstructionError::InvalidCallTarget(target));
+    }
+    args.ret_stack.push(args.register.get_ip());
+    args.executor_state.push_argument_frame();
+    args.register.set_ip(target);
+    return Ok(());
+}
@@ -0,0 +1,9 @@
+use super::InstructionArgument;
+
+pub fn chan(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let reg = args.argument.parse_register()?;
+    let id = args.executor_state.channels().create();
+    args.register.set_general(&reg, id)?;
+    return Ok(());
+}
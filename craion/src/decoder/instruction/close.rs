@@ -0,0 +1,10 @@
+use super::InstructionArgument;
+
+pub fn close(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let id = args
+        .register
+        .get_general(&args.argument.parse_register()?)?;
+    args.executor_state.channels().get(id)?.close();
+    return Ok(());
+}
@@ -0,0 +1,8 @@
+use super::InstructionArgument;
+
+pub fn clty(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let index = args.argument.parse_u16()?;
+    args.executor_state.locals()?.clear(index)?;
+    return Ok(());
+}
@@ -0,0 +1,10 @@
+use super::InstructionArgument;
+
+pub fn ftyll(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let target = args.argument.parse_u16()?;
+    let field = args.argument.parse_u64()?;
+    let source = args.argument.parse_u16()?;
+    args.executor_state.set_local_field(target, field, source)?;
+    return Ok(());
+}
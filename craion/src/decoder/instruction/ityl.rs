@@ -0,0 +1,13 @@
+use crate::executor::local::Local;
+
+use super::InstructionArgument;
+
+pub fn ityl(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let index = args.argument.parse_u16()?;
+    let ty = args.argument.parse_u64()?;
+    args.executor_state
+        .locals()?
+        .initialize(index, Local::new(ty))?;
+    return Ok(());
+}
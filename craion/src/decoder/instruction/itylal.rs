@@ -0,0 +1,17 @@
+use crate::executor::local::Local;
+
+use super::{InstructionArgument, InstructionError};
+
+/// Load the argument at `index` into the local at `index`, arguments are u64
+pub fn itylal(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let index = args.argument.parse_u16()?;
+    let value = args
+        .executor_state
+        .get_argument(index.into())
+        .ok_or(InstructionError::UndefinedArgument(index.into()))?;
+    args.executor_state
+        .locals()?
+        .initialize(index, Local::scalar(value))?;
+    return Ok(());
+}
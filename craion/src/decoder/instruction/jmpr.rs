@@ -0,0 +1,18 @@
+use common::sin::sections::SectionType;
+
+use crate::memory::address::Address;
+
+use super::{InstructionArgument, InstructionError};
+
+/// Jump to the address in a register, it has to be inside a procedure
+pub fn jmpr(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    let reg = args.argument.parse_register()?;
+    let target = Address::new(args.register.get_general(&reg)? as usize);
+    match args.section_manager.find_section(target) {
+        Some((_, section)) if section.section_type() == SectionType::Procedure => {
+            args.register.set_ip(target);
+        }
+        _ => return Err(InstructionError::InvalidJumpTarget(target)),
+    }
+    return Ok(());
+}
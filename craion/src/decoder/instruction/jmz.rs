@@ -1,8 +1,5 @@
-use proc::instruction;
-
 use super::InstructionArgument;
 
-#[instruction(JMZ_OPCODE, "crate::decoder::instruction::jmz::jmz")]
 pub fn jmz(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     if args.register.get_zero() {
         parse_and_jump!(args);
@@ -0,0 +1,23 @@
+use crate::executor::thread::ThreadRequest;
+
+use super::InstructionArgument;
+
+/// Wait for a thread and put its return value in a register, the instruction runs again once
+/// the thread finishes
+pub fn join(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    let reg = args.argument.parse_register()?;
+    let id = args
+        .register
+        .get_general(&args.argument.parse_register()?)?;
+    match args.executor_state.threads().join(id)? {
+        Some(value) => {
+            args.register.set_general(&reg, value)?;
+            args.register.inc_ip(args.instruction_length);
+        }
+        None => args
+            .executor_state
+            .threads()
+            .request(ThreadRequest::Join(id)),
+    }
+    return Ok(());
+}
@@ -0,0 +1,23 @@
+use super::{InstructionArgument, InstructionError};
+
+/// Jump to the entry of the table picked by a register, the table is a section hash, a u16
+/// entry count and a u16 offset into that section per entry. An index past the end of the
+/// table falls through to the next instruction
+pub fn jtab(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    let reg = args.argument.parse_register()?;
+    let index = args.register.get_general(&reg)?;
+    let (section_hash, table) = args.argument.parse_jump_table()?;
+    let Some(&offset) = usize::try_from(index)
+        .ok()
+        .and_then(|index| table.get(index))
+    else {
+        args.register.inc_ip(args.instruction_length);
+        return Ok(());
+    };
+    let section = args
+        .section_manager
+        .jump_section(args.register.get_ip(), section_hash)
+        .ok_or(InstructionError::InvalidSection(section_hash))?;
+    args.register.set_ip(section.mem_start() + offset.into());
+    return Ok(());
+}
@@ -1,13 +1,13 @@
-use proc::instruction;
+use super::{InstructionArgument, InstructionError};
 
-use super::InstructionArgument;
-
-#[instruction(LARG_OPCODE, "crate::decoder::instruction::larg::larg")]
 pub fn larg(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     args.register.inc_ip(args.instruction_length);
     let reg = args.argument.parse_register()?;
     let index = args.argument.parse_u32()?;
-    args.register
-        .set_general(&reg, args.executor_state.get_argument(index))?;
+    let value = args
+        .executor_state
+        .get_argument(index)
+        .ok_or(InstructionError::UndefinedArgument(index))?;
+    args.register.set_general(&reg, value)?;
     return Ok(());
 }
@@ -0,0 +1,9 @@
+use super::InstructionArgument;
+
+/// Set up the untyped local variables of the current procedure
+pub fn local(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let size = args.argument.parse_u16()?;
+    args.executor_state.init_locals(size);
+    return Ok(());
+}
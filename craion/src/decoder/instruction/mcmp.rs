@@ -0,0 +1,22 @@
+use std::cmp::Ordering;
+
+use crate::memory::address::Address;
+
+use super::InstructionArgument;
+
+/// Compare two ranges of as many bytes as the third register holds, the flags are set like
+/// `cmp` of the first pair of bytes that differ
+pub fn mcmp(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let left = args.argument.parse_register()?;
+    let right = args.argument.parse_register()?;
+    let len = args.argument.parse_register()?;
+    let left = Address::new(args.register.get_general(&left)? as usize);
+    let right = Address::new(args.register.get_general(&right)? as usize);
+    let len = args.register.get_general(&len)? as usize;
+    let ordering = args.memory.mem_compare(left, right, len)?;
+    args.register.set_carry(ordering == Ordering::Less);
+    args.register.set_zero(ordering == Ordering::Equal);
+    args.register.set_negative(ordering == Ordering::Less);
+    return Ok(());
+}
@@ -0,0 +1,17 @@
+use crate::memory::address::Address;
+
+use super::InstructionArgument;
+
+/// Copy as many bytes as the third register holds from the address in the second register to
+/// the address in the first, the ranges may overlap
+pub fn mcpy(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let destination = args.argument.parse_register()?;
+    let source = args.argument.parse_register()?;
+    let len = args.argument.parse_register()?;
+    let destination = Address::new(args.register.get_general(&destination)? as usize);
+    let source = Address::new(args.register.get_general(&source)? as usize);
+    let len = args.register.get_general(&len)? as usize;
+    args.memory.mem_copy(destination, source, len)?;
+    return Ok(());
+}
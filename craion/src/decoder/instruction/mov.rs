@@ -8,16 +8,14 @@ use common::{
     memory::buffer_reader::BufferReader,
     register::{RegisterSizes, RegisterType},
 };
-use proc::instruction;
 
 use crate::memory::address::Address;
 
 use super::{InstructionArgument, InstructionError};
 
-#[instruction(MOV_OPCODE, "crate::decoder::instruction::mov::mov")]
 pub fn mov(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     args.register.inc_ip(args.instruction_length);
-    match args.argument.parse_u8()? {
+    match args.argument.parse_sub_opcode()? {
         MOV_REG2REG => {
             let dst = args.argument.parse_register()?;
             args.register.reset_group(&dst.group());
@@ -29,7 +27,7 @@ pub fn mov(args: &mut InstructionArgument) -> Result<(), super::InstructionError
         }
         MOV_REG2DEREF_REG => {
             let register = args.argument.parse_register()?;
-            let address = args.argument.parse_register()?;
+            let address = args.argument.parse_deref()?;
             let address = Address::new(args.register.get_general(&address)? as usize);
             let value = args.register.get_general(&register)?;
             match register.size() {
@@ -53,15 +51,8 @@ pub fn mov(args: &mut InstructionArgument) -> Result<(), super::InstructionError
         MOV_NUM2REG => {
             let reg = args.argument.parse_register()?;
             args.register.reset_group(&reg.group());
-            args.register.set_general(
-                &reg,
-                match reg.size() {
-                    RegisterSizes::SizeU8 => args.argument.parse_u8()?.into(),
-                    RegisterSizes::SizeU16 => args.argument.parse_u16()?.into(),
-                    RegisterSizes::SizeU32 => args.argument.parse_u32()?.into(),
-                    RegisterSizes::SizeU64 => args.argument.parse_u64()?.into(),
-                },
-            )?;
+            args.register
+                .set_general(&reg, args.argument.parse_immediate()?)?;
         }
         MOV_ADD2SP => {
             args.argument.parse_register()?;
@@ -76,25 +67,25 @@ pub fn mov(args: &mut InstructionArgument) -> Result<(), super::InstructionError
         }
         MOV_DEREF_REG2REG => {
             let register = args.argument.parse_register()?;
-            let address = args.argument.parse_register()?;
+            let address = args.argument.parse_deref()?;
             let address = Address::new(args.register.get_general(&address)? as usize);
             match register.size() {
                 RegisterSizes::SizeU8 => {
-                    let data = args.memory.mem_get(address)?;
+                    let data = args.memory.mem_read(address)?;
                     args.register.set_general(&register, data.into())?;
                 }
                 RegisterSizes::SizeU16 => {
-                    let data = args.memory.mem_gets(address, 2)?;
+                    let data = args.memory.mem_reads(address, 2)?;
                     let data = BufferReader::new(data).read_u16().unwrap();
                     args.register.set_general(&register, data.into())?;
                 }
                 RegisterSizes::SizeU32 => {
-                    let data = args.memory.mem_gets(address, 4)?;
+                    let data = args.memory.mem_reads(address, 4)?;
                     let data = BufferReader::new(data).read_u32().unwrap();
                     args.register.set_general(&register, data.into())?;
                 }
                 RegisterSizes::SizeU64 => {
-                    let data = args.memory.mem_gets(address, 8)?;
+                    let data = args.memory.mem_reads(address, 8)?;
                     let data = BufferReader::new(data).read_u64().unwrap();
                     args.register.set_general(&register, data)?;
                 }
@@ -116,7 +107,7 @@ pub fn mov(args: &mut InstructionArgument) -> Result<(), super::InstructionError
             }
         }
         MOV_NUM2DEREF_REG => {
-            let reg = args.argument.parse_register()?;
+            let reg = args.argument.parse_deref()?;
             let num = args.argument.parse_u64()?;
             let address = Address::new(args.register.get_general(&reg)? as usize);
             args.memory.mem_sets(address, &num.to_le_bytes())?;
@@ -0,0 +1,17 @@
+use crate::memory::address::Address;
+
+use super::InstructionArgument;
+
+/// Fill as many bytes as the third register holds at the address in the first register with
+/// the low byte of the second
+pub fn mset(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let destination = args.argument.parse_register()?;
+    let value = args.argument.parse_register()?;
+    let len = args.argument.parse_register()?;
+    let destination = Address::new(args.register.get_general(&destination)? as usize);
+    let value = args.register.get_general(&value)? as u8;
+    let len = args.register.get_general(&len)? as usize;
+    args.memory.mem_fill(destination, value, len)?;
+    return Ok(());
+}
@@ -0,0 +1,15 @@
+use crate::memory::address::Address;
+
+use super::InstructionArgument;
+
+/// Print the string a register points to, a u32 byte length followed by the UTF-8 bytes
+pub fn outs(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let reg = args.argument.parse_register()?;
+    let address = Address::new(args.register.get_general(&reg)? as usize);
+    let len = u32::from_le_bytes(args.memory.mem_reads(address, 4)?.try_into().unwrap());
+    let bytes = args.memory.mem_reads(address + 4, len as usize)?;
+    let value = std::str::from_utf8(bytes).map_err(|_| super::InstructionError::InvalidUTF8)?;
+    print!("{value}");
+    return Ok(());
+}
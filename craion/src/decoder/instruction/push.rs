@@ -1,9 +1,7 @@
 use common::register::RegisterSizes;
-use proc::instruction;
 
 use super::InstructionArgument;
 
-#[instruction(PUSH_OPCODE, "crate::decoder::instruction::push::push")]
 pub fn push(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     args.register.inc_ip(args.instruction_length);
     let reg = args.argument.parse_register()?;
@@ -0,0 +1,28 @@
+use crate::executor::{channel::ChannelError, thread::ThreadRequest};
+
+use super::InstructionArgument;
+
+/// Receive a message into a register, carry is set and the register zeroed once the channel
+/// is closed and empty. Blocks the thread while the channel is open and empty
+pub fn recv(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    let reg = args.argument.parse_register()?;
+    let id = args
+        .register
+        .get_general(&args.argument.parse_register()?)?;
+    let channel = args.executor_state.channels().get(id)?.clone();
+    let (value, closed) = match channel.try_recv() {
+        Ok(Some(value)) => (value, false),
+        Err(ChannelError::Closed) => (0, true),
+        Err(e) => return Err(e.into()),
+        Ok(None) => {
+            args.executor_state
+                .threads()
+                .request(ThreadRequest::Recv(channel));
+            return Ok(());
+        }
+    };
+    args.register.set_general(&reg, value)?;
+    args.register.set_carry(closed);
+    args.register.inc_ip(args.instruction_length);
+    return Ok(());
+}
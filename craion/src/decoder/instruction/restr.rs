@@ -1,9 +1,7 @@
 use common::register::RegisterType;
-use proc::instruction;
 
 use super::{InstructionArgument, InstructionError};
 
-#[instruction(RESTR_OPCODE, "crate::decoder::instruction::restr::restr")]
 pub fn restr(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     args.register.inc_ip(args.instruction_length);
 
@@ -1,13 +1,24 @@
-use proc::instruction;
+use common::register::RegisterType;
+
+use crate::executor::thread::{ThreadRequest, MAIN_THREAD};
 
 use super::{InstructionArgument, InstructionError};
 
-#[instruction(RET_OPCODE, "crate::decoder::instruction::ret::ret")]
+/// Returning from the procedure a spawned thread started in finishes the thread with the value
+/// of `a64`
 pub fn ret(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    if args.ret_stack.is_empty() && args.executor_state.threads().current() != MAIN_THREAD {
+        let value = args.register.get_general(&RegisterType::A64)?;
+        args.executor_state
+            .threads()
+            .request(ThreadRequest::Finish(value));
+        return Ok(());
+    }
     args.register.set_ip(
         args.ret_stack
             .pop()
             .ok_or(InstructionError::EmptyRetStack)?,
     );
+    args.executor_state.pop_argument_frame();
     return Ok(());
 }
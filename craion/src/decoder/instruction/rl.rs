@@ -0,0 +1,11 @@
+use common::register::RegisterType;
+
+use super::InstructionArgument;
+
+/// Return with the value of a u64 local in `a64`
+pub fn rl(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    let index = args.argument.parse_u16()?;
+    let value = args.executor_state.locals()?.get(index)?.as_scalar()?;
+    args.register.set_general(&RegisterType::A64, value)?;
+    return super::ret::ret(args);
+}
@@ -1,9 +1,7 @@
 use common::register::RegisterType;
-use proc::instruction;
 
 use super::{InstructionArgument, InstructionError};
 
-#[instruction(SAVR_OPCODE, "crate::decoder::instruction::savr::savr")]
 pub fn savr(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     args.register.inc_ip(args.instruction_length);
 
@@ -0,0 +1,13 @@
+use super::InstructionArgument;
+
+pub fn send(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let id = args
+        .register
+        .get_general(&args.argument.parse_register()?)?;
+    let value = args
+        .register
+        .get_general(&args.argument.parse_register()?)?;
+    args.executor_state.channels().get(id)?.send(value)?;
+    return Ok(());
+}
@@ -0,0 +1,24 @@
+use common::sin::sections::SectionType;
+
+use super::{InstructionArgument, InstructionError};
+
+pub fn spawn(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    let reg = args.argument.parse_register()?;
+    let procedure_hash = args.argument.parse_procedure()?;
+    let section = args
+        .section_manager
+        .get_section_hash(procedure_hash)
+        .ok_or(InstructionError::InvalidSection(procedure_hash))?;
+    if section.section_type() != SectionType::Procedure {
+        return Err(InstructionError::NotProcedureSection);
+    }
+    let start = section.mem_start();
+    let arguments = args.executor_state.take_pending_arguments();
+    let id = args
+        .executor_state
+        .threads()
+        .spawn(start, arguments, args.register.get_sp())?;
+    args.register.set_general(&reg, id)?;
+    return Ok(());
+}
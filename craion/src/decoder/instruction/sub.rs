@@ -1,15 +1,13 @@
 use common::constants::{SUB_OPCODE, SUB_REG_W_NUM, SUB_REG_W_REG, SUB_SP_W_NUM};
-use proc::instruction;
 
 use crate::memory::address::Address;
 
 use super::InstructionArgument;
 
-#[instruction(SUB_OPCODE, "crate::decoder::instruction::sub::sub")]
 pub fn sub(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
     args.register.inc_ip(args.instruction_length);
 
-    match args.argument.parse_u8()? {
+    match args.argument.parse_sub_opcode()? {
         SUB_REG_W_REG => {
             let reg1 = args.argument.parse_register()?;
             let reg2 = args.argument.parse_register()?;
@@ -0,0 +1,9 @@
+use crate::executor::thread::ThreadRequest;
+
+use super::InstructionArgument;
+
+pub fn r#yield(args: &mut InstructionArgument) -> Result<(), super::InstructionError> {
+    args.register.inc_ip(args.instruction_length);
+    args.executor_state.threads().request(ThreadRequest::Yield);
+    return Ok(());
+}
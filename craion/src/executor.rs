@@ -1,57 +1,341 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error, fmt::Display, path::PathBuf, sync::Arc};
 
-use common::sin::sections::SinSection;
+use common::sin::{
+    debug_info::DebugInfo,
+    sections::{SectionType, SinSection},
+    type_info::TypeInfo,
+    SinError,
+};
+
+use common::register::RegisterType;
 
 use crate::{
-    decoder::decode,
-    memory::{argument_memory::ArgumentMemory, Memory},
+    core_dump::CoreDump,
+    decoder::{decode, instruction::InstructionError, DecoderError},
+    jit::{Jit, JitError},
+    layout::{Layout, LayoutError},
+    memory::{
+        address::Address,
+        device::Device,
+        watchpoint::{WatchKind, WatchpointHit},
+        Memory, MemoryError,
+    },
     ret_stack::RetStack,
     section_manager::SectionManager,
 };
 
-use self::registers::RegisterFile;
+use self::{
+    backtrace::{Backtrace, Frame},
+    channel::{Channel, ChannelError, Channels, Signal},
+    local::{Local, Locals, TypeError},
+    registers::RegisterFile,
+    thread::{CallFrames, ThreadError, Threads},
+};
 
+pub mod backtrace;
+pub mod builder;
+pub mod channel;
+pub mod image;
+pub mod local;
 pub mod registers;
+pub mod thread;
 
 #[derive(Debug)]
 pub struct ExecutorState {
-    stack_saved_size: Vec<u64>,
-    procedure_arguments: HashMap<u32, u64>,
+    frames: CallFrames,
+    threads: Threads,
+    channels: Channels,
+    /// Struct layouts from the type sections of the loaded program
+    types: Arc<TypeInfo>,
     exit_code: u64,
 }
 
 pub struct Executor {
     memory: Memory,
     register: RegisterFile,
-    argument_memory: ArgumentMemory,
     ret_stack: RetStack,
     section_manager: SectionManager,
     state: ExecutorState,
+    debug_info: Arc<DebugInfo>,
+    watchpoint_stop: Option<WatchpointStop>,
+    jit: Option<Jit>,
+    step_limit: Option<u64>,
+    steps: u64,
+    core_file: Option<PathBuf>,
+    /// Result of writing the core of the last failure
+    core_written: Option<std::io::Result<()>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    Sin(SinError),
+    Layout(LayoutError),
+    /// No section with the hash is loaded
+    UndefinedSection(u64),
+    /// A procedure or constant section with no data
+    EmptySection(u64),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sin(error) => write!(f, "{}", error),
+            Self::Layout(error) => write!(f, "{}", error),
+            Self::UndefinedSection(hash) => {
+                write!(f, "Section {:#018x} is not loaded", hash)
+            }
+            Self::EmptySection(hash) => write!(f, "Section {:#018x} is empty", hash),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+impl From<SinError> for LoadError {
+    fn from(value: SinError) -> Self {
+        Self::Sin(value)
+    }
+}
+
+impl From<LayoutError> for LoadError {
+    fn from(value: LayoutError) -> Self {
+        Self::Layout(value)
+    }
+}
+
+#[derive(Debug)]
+pub enum ExecutionError {
+    UndefinedProcedure(String),
+    NotProcedure(String),
+    Decode {
+        error: DecoderError,
+        ip: Address,
+    },
+    Instruction {
+        error: InstructionError,
+        ip: Address,
+        opcode: u16,
+    },
+    Thread {
+        error: ThreadError,
+        ip: Address,
+    },
+    /// Stopped on a watchpoint, see `Executor::watchpoint_stop`
+    Watchpoint(Address),
+    /// Stopped before the instruction at the address, the step limit was reached
+    StepLimit(Address),
+    /// The program halted before the call returned, with the exit code
+    Halted(u64),
+}
+
+impl ExecutionError {
+    /// Address of the instruction that failed
+    pub fn ip(&self) -> Option<Address> {
+        return match self {
+            Self::Decode { ip, .. }
+            | Self::Instruction { ip, .. }
+            | Self::Thread { ip, .. }
+            | Self::Watchpoint(ip)
+            | Self::StepLimit(ip) => Some(*ip),
+            Self::UndefinedProcedure(_) | Self::NotProcedure(_) | Self::Halted(_) => None,
+        };
+    }
+}
+
+impl Display for ExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UndefinedProcedure(name) => write!(f, "Procedure `{}` is not loaded", name),
+            Self::NotProcedure(name) => write!(f, "Section `{}` is not a procedure", name),
+            Self::Decode { error, .. } => write!(f, "{}", error),
+            Self::Instruction { error, opcode, .. } => write!(
+                f,
+                "Error occur while executing instruction: '{}', opcode: {}",
+                error, opcode
+            ),
+            Self::Thread { error, .. } => write!(f, "{}", error),
+            Self::Watchpoint(ip) => write!(f, "Stopped on a watchpoint at {}", ip),
+            Self::StepLimit(ip) => write!(f, "Step limit reached at {}", ip),
+            Self::Halted(exit_code) => {
+                write!(f, "The program halted with exit code {}", exit_code)
+            }
+        }
+    }
+}
+
+impl Error for ExecutionError {}
+
+/// Why execution stopped on a watchpoint, the ip points to the watched instruction
+#[derive(Debug)]
+pub struct WatchpointStop {
+    ip: Address,
+    opcode: u16,
+    instruction: Vec<u8>,
+    hits: Vec<WatchpointHit>,
+}
+
+impl WatchpointStop {
+    pub fn ip(&self) -> Address {
+        return self.ip;
+    }
+
+    pub fn opcode(&self) -> u16 {
+        return self.opcode;
+    }
+
+    pub fn instruction(&self) -> &[u8] {
+        return &self.instruction;
+    }
+
+    pub fn hits(&self) -> &[WatchpointHit] {
+        return &self.hits;
+    }
+}
+
+impl Display for WatchpointStop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Stopped at {}, opcode: {}, instruction: {:02x?}",
+            self.ip, self.opcode, self.instruction
+        )?;
+        for hit in self.hits.iter() {
+            writeln!(f, "  {hit}")?;
+        }
+        return Ok(());
+    }
 }
 
 impl ExecutorState {
-    pub fn new() -> Self {
+    pub fn new(layout: &Layout) -> Self {
+        let signal = Arc::new(Signal::default());
         Self {
-            stack_saved_size: Vec::new(),
-            procedure_arguments: HashMap::new(),
+            frames: CallFrames::default(),
+            threads: Threads::new(layout, signal.clone()),
+            channels: Channels::new(signal),
+            types: Arc::new(TypeInfo::new()),
             exit_code: 0,
         }
     }
 
     pub fn save_stack_size(&mut self, size: u64) {
-        self.stack_saved_size.push(size);
+        self.frames.stack_saved_size.push(size);
     }
 
     pub fn consume_stack_size(&mut self) -> u64 {
-        return self.stack_saved_size.pop().unwrap_or(0);
+        return self.frames.stack_saved_size.pop().unwrap_or(0);
     }
 
+    /// Set an argument for the next `call`
     pub fn load_argument(&mut self, index: u32, value: u64) {
-        self.procedure_arguments.insert(index, value);
+        self.frames.pending_arguments.insert(index, value);
+    }
+
+    /// Take the arguments set for the next `call` or `spawn`
+    pub fn take_pending_arguments(&mut self) -> HashMap<u32, u64> {
+        return std::mem::take(&mut self.frames.pending_arguments);
+    }
+
+    /// Bind the pending arguments to a new call frame
+    pub fn push_argument_frame(&mut self) {
+        let arguments = self.take_pending_arguments();
+        self.frames.argument_frames.push(arguments);
+    }
+
+    pub fn pop_argument_frame(&mut self) {
+        self.frames.argument_frames.pop();
+        let depth = self.frames.argument_frames.len();
+        while self
+            .frames
+            .locals
+            .last()
+            .is_some_and(|(frame, _)| *frame > depth)
+        {
+            self.frames.locals.pop();
+        }
+    }
+
+    /// Number of argument frames of the running thread
+    pub fn argument_frame_depth(&self) -> usize {
+        return self.frames.argument_frames.len();
+    }
+
+    /// Pop argument frames and their locals until `depth` are left
+    pub fn truncate_argument_frames(&mut self, depth: usize) {
+        while self.frames.argument_frames.len() > depth {
+            self.pop_argument_frame();
+        }
+    }
+
+    /// Set up `size` untyped locals for the current call, replacing the ones it had
+    pub fn init_locals(&mut self, size: u16) {
+        let depth = self.frames.argument_frames.len();
+        if let Some((frame, locals)) = self.frames.locals.last_mut() {
+            if *frame == depth {
+                *locals = Locals::new(size);
+                return;
+            }
+        }
+        self.frames.locals.push((depth, Locals::new(size)));
     }
 
-    pub fn get_argument(&self, index: u32) -> u64 {
-        return *self.procedure_arguments.get(&index).unwrap_or(&0);
+    /// The locals of the current call
+    pub fn locals(&mut self) -> Result<&mut Locals, TypeError> {
+        let depth = self.frames.argument_frames.len();
+        return match self.frames.locals.last_mut() {
+            Some((frame, locals)) if *frame == depth => Ok(locals),
+            _ => Err(TypeError::NoLocals),
+        };
+    }
+
+    /// `Locals::set_field` on the locals of the current call with the loaded types
+    pub fn set_local_field(
+        &mut self,
+        target: u16,
+        field: u64,
+        source: u16,
+    ) -> Result<(), TypeError> {
+        let depth = self.frames.argument_frames.len();
+        return match self.frames.locals.last_mut() {
+            Some((frame, locals)) if *frame == depth => {
+                locals.set_field(&self.types, target, field, source)
+            }
+            _ => Err(TypeError::NoLocals),
+        };
+    }
+
+    pub fn locals_ref(&self) -> Option<&Locals> {
+        let depth = self.frames.argument_frames.len();
+        return match self.frames.locals.last() {
+            Some((frame, locals)) if *frame == depth => Some(locals),
+            _ => None,
+        };
+    }
+
+    /// Get an argument of the current call frame
+    pub fn get_argument(&self, index: u32) -> Option<u64> {
+        return self
+            .frames
+            .argument_frames
+            .last()
+            .and_then(|arguments| arguments.get(&index))
+            .copied();
+    }
+
+    pub fn types(&self) -> &TypeInfo {
+        return &self.types;
+    }
+
+    pub fn threads(&mut self) -> &mut Threads {
+        return &mut self.threads;
+    }
+
+    pub fn threads_ref(&self) -> &Threads {
+        return &self.threads;
+    }
+
+    pub fn channels(&mut self) -> &mut Channels {
+        return &mut self.channels;
     }
 
     pub fn set_exit_code(&mut self, value: u64) {
@@ -60,14 +344,47 @@ impl ExecutorState {
 }
 
 impl Executor {
+    /// Create an executor with the default layout of `mem_size` bytes, see `Layout::new`
     pub fn new(mem_size: usize) -> Self {
+        return Self::from_layout(Layout::new(mem_size));
+    }
+
+    /// Create an executor with a custom layout, sp starts at the top of the stack
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::executor::Executor;
+    /// use craion::layout::Layout;
+    /// use craion::memory::address::Address;
+    /// let layout = Layout::new(0x10000)
+    ///     .with_stack_top(Address::new(0x8000))
+    ///     .with_stack_size(0x1000);
+    /// let executor = Executor::with_layout(layout).unwrap();
+    ///
+    /// assert_eq!(Address::new(0x8000), executor.registers_ref().get_sp());
+    /// ```
+    pub fn with_layout(layout: Layout) -> Result<Self, LayoutError> {
+        layout.validate()?;
+        return Ok(Self::from_layout(layout));
+    }
+
+    fn from_layout(layout: Layout) -> Self {
+        let mut register = RegisterFile::new();
+        register.set_sp(layout.stack_top());
         Self {
-            memory: Memory::new(mem_size),
-            register: RegisterFile::new(),
-            argument_memory: ArgumentMemory::new(),
+            memory: Memory::new(layout.memory_size()),
+            register,
             ret_stack: RetStack::new(),
-            section_manager: SectionManager::new(),
-            state: ExecutorState::new(),
+            section_manager: SectionManager::new(layout),
+            state: ExecutorState::new(&layout),
+            debug_info: Arc::new(DebugInfo::new()),
+            watchpoint_stop: None,
+            jit: None,
+            step_limit: None,
+            steps: 0,
+            core_file: None,
+            core_written: None,
         }
     }
 
@@ -79,13 +396,122 @@ impl Executor {
         return &mut self.register;
     }
 
+    pub fn section_manager_ref(&self) -> &SectionManager {
+        return &self.section_manager;
+    }
+
+    pub fn layout(&self) -> &Layout {
+        return self.section_manager.layout();
+    }
+
     pub fn registers_ref(&self) -> &RegisterFile {
         return &self.register;
     }
 
-    pub fn load_section(&mut self, section: &SinSection, data: &[u8]) {
+    /// A typed local of the current call of the running thread
+    pub fn local(&self, index: u16) -> Result<&Local, TypeError> {
+        return self
+            .state
+            .locals_ref()
+            .ok_or(TypeError::NoLocals)?
+            .get(index);
+    }
+
+    /// Load a section into memory, debug sections are kept aside for error reporting and type
+    /// sections are added to the type registry
+    ///
+    /// Loading a section with the hash of a loaded one replaces it, even while the program
+    /// runs. Calls and jumps from other code go to the new section, code already running in
+    /// the old one keeps running until it returns, see `reclaim_sections`
+    pub fn load_section(&mut self, section: &SinSection, data: &[u8]) -> Result<(), LoadError> {
+        if section.section_type() == SectionType::Debug {
+            let data = data
+                .get(section.start() as usize..section.end() as usize)
+                .ok_or(SinError::InvalidSection)?;
+            Arc::make_mut(&mut self.debug_info).extend(DebugInfo::from_bytes(data)?);
+            return Ok(());
+        }
+        if section.section_type() == SectionType::Types {
+            let data = data
+                .get(section.start() as usize..section.end() as usize)
+                .ok_or(SinError::InvalidSection)?;
+            Arc::make_mut(&mut self.state.types).extend(TypeInfo::from_bytes(data)?);
+            return Ok(());
+        }
+        self.reclaim_sections();
         self.section_manager
-            .load_section(section, data, &mut self.memory);
+            .load_section(section, data, &mut self.memory)?;
+        if let Some(jit) = &mut self.jit {
+            jit.invalidate();
+        }
+        return Ok(());
+    }
+
+    /// Unload a section, it stays in memory while code runs in it
+    pub fn unload_section(&mut self, hash: u64) -> Result<(), LoadError> {
+        if !self.section_manager.unload_section(hash) {
+            return Err(LoadError::UndefinedSection(hash));
+        }
+        self.reclaim_sections();
+        if let Some(jit) = &mut self.jit {
+            jit.invalidate();
+        }
+        return Ok(());
+    }
+
+    /// Free the memory of replaced and unloaded procedures no thread runs in or returns to,
+    /// returns the number of bytes freed. Loading and unloading sections does this too.
+    /// Constant sections are only freed by `free_retired_constants`
+    pub fn reclaim_sections(&mut self) -> usize {
+        // Return addresses point past the call, which can be the end of the section
+        let live: Vec<Address> = [self.register.get_ip()]
+            .into_iter()
+            .chain(self.ret_stack.frames())
+            .chain(self.state.threads.code_addresses())
+            .flat_map(|address| [address, Address::new(address.get_raw().saturating_sub(1))])
+            .collect();
+        return self.section_manager.reclaim(&live, &mut self.memory);
+    }
+
+    /// Free the memory of every replaced and unloaded constant section, returns the number of
+    /// bytes freed. The program must not use addresses into them anymore
+    pub fn free_retired_constants(&mut self) -> usize {
+        return self.section_manager.free_constants(&mut self.memory);
+    }
+
+    pub fn debug_info(&self) -> &DebugInfo {
+        return &self.debug_info;
+    }
+
+    /// Struct layouts `ftyll` checks the fields of locals against
+    pub fn types(&self) -> &TypeInfo {
+        return &self.state.types;
+    }
+
+    /// Describe `address` as a source location if debug info is available
+    pub fn describe_address(&self, address: Address) -> String {
+        let frame = Frame::resolve(address, &self.section_manager, &self.debug_info);
+        return match (frame.name(), frame.location()) {
+            (Some(name), Some((file, line, column))) => {
+                format!("proc {} at {}:{}:{}", name, file, line, column)
+            }
+            (Some(name), None) => format!("proc {name} at {address}"),
+            _ => format!("instruction pointer: {address}"),
+        };
+    }
+
+    /// Build a backtrace from `ip` and the current return stack
+    pub fn backtrace(&self, ip: Address) -> Backtrace {
+        return Backtrace::new(
+            ip,
+            self.ret_stack.frames(),
+            &self.section_manager,
+            &self.debug_info,
+        );
+    }
+
+    pub fn ret_stack(&self) -> &RetStack {
+        return &self.ret_stack;
     }
 
     pub fn memory(&mut self) -> &mut Memory {
@@ -96,39 +522,277 @@ impl Executor {
         return &self.memory;
     }
 
-    pub fn execute(&mut self) {
+    /// Map a device into guest memory at `start`
+    pub fn map_device(
+        &mut self,
+        start: Address,
+        device: impl Device + 'static,
+    ) -> Result<(), MemoryError> {
+        return self.memory.map_device(start, Box::new(device));
+    }
+
+    /// Compile code reached more than `threshold` times to native code
+    pub fn enable_jit(&mut self, threshold: u32) -> Result<(), JitError> {
+        self.jit = Some(Jit::new(threshold)?);
+        return Ok(());
+    }
+
+    pub fn jit(&self) -> Option<&Jit> {
+        return self.jit.as_ref();
+    }
+
+    /// Stop execution after an instruction accesses `start..start + size`
+    pub fn add_watchpoint(&mut self, start: Address, size: usize, kind: WatchKind) -> usize {
+        return self.memory.watchpoints().add(start, size, kind);
+    }
+
+    pub fn remove_watchpoint(&mut self, id: usize) -> bool {
+        return self.memory.watchpoints().remove(id);
+    }
+
+    pub fn threads(&self) -> &Threads {
+        return &self.state.threads;
+    }
+
+    /// Create a channel guest code can reach by the returned id
+    pub fn create_channel(&mut self) -> u64 {
+        return self.state.channels.create();
+    }
+
+    /// Get a handle to a channel to send messages into the program from host code, even while
+    /// it is running on another thread
+    pub fn channel(&self, id: u64) -> Result<Channel, ChannelError> {
+        return self.state.channels.handle(id);
+    }
+
+    /// Make a channel of another executor or host code reachable from guest code
+    pub fn attach_channel(&mut self, channel: &Channel) -> u64 {
+        return self.state.channels.attach_handle(channel);
+    }
+
+    /// Stop execution once `limit` steps were taken, every interpreted instruction and every
+    /// pass through a compiled trace is a step. Raising the limit lets a stopped program resume
+    pub fn set_step_limit(&mut self, limit: Option<u64>) {
+        self.step_limit = limit;
+    }
+
+    /// Number of steps taken so far
+    pub fn steps(&self) -> u64 {
+        return self.steps;
+    }
+
+    /// Write a core dump to `path` when the program fails in `execute` or `call`, see
+    /// `CoreDump`. Stopping on a watchpoint or the step limit is not a failure
+    pub fn set_core_file(&mut self, path: Option<PathBuf>) {
+        self.core_file = path;
+    }
+
+    /// Returns the watchpoint that stopped the last `execute`, if any
+    pub fn watchpoint_stop(&self) -> Option<&WatchpointStop> {
+        return self.watchpoint_stop.as_ref();
+    }
+
+    /// Run until the program halts, fails, or touches a watched range. A stopped program
+    /// can be resumed by calling this again. Returns whether the program exited
+    pub fn execute(&mut self) -> bool {
+        match self.run(None) {
+            Ok(()) => {
+                println!("Program exit with exit code {}", self.state.exit_code);
+                return true;
+            }
+            Err(ExecutionError::Watchpoint(ip)) => {
+                if let Some(stop) = &self.watchpoint_stop {
+                    print!("{}", stop);
+                }
+                println!("{}", self.describe_address(ip));
+                print!("{}", self.backtrace(ip));
+            }
+            Err(e) => {
+                let ip = e.ip().unwrap_or(self.register.get_ip());
+                println!("{}, {}", e, self.describe_address(ip));
+                print!("{}", self.backtrace(ip));
+                if let (Some(path), Some(written)) = (&self.core_file, self.core_written.take()) {
+                    match written {
+                        Ok(()) => println!("Core dumped to {}", path.display()),
+                        Err(error) => {
+                            println!("Couldn't write core to {}: {}", path.display(), error)
+                        }
+                    }
+                }
+            }
+        }
+        return false;
+    }
+
+    /// Call a procedure with `arguments` as if by `arg` and `call`, and return `a64` once it
+    /// returns. Other threads keep being scheduled while it runs, and the ip is restored
+    /// afterwards unless execution fails. A `halt` or `exit` in the procedure is returned as
+    /// `Halted`, the frames of the call are dropped and the ip is restored too
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::executor::{ExecutionError, Executor};
+    /// let mut executor = Executor::new(0xFFFF);
+    ///
+    /// assert!(matches!(
+    ///     executor.call("dev$main$main", &[]),
+    ///     Err(ExecutionError::UndefinedProcedure(_))
+    /// ));
+    /// ```
+    pub fn call(&mut self, procedure: &str, arguments: &[u64]) -> Result<u64, ExecutionError> {
+        let section = self
+            .section_manager
+            .get_section(procedure)
+            .ok_or(ExecutionError::UndefinedProcedure(procedure.to_string()))?;
+        if section.section_type() != SectionType::Procedure {
+            return Err(ExecutionError::NotProcedure(procedure.to_string()));
+        }
+        let start = section.mem_start();
+        for (index, value) in arguments.iter().enumerate() {
+            self.state.load_argument(index as u32, *value);
+        }
+        let frames = self.state.argument_frame_depth();
+        self.state.push_argument_frame();
+        let ip = self.register.get_ip();
+        let depth = self.ret_stack.len();
+        let thread = self.state.threads.current();
+        self.ret_stack.push(ip);
+        self.register.set_ip(start);
+        self.register.set_halt(false);
+        let result = self.run(Some((thread, depth)));
+        if matches!(result, Err(ExecutionError::Halted(_)))
+            && self.state.threads.current() == thread
+        {
+            self.ret_stack.truncate(depth);
+            self.state.truncate_argument_frames(frames);
+            self.register.set_ip(ip);
+        }
+        result?;
+        return Ok(self.register.get_general(&RegisterType::A64).unwrap_or(0));
+    }
+
+    /// Execute instructions until the program halts, or until the thread of `until` returns
+    /// to the given return stack depth. A core is written if the program fails
+    fn run(&mut self, until: Option<(u64, usize)>) -> Result<(), ExecutionError> {
+        let result = self.run_until(until);
+        if let Err(error) = &result {
+            self.dump_core(error);
+        }
+        return result;
+    }
+
+    /// Write a core to the core file if one is set, watchpoints and the step limit only pause
+    /// the program
+    fn dump_core(&mut self, error: &ExecutionError) {
+        if matches!(
+            error,
+            ExecutionError::Watchpoint(_) | ExecutionError::StepLimit(_)
+        ) {
+            return;
+        }
+        let Some(path) = &self.core_file else {
+            return;
+        };
+        self.core_written = Some(std::fs::write(
+            path,
+            CoreDump::capture(self, error).to_bytes(),
+        ));
+    }
+
+    fn run_until(&mut self, until: Option<(u64, usize)>) -> Result<(), ExecutionError> {
+        self.watchpoint_stop = None;
+        self.memory.watchpoints().take_hits();
+        let mut block_head = true;
         while !self.register.get_halt() {
-            {
-                let mut instruction = match decode(
-                    &mut self.memory,
+            if let Some(request) = self.state.threads.take_request() {
+                self.state
+                    .threads
+                    .schedule(
+                        request,
+                        &mut self.register,
+                        &mut self.ret_stack,
+                        &mut self.state.frames,
+                    )
+                    .map_err(|error| ExecutionError::Thread {
+                        error,
+                        ip: self.register.get_ip(),
+                    })?;
+            }
+            let ip = self.register.get_ip();
+            if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+                return Err(ExecutionError::StepLimit(ip));
+            }
+            if let (Some(jit), true) = (&mut self.jit, block_head) {
+                let passes = self.step_limit.map_or(u64::MAX, |limit| limit - self.steps);
+                let entered = jit.enter(
                     &mut self.register,
-                    &mut self.argument_memory,
-                    &mut self.ret_stack,
-                    &mut self.section_manager,
-                    &mut self.state,
-                ) {
-                    Ok(result) => result,
-                    Err(e) => {
-                        println!("{}", e);
-                        return;
+                    &mut self.memory,
+                    &self.section_manager,
+                    passes,
+                );
+                if let Some(passes) = entered {
+                    self.steps += passes;
+                    self.check_stack(ip)?;
+                    if self.register.get_ip() != ip {
+                        continue;
                     }
-                };
-
-                match instruction.execute() {
-                    Ok(_) => {}
-                    Err(e) => {
-                        println!(
-                            "Error occur while executing instruction: '{}', opcode: {}, instruction pointer: {}",
-                            e,
-                            instruction.op_code(),
-                            self.register.get_ip()
-                        );
-                        return;
+                    if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+                        return Err(ExecutionError::StepLimit(ip));
                     }
-                };
+                }
+            }
+            self.steps += 1;
+            let mut instruction = decode(
+                &mut self.memory,
+                &mut self.register,
+                &mut self.ret_stack,
+                &mut self.section_manager,
+                &mut self.state,
+            )
+            .map_err(|error| ExecutionError::Decode { error, ip })?;
+
+            let opcode = instruction.op_code();
+            block_head = Jit::ends_block(opcode);
+            instruction
+                .execute()
+                .map_err(|error| ExecutionError::Instruction { error, ip, opcode })?;
+            self.check_stack(ip)?;
+            let hits = self.memory.watchpoints().take_hits();
+            if !hits.is_empty() {
+                let instruction = self
+                    .memory
+                    .mem_gets(ip, 1)
+                    .and_then(|il| self.memory.mem_gets(ip, il[0] as usize))
+                    .map(|instruction| instruction.to_vec())
+                    .unwrap_or_default();
+                self.watchpoint_stop = Some(WatchpointStop {
+                    ip,
+                    opcode,
+                    instruction,
+                    hits,
+                });
+                return Err(ExecutionError::Watchpoint(ip));
+            }
+            if let Some((thread, depth)) = until {
+                if self.state.threads.current() == thread && self.ret_stack.len() == depth {
+                    return Ok(());
+                }
             }
         }
-        println!("Program exit with exit code {}", self.state.exit_code);
+        if until.is_some() {
+            return Err(ExecutionError::Halted(self.state.exit_code));
+        }
+        return Ok(());
+    }
+
+    /// Stop a thread that left its stack slot before another thread runs on the stack it wrote
+    fn check_stack(&self, ip: Address) -> Result<(), ExecutionError> {
+        return self
+            .state
+            .threads
+            .check_stack(self.register.get_sp())
+            .map_err(|error| ExecutionError::Thread { error, ip });
     }
 
     pub fn debug_register(&self) {
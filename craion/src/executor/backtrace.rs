@@ -0,0 +1,122 @@
+use std::fmt::Display;
+
+use common::sin::debug_info::DebugInfo;
+
+use crate::{memory::address::Address, section_manager::SectionManager};
+
+/// A single frame of a backtrace resolved to its containing section
+#[derive(Debug, Clone)]
+pub struct Frame {
+    address: Address,
+    section: Option<(u64, usize)>,
+    name: Option<String>,
+    location: Option<(String, u32, u32)>,
+}
+
+/// The call chain at the point of an error, innermost frame first
+#[derive(Debug, Clone)]
+pub struct Backtrace {
+    frames: Vec<Frame>,
+}
+
+impl Frame {
+    /// Resolve `address` to a section and, if debug info is present, to a source location
+    pub fn resolve(
+        address: Address,
+        section_manager: &SectionManager,
+        debug_info: &DebugInfo,
+    ) -> Self {
+        let mut frame = Self {
+            address,
+            section: None,
+            name: None,
+            location: None,
+        };
+        let Some((hash, section)) = section_manager.find_section(address) else {
+            return frame;
+        };
+        let offset = address.get_raw() - section.mem_start().get_raw();
+        frame.section = Some((hash, offset));
+        if let Some(procedure) = debug_info.procedure(hash) {
+            frame.name = Some(procedure.name().to_string());
+            frame.location = procedure
+                .location(offset as u32)
+                .map(|line| (procedure.file().to_string(), line.line(), line.column()));
+        }
+        return frame;
+    }
+
+    pub fn address(&self) -> Address {
+        return self.address;
+    }
+
+    /// The hash of the containing section and the offset into it
+    pub fn section(&self) -> Option<(u64, usize)> {
+        return self.section;
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        return self.name.as_deref();
+    }
+
+    pub fn location(&self) -> Option<(&str, u32, u32)> {
+        return self
+            .location
+            .as_ref()
+            .map(|(file, line, column)| (file.as_str(), *line, *column));
+    }
+}
+
+impl Backtrace {
+    /// Build a backtrace from the current ip and the return addresses, most recent first
+    ///
+    /// Return addresses point past the `call`, so they are resolved one byte earlier to
+    /// land inside the calling instruction. `Executor::call` pushes the ip it was called at,
+    /// which can be 0
+    pub fn new(
+        ip: Address,
+        return_addresses: impl Iterator<Item = Address>,
+        section_manager: &SectionManager,
+        debug_info: &DebugInfo,
+    ) -> Self {
+        let mut frames = vec![Frame::resolve(ip, section_manager, debug_info)];
+        for address in return_addresses {
+            let mut frame = Frame::resolve(
+                Address::new(address.get_raw().saturating_sub(1)),
+                section_manager,
+                debug_info,
+            );
+            frame.address = address;
+            frames.push(frame);
+        }
+        return Self { frames };
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        return &self.frames;
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.name, self.section) {
+            (Some(name), Some((_, offset))) => write!(f, "proc {}+{:#x}", name, offset)?,
+            (None, Some((hash, offset))) => write!(f, "section {:#x}+{:#x}", hash, offset)?,
+            _ => write!(f, "{}", self.address)?,
+        }
+        if let Some((file, line, column)) = &self.location {
+            write!(f, " at {}:{}:{}", file, line, column)?;
+        }
+        return Ok(());
+    }
+}
+
+impl Display for Backtrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Backtrace:")?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            writeln!(f, "  #{}: {}", i, frame)?;
+        }
+        return Ok(());
+    }
+}
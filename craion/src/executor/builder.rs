@@ -0,0 +1,248 @@
+use std::{error::Error, fmt::Display, path::PathBuf};
+
+use common::sin::{sections::SectionType, Sin, SinError};
+
+use crate::{
+    jit::JitError,
+    layout::{Layout, LayoutError, DEFAULT_MEMORY_SIZE},
+    memory::{address::Address, device::Device, MemoryError},
+    verifier::{Verifier, VerifierError},
+};
+
+use super::{image::ProgramImage, Executor, LoadError};
+
+/// Procedure execution starts at unless another entry is given
+pub const DEFAULT_ENTRY: &str = "start";
+
+#[derive(Debug)]
+pub enum BuildError {
+    Layout(LayoutError),
+    Sin(SinError),
+    Verify(Vec<VerifierError>),
+    Load(LoadError),
+    Device(MemoryError),
+    Jit(JitError),
+    EntryNotFound(String),
+    EntryNotProcedure(String),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Layout(e) => write!(f, "Invalid memory layout: {}", e),
+            Self::Sin(e) => write!(f, "Couldn't parse the sin file: {}", e),
+            Self::Verify(errors) => write!(
+                f,
+                "Couldn't verify the sin file, found {} errors",
+                errors.len()
+            ),
+            Self::Load(e) => write!(f, "Couldn't load the sin file: {}", e),
+            Self::Device(e) => write!(f, "Couldn't map a device: {}", e),
+            Self::Jit(e) => write!(f, "{}", e),
+            Self::EntryNotFound(name) => write!(f, "Entry point `{}` not found", name),
+            Self::EntryNotProcedure(name) => {
+                write!(f, "Entry point `{}` is not a procedure", name)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+impl From<LayoutError> for BuildError {
+    fn from(value: LayoutError) -> Self {
+        return Self::Layout(value);
+    }
+}
+
+impl From<SinError> for BuildError {
+    fn from(value: SinError) -> Self {
+        return Self::Sin(value);
+    }
+}
+
+impl From<LoadError> for BuildError {
+    fn from(value: LoadError) -> Self {
+        return Self::Load(value);
+    }
+}
+
+impl From<MemoryError> for BuildError {
+    fn from(value: MemoryError) -> Self {
+        return Self::Device(value);
+    }
+}
+
+impl From<JitError> for BuildError {
+    fn from(value: JitError) -> Self {
+        return Self::Jit(value);
+    }
+}
+
+enum Program<'a> {
+    Sin(Sin<'a>),
+    Bytes(&'a [u8]),
+    Image(&'a ProgramImage),
+}
+
+/// Configure an executor and load a program into it in one go
+///
+/// # Examples
+///
+/// ```
+/// use craion::executor::builder::{BuildError, ExecutorBuilder};
+/// use craion::memory::address::Address;
+/// let executor = ExecutorBuilder::new()
+///     .memory_size(0x10000)
+///     .stack(Address::new(0x10000), 0x1000)
+///     .step_limit(1000)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(Address::new(0x10000), executor.registers_ref().get_sp());
+/// assert!(matches!(
+///     ExecutorBuilder::new().bytes(&[0; 4]).build(),
+///     Err(BuildError::Sin(_))
+/// ));
+/// ```
+pub struct ExecutorBuilder<'a> {
+    layout: Layout,
+    program: Option<Program<'a>>,
+    entry: String,
+    verify: bool,
+    devices: Vec<(Address, Box<dyn Device>)>,
+    jit_threshold: Option<u32>,
+    step_limit: Option<u64>,
+    core_file: Option<PathBuf>,
+}
+
+impl<'a> ExecutorBuilder<'a> {
+    pub fn new() -> Self {
+        Self {
+            layout: Layout::new(DEFAULT_MEMORY_SIZE),
+            program: None,
+            entry: DEFAULT_ENTRY.to_string(),
+            verify: true,
+            devices: Vec::new(),
+            jit_threshold: None,
+            step_limit: None,
+            core_file: None,
+        }
+    }
+
+    /// Use the default layout of `memory_size` bytes, see `Layout::new`
+    pub fn memory_size(mut self, memory_size: usize) -> Self {
+        self.layout = Layout::new(memory_size);
+        return self;
+    }
+
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        return self;
+    }
+
+    /// Place the stack below `top`, sp starts at `top`
+    pub fn stack(mut self, top: Address, size: usize) -> Self {
+        self.layout = self.layout.with_stack_top(top).with_stack_size(size);
+        return self;
+    }
+
+    pub fn sin(mut self, sin: Sin<'a>) -> Self {
+        self.program = Some(Program::Sin(sin));
+        return self;
+    }
+
+    /// Load the encoded sin file in `bytes`, it's parsed when building
+    pub fn bytes(mut self, bytes: &'a [u8]) -> Self {
+        self.program = Some(Program::Bytes(bytes));
+        return self;
+    }
+
+    /// Start from the sections of `image` instead of loading a program, its layout replaces
+    /// the configured one and it is not verified again
+    pub fn image(mut self, image: &'a ProgramImage) -> Self {
+        self.program = Some(Program::Image(image));
+        return self;
+    }
+
+    /// Name of the procedure ip starts at, only looked up when a program is given
+    pub fn entry(mut self, entry: &str) -> Self {
+        self.entry = entry.to_string();
+        return self;
+    }
+
+    /// Whether to run the verifier on the program before loading it, on by default
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        return self;
+    }
+
+    /// Map a device into guest memory at `start`
+    pub fn device(mut self, start: Address, device: impl Device + 'static) -> Self {
+        self.devices.push((start, Box::new(device)));
+        return self;
+    }
+
+    pub fn jit(mut self, threshold: u32) -> Self {
+        self.jit_threshold = Some(threshold);
+        return self;
+    }
+
+    /// Stop execution after `limit` steps, see `Executor::set_step_limit`
+    pub fn step_limit(mut self, limit: u64) -> Self {
+        self.step_limit = Some(limit);
+        return self;
+    }
+
+    /// Write a core dump to `path` when the program fails in `execute` or `call`, see
+    /// `Executor::set_core_file`
+    pub fn core_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.core_file = Some(path.into());
+        return self;
+    }
+
+    pub fn build(self) -> Result<Executor, BuildError> {
+        let mut executor = match self.program {
+            Some(Program::Image(image)) => Executor::from_image(image),
+            _ => Executor::with_layout(self.layout)?,
+        };
+        for (start, device) in self.devices {
+            executor.memory.map_device(start, device)?;
+        }
+        if let Some(threshold) = self.jit_threshold {
+            executor.enable_jit(threshold)?;
+        }
+        executor.set_step_limit(self.step_limit);
+        executor.set_core_file(self.core_file);
+        let sin = match self.program {
+            Some(Program::Sin(sin)) => Some(sin),
+            Some(Program::Bytes(bytes)) => Some(Sin::from_bytes(bytes)?),
+            Some(Program::Image(_)) => None,
+            None => return Ok(executor),
+        };
+        if let Some(sin) = sin {
+            if self.verify {
+                Verifier::new(&sin).verify().map_err(BuildError::Verify)?;
+            }
+            for section in sin.sections() {
+                executor.load_section(section, sin.data())?;
+            }
+        }
+        let entry = executor
+            .section_manager
+            .get_section(&self.entry)
+            .ok_or(BuildError::EntryNotFound(self.entry.clone()))?;
+        if entry.section_type() != SectionType::Procedure {
+            return Err(BuildError::EntryNotProcedure(self.entry));
+        }
+        let entry = entry.mem_start();
+        executor.register.set_ip(entry);
+        return Ok(executor);
+    }
+}
+
+impl Default for ExecutorBuilder<'_> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
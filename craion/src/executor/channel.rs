@@ -0,0 +1,246 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt::Display,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum ChannelError {
+    Closed,
+    InvalidChannel(u64),
+}
+
+impl Display for ChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "Trying to send to a closed channel"),
+            Self::InvalidChannel(id) => {
+                write!(f, "Trying to access channel {} which does not exist", id)
+            }
+        }
+    }
+}
+
+impl Error for ChannelError {}
+
+/// Wakes an executor that waits for a message, each executor owns one
+#[derive(Debug, Default)]
+pub struct Signal {
+    generation: Mutex<u64>,
+    condvar: Condvar,
+}
+
+impl Signal {
+    pub fn generation(&self) -> u64 {
+        return *self.generation.lock().unwrap();
+    }
+
+    pub fn notify(&self) {
+        *self.generation.lock().unwrap() += 1;
+        self.condvar.notify_all();
+    }
+
+    /// Block until `notify` is called after `generation` was read
+    pub fn wait(&self, generation: u64) {
+        let guard = self.generation.lock().unwrap();
+        let _guard = self
+            .condvar
+            .wait_while(guard, |current| *current == generation)
+            .unwrap();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    messages: VecDeque<u64>,
+    closed: bool,
+    /// Executors the channel is registered in and handles held by host code
+    parties: usize,
+    signals: Vec<Arc<Signal>>,
+}
+
+/// A queue of u64 messages shared by guest threads, executors and host code
+#[derive(Debug, Default)]
+pub struct ChannelState {
+    queue: Mutex<Queue>,
+}
+
+impl ChannelState {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        return self.queue.lock().unwrap();
+    }
+
+    fn notify(queue: &Queue) {
+        for signal in queue.signals.iter() {
+            signal.notify();
+        }
+    }
+
+    pub fn send(&self, value: u64) -> Result<(), ChannelError> {
+        let mut queue = self.lock();
+        if queue.closed {
+            return Err(ChannelError::Closed);
+        }
+        queue.messages.push_back(value);
+        Self::notify(&queue);
+        return Ok(());
+    }
+
+    /// Returns `Ok(None)` if the channel is open and empty, and `Err(Closed)` once it is closed
+    /// and every message was received
+    pub fn try_recv(&self) -> Result<Option<u64>, ChannelError> {
+        let mut queue = self.lock();
+        return match queue.messages.pop_front() {
+            Some(value) => Ok(Some(value)),
+            None if queue.closed => Err(ChannelError::Closed),
+            None => Ok(None),
+        };
+    }
+
+    pub fn close(&self) {
+        let mut queue = self.lock();
+        queue.closed = true;
+        Self::notify(&queue);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        return self.lock().closed;
+    }
+
+    /// Whether a receive would not block
+    pub fn is_ready(&self) -> bool {
+        let queue = self.lock();
+        return queue.closed || !queue.messages.is_empty();
+    }
+
+    /// Whether anyone other than a single executor can still send or close
+    pub fn is_shared(&self) -> bool {
+        return self.lock().parties > 1;
+    }
+
+    fn join(&self, signal: Option<Arc<Signal>>) {
+        let mut queue = self.lock();
+        queue.parties += 1;
+        queue.signals.extend(signal);
+    }
+
+    fn leave(&self, signal: Option<&Arc<Signal>>) {
+        let mut queue = self.lock();
+        queue.parties -= 1;
+        if let Some(signal) = signal {
+            queue
+                .signals
+                .retain(|registered| !Arc::ptr_eq(registered, signal));
+        }
+        Self::notify(&queue);
+    }
+}
+
+/// Handle to a channel for host code, it can be sent to other threads
+///
+/// # Examples
+///
+/// ```
+/// use craion::executor::Executor;
+/// let mut executor = Executor::new(0xFFFF);
+/// let id = executor.create_channel();
+/// let channel = executor.channel(id).unwrap();
+///
+/// std::thread::spawn(move || channel.send(42).unwrap()).join().unwrap();
+/// assert_eq!(Ok(Some(42)), executor.channel(id).unwrap().try_recv());
+/// ```
+#[derive(Debug)]
+pub struct Channel {
+    state: Arc<ChannelState>,
+}
+
+impl Channel {
+    fn new(state: Arc<ChannelState>) -> Self {
+        state.join(None);
+        return Self { state };
+    }
+
+    pub fn send(&self, value: u64) -> Result<(), ChannelError> {
+        return self.state.send(value);
+    }
+
+    pub fn try_recv(&self) -> Result<Option<u64>, ChannelError> {
+        return self.state.try_recv();
+    }
+
+    pub fn close(&self) {
+        self.state.close();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        return self.state.is_closed();
+    }
+}
+
+impl Clone for Channel {
+    fn clone(&self) -> Self {
+        return Self::new(self.state.clone());
+    }
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        self.state.leave(None);
+    }
+}
+
+/// Channels an executor can reach by id
+#[derive(Debug)]
+pub struct Channels {
+    channels: HashMap<u64, Arc<ChannelState>>,
+    next_id: u64,
+    signal: Arc<Signal>,
+}
+
+impl Channels {
+    pub fn new(signal: Arc<Signal>) -> Self {
+        Self {
+            channels: HashMap::new(),
+            next_id: 0,
+            signal,
+        }
+    }
+
+    pub fn create(&mut self) -> u64 {
+        return self.attach(Arc::new(ChannelState::default()));
+    }
+
+    /// Make a channel created elsewhere reachable from this executor
+    pub fn attach(&mut self, state: Arc<ChannelState>) -> u64 {
+        state.join(Some(self.signal.clone()));
+        let id = self.next_id;
+        self.next_id += 1;
+        self.channels.insert(id, state);
+        return id;
+    }
+
+    pub fn get(&self, id: u64) -> Result<&Arc<ChannelState>, ChannelError> {
+        return self
+            .channels
+            .get(&id)
+            .ok_or(ChannelError::InvalidChannel(id));
+    }
+
+    /// Get a handle to pass to host code
+    pub fn handle(&self, id: u64) -> Result<Channel, ChannelError> {
+        return Ok(Channel::new(self.get(id)?.clone()));
+    }
+
+    pub fn attach_handle(&mut self, channel: &Channel) -> u64 {
+        return self.attach(channel.state.clone());
+    }
+}
+
+impl Drop for Channels {
+    fn drop(&mut self) {
+        for state in self.channels.values() {
+            state.leave(Some(&self.signal));
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use common::sin::{debug_info::DebugInfo, type_info::TypeInfo, Sin};
+
+use crate::{
+    layout::Layout,
+    memory::{Memory, MemorySnapshot, PAGE_SIZE},
+    section_manager::SectionManager,
+};
+
+use super::{Executor, LoadError};
+
+/// A program loaded once for many executors to start from
+///
+/// Executors made from an image share its code and constant pages, a page is copied only
+/// when an executor writes to it. Each executor has its own registers, stack, heap and
+/// threads, and can load more sections without affecting the image. Images can be shared
+/// between threads.
+///
+/// # Examples
+///
+/// ```
+/// use common::register::RegisterType::A64;
+/// use craion::{assembler::Assembler, executor::{image::ProgramImage, Executor}, layout::Layout};
+///
+/// let program = Assembler::new()
+///     .procedure("answer")
+///     .mov_reg_num(A64, 42)
+///     .ret()
+///     .finish()
+///     .unwrap();
+/// let image = ProgramImage::new(Layout::new(0x10000), &program.sin()).unwrap();
+/// for _ in 0..4 {
+///     let mut executor = Executor::from_image(&image);
+///     assert_eq!(executor.call("answer", &[]).unwrap(), 42);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ProgramImage {
+    memory: MemorySnapshot,
+    section_manager: SectionManager,
+    debug_info: Arc<DebugInfo>,
+    types: Arc<TypeInfo>,
+}
+
+impl ProgramImage {
+    /// Load every section of `sin` the way `Executor::load_section` does, the program is not
+    /// verified
+    pub fn new(layout: Layout, sin: &Sin) -> Result<Self, LoadError> {
+        layout.validate()?;
+        let mut executor = Executor::from_layout(layout);
+        for section in sin.sections() {
+            executor.load_section(section, sin.data())?;
+        }
+        return Ok(Self {
+            memory: executor.memory.snapshot(),
+            section_manager: executor.section_manager,
+            debug_info: executor.debug_info,
+            types: executor.state.types,
+        });
+    }
+
+    pub fn layout(&self) -> &Layout {
+        return self.section_manager.layout();
+    }
+
+    pub fn section_manager(&self) -> &SectionManager {
+        return &self.section_manager;
+    }
+
+    pub fn debug_info(&self) -> &DebugInfo {
+        return &self.debug_info;
+    }
+
+    pub fn types(&self) -> &TypeInfo {
+        return &self.types;
+    }
+
+    /// Bytes of memory the image holds, executors made from it only add the pages they write
+    pub fn size(&self) -> usize {
+        return self.memory.allocated_pages() * PAGE_SIZE;
+    }
+}
+
+impl Executor {
+    /// Create an executor with the layout and the loaded sections of `image`, nothing is
+    /// copied until the executor writes to a page of the image
+    pub fn from_image(image: &ProgramImage) -> Self {
+        let mut executor = Self::from_layout(*image.layout());
+        executor.memory = Memory::from(&image.memory);
+        executor.section_manager = image.section_manager.clone();
+        executor.debug_info = image.debug_info.clone();
+        executor.state.types = image.types.clone();
+        return executor;
+    }
+}
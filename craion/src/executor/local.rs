@@ -0,0 +1,214 @@
+use std::{error::Error, fmt::Display};
+
+use common::{no_hash_hashmap::NoHashHashMap, sin::type_info::TypeInfo};
+use xxhash_rust::const_xxh3::xxh3_64;
+
+/// Type of the values loaded from arguments, and the type to initialize a scalar local with
+pub const U64_TYPE: u64 = xxh3_64(b"u64");
+
+#[derive(Debug, PartialEq)]
+pub enum TypeError {
+    /// The procedure accessed a local without setting up its locals with `local`
+    NoLocals,
+    /// The index and the number of locals
+    OutOfRange(u16, usize),
+    Uninitialized(u16),
+    /// The local already has the type, clear it first
+    Initialized(u16, u64),
+    Mismatch {
+        expected: u64,
+        found: u64,
+    },
+    /// A struct of the type is used where a u64 is expected
+    NotScalar(u64),
+    /// A field is set on a scalar
+    NotStruct(u64),
+    /// The type has a layout without the field
+    UndefinedField {
+        ty: u64,
+        field: u64,
+    },
+}
+
+impl Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoLocals => write!(f, "Trying to access a local variable before `local`"),
+            Self::OutOfRange(index, count) => write!(
+                f,
+                "Trying to access local {} but only {} locals exist",
+                index, count
+            ),
+            Self::Uninitialized(index) => {
+                write!(f, "Trying to use local {} which has no type", index)
+            }
+            Self::Initialized(index, ty) => write!(
+                f,
+                "Trying to initialize local {} which already has type {:#018x}",
+                index, ty
+            ),
+            Self::Mismatch { expected, found } => write!(
+                f,
+                "Type mismatch, expected {:#018x} found {:#018x}",
+                expected, found
+            ),
+            Self::NotScalar(ty) => write!(f, "Expected a u64 found a {:#018x}", ty),
+            Self::NotStruct(ty) => write!(f, "Trying to set a field of a {:#018x}", ty),
+            Self::UndefinedField { ty, field } => {
+                write!(f, "Type {:#018x} has no field {:#018x}", ty, field)
+            }
+        }
+    }
+}
+
+impl Error for TypeError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocalValue {
+    Scalar(u64),
+    /// Fields by field hash, a field gets its type when it is first set
+    Fields(NoHashHashMap<u64, Local>),
+}
+
+/// A value in a local variable slot and its type hash
+#[derive(Debug, Clone, PartialEq)]
+pub struct Local {
+    ty: u64,
+    value: LocalValue,
+}
+
+impl Local {
+    /// A zero for `U64_TYPE`, a struct without fields otherwise
+    pub fn new(ty: u64) -> Self {
+        let value = match ty {
+            U64_TYPE => LocalValue::Scalar(0),
+            _ => LocalValue::Fields(NoHashHashMap::default()),
+        };
+        return Self { ty, value };
+    }
+
+    pub fn scalar(value: u64) -> Self {
+        return Self {
+            ty: U64_TYPE,
+            value: LocalValue::Scalar(value),
+        };
+    }
+
+    pub fn type_hash(&self) -> u64 {
+        return self.ty;
+    }
+
+    pub fn value(&self) -> &LocalValue {
+        return &self.value;
+    }
+
+    pub fn as_scalar(&self) -> Result<u64, TypeError> {
+        return match self.value {
+            LocalValue::Scalar(value) => Ok(value),
+            LocalValue::Fields(_) => Err(TypeError::NotScalar(self.ty)),
+        };
+    }
+
+    pub fn field(&self, field: u64) -> Option<&Local> {
+        return match &self.value {
+            LocalValue::Fields(fields) => fields.get(&field),
+            LocalValue::Scalar(_) => None,
+        };
+    }
+
+    /// Set a field, a field that was set before keeps its type
+    fn set_field(&mut self, field: u64, value: Local) -> Result<(), TypeError> {
+        let LocalValue::Fields(fields) = &mut self.value else {
+            return Err(TypeError::NotStruct(self.ty));
+        };
+        if let Some(old) = fields.get(&field) {
+            if old.ty != value.ty {
+                return Err(TypeError::Mismatch {
+                    expected: old.ty,
+                    found: value.ty,
+                });
+            }
+        }
+        fields.insert(field, value);
+        return Ok(());
+    }
+}
+
+/// The local variable slots of one procedure call
+#[derive(Debug, Clone, Default)]
+pub struct Locals {
+    slots: Vec<Option<Local>>,
+}
+
+impl Locals {
+    pub fn new(size: u16) -> Self {
+        return Self {
+            slots: vec![None; size as usize],
+        };
+    }
+
+    pub fn slots(&self) -> &[Option<Local>] {
+        return &self.slots;
+    }
+
+    fn slot(&mut self, index: u16) -> Result<&mut Option<Local>, TypeError> {
+        let count = self.slots.len();
+        return self
+            .slots
+            .get_mut(index as usize)
+            .ok_or(TypeError::OutOfRange(index, count));
+    }
+
+    pub fn get(&self, index: u16) -> Result<&Local, TypeError> {
+        return self
+            .slots
+            .get(index as usize)
+            .ok_or(TypeError::OutOfRange(index, self.slots.len()))?
+            .as_ref()
+            .ok_or(TypeError::Uninitialized(index));
+    }
+
+    /// Put a value in a local that has no type
+    pub fn initialize(&mut self, index: u16, local: Local) -> Result<(), TypeError> {
+        let slot = self.slot(index)?;
+        if let Some(old) = slot {
+            return Err(TypeError::Initialized(index, old.ty));
+        }
+        *slot = Some(local);
+        return Ok(());
+    }
+
+    pub fn clear(&mut self, index: u16) -> Result<(), TypeError> {
+        *self.slot(index)? = None;
+        return Ok(());
+    }
+
+    /// Copy local `source` into a field of local `target`, fields of types with a layout in
+    /// `types` must exist and have the type of the source
+    pub fn set_field(
+        &mut self,
+        types: &TypeInfo,
+        target: u16,
+        field: u64,
+        source: u16,
+    ) -> Result<(), TypeError> {
+        let value = self.get(source)?.clone();
+        let ty = self.get(target)?.ty;
+        if let Some(layout) = types.get(ty) {
+            let declared = layout
+                .field(field)
+                .ok_or(TypeError::UndefinedField { ty, field })?;
+            if declared.type_hash() != value.ty {
+                return Err(TypeError::Mismatch {
+                    expected: declared.type_hash(),
+                    found: value.ty,
+                });
+            }
+        }
+        return self
+            .slot(target)?
+            .as_mut()
+            .ok_or(TypeError::Uninitialized(target))?
+            .set_field(field, value);
+    }
+}
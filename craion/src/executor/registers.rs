@@ -6,7 +6,7 @@ use crate::memory::address::Address;
 
 use self::flags::Flags;
 
-mod flags;
+pub mod flags;
 
 /// Simple register file
 ///
@@ -83,15 +83,15 @@ impl RegisterFile {
     }
 
     pub fn save_b_register(&mut self) {
-        self.saved_a_register.push(self.b);
+        self.saved_b_register.push(self.b);
     }
 
     pub fn save_c_register(&mut self) {
-        self.saved_a_register.push(self.c);
+        self.saved_c_register.push(self.c);
     }
 
     pub fn save_d_register(&mut self) {
-        self.saved_a_register.push(self.d);
+        self.saved_d_register.push(self.d);
     }
 
     pub fn restore_a_register(&mut self) {
@@ -110,6 +110,17 @@ impl RegisterFile {
         self.d = self.saved_d_register.pop().unwrap_or(0);
     }
 
+    /// Values `savr` pushed for the register group, the last one is restored first
+    pub fn saved_registers(&self, group: &RegisterTypeGroup) -> &[u64] {
+        return match group {
+            RegisterTypeGroup::A => &self.saved_a_register,
+            RegisterTypeGroup::B => &self.saved_b_register,
+            RegisterTypeGroup::C => &self.saved_c_register,
+            RegisterTypeGroup::D => &self.saved_d_register,
+            _ => &[],
+        };
+    }
+
     pub fn set_sp(&mut self, data: Address) {
         self.sp = data;
     }
@@ -0,0 +1,299 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt::Display,
+    sync::Arc,
+};
+
+use crate::{layout::Layout, memory::address::Address, ret_stack::RetStack};
+
+use super::{
+    channel::{ChannelState, Signal},
+    local::Locals,
+    registers::RegisterFile,
+};
+
+/// Id of the thread the program starts on
+pub const MAIN_THREAD: u64 = 0;
+
+#[derive(Debug)]
+pub enum ThreadError {
+    /// Every stack slot is taken
+    NoStackSlot,
+    InvalidThread(u64),
+    /// Every thread is waiting on a join or a receive that can't finish
+    Deadlock,
+    /// The thread moved sp out of its stack slot
+    StackOverflow(u64),
+}
+
+impl Display for ThreadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoStackSlot => write!(f, "Trying to spawn a thread with every stack slot taken"),
+            Self::InvalidThread(id) => {
+                write!(f, "Trying to join thread {} which does not exist", id)
+            }
+            Self::Deadlock => write!(
+                f,
+                "Deadlock, every thread is waiting on a join or a receive"
+            ),
+            Self::StackOverflow(id) => {
+                write!(f, "Thread {} moved its stack out of its stack slot", id)
+            }
+        }
+    }
+}
+
+impl Error for ThreadError {}
+
+/// Per thread state of `enter`/`leave`, procedure arguments and typed locals
+#[derive(Debug, Default)]
+pub struct CallFrames {
+    pub(super) stack_saved_size: Vec<u64>,
+    pub(super) pending_arguments: HashMap<u32, u64>,
+    pub(super) argument_frames: Vec<HashMap<u32, u64>>,
+    /// Locals with the number of argument frames of the call that set them up
+    pub(super) locals: Vec<(usize, Locals)>,
+}
+
+/// What the current thread asked the scheduler for, handled after the instruction
+#[derive(Debug, Clone)]
+pub enum ThreadRequest {
+    Yield,
+    /// Wait until the thread finishes, the join is executed again once it does
+    Join(u64),
+    /// Wait until the channel has a message or is closed, the receive is executed again then
+    Recv(Arc<ChannelState>),
+    /// The thread returned from its procedure with a value
+    Finish(u64),
+}
+
+/// A thread that is not running
+#[derive(Debug)]
+struct Thread {
+    id: u64,
+    slot: usize,
+    register: RegisterFile,
+    ret_stack: RetStack,
+    frames: CallFrames,
+}
+
+/// Round robin scheduler of the guest threads
+///
+/// Only the running thread lives in the executor, the others are parked here. Each thread
+/// gets a slot of the stack region, the main thread uses the top one. A thread running alone
+/// may use the whole region, once others are live every thread has to stay in its slot
+#[derive(Debug)]
+pub struct Threads {
+    current: u64,
+    current_slot: usize,
+    next_id: u64,
+    ready: VecDeque<Thread>,
+    /// Threads waiting on a join or a receive and what they wait on
+    blocked: Vec<(Thread, ThreadRequest)>,
+    /// Return values of threads that finished and were not joined yet
+    finished: HashMap<u64, u64>,
+    slots: Vec<bool>,
+    stack_top: Address,
+    slot_size: usize,
+    request: Option<ThreadRequest>,
+    signal: Arc<Signal>,
+}
+
+impl Threads {
+    pub fn new(layout: &Layout, signal: Arc<Signal>) -> Self {
+        let count = layout
+            .stack_size()
+            .checked_div(layout.thread_stack_size())
+            .unwrap_or(0)
+            .max(1);
+        let mut slots = vec![false; count];
+        slots[0] = true;
+        Self {
+            current: MAIN_THREAD,
+            current_slot: 0,
+            next_id: MAIN_THREAD + 1,
+            ready: VecDeque::new(),
+            blocked: Vec::new(),
+            finished: HashMap::new(),
+            slots,
+            stack_top: layout.stack_top(),
+            slot_size: layout.thread_stack_size(),
+            request: None,
+            signal,
+        }
+    }
+
+    /// Id of the running thread
+    pub fn current(&self) -> u64 {
+        return self.current;
+    }
+
+    /// Number of threads that have not finished, including the running one
+    pub fn live(&self) -> usize {
+        return 1 + self.ready.len() + self.blocked.len();
+    }
+
+    /// Ips and return addresses of the threads that are not running
+    pub fn code_addresses(&self) -> impl Iterator<Item = Address> + '_ {
+        return self
+            .ready
+            .iter()
+            .chain(self.blocked.iter().map(|(thread, _)| thread))
+            .flat_map(|thread| {
+                [thread.register.get_ip()]
+                    .into_iter()
+                    .chain(thread.ret_stack.frames())
+            });
+    }
+
+    fn is_live(&self, id: u64) -> bool {
+        return id == self.current
+            || self.ready.iter().any(|thread| thread.id == id)
+            || self.blocked.iter().any(|(thread, _)| thread.id == id);
+    }
+
+    /// Check that `sp` of the running thread is in its slot, a thread running alone is not
+    /// bounded
+    pub fn check_stack(&self, sp: Address) -> Result<(), ThreadError> {
+        if self.live() == 1 {
+            return Ok(());
+        }
+        return self.check_slot(sp);
+    }
+
+    fn check_slot(&self, sp: Address) -> Result<(), ThreadError> {
+        let top = self.stack_top.get_raw() - self.current_slot * self.slot_size;
+        if sp.get_raw() > top || sp.get_raw() < top - self.slot_size {
+            return Err(ThreadError::StackOverflow(self.current));
+        }
+        return Ok(());
+    }
+
+    /// Create a thread starting at `ip` and queue it after the others, returns its id. `sp`
+    /// of the running thread has to be in its slot, the new thread would overwrite it otherwise
+    pub fn spawn(
+        &mut self,
+        ip: Address,
+        arguments: HashMap<u32, u64>,
+        sp: Address,
+    ) -> Result<u64, ThreadError> {
+        self.check_slot(sp)?;
+        let slot = self
+            .slots
+            .iter()
+            .position(|used| !used)
+            .ok_or(ThreadError::NoStackSlot)?;
+        self.slots[slot] = true;
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut register = RegisterFile::new();
+        register.set_ip(ip);
+        register.set_sp(self.stack_top - slot * self.slot_size);
+        self.ready.push_back(Thread {
+            id,
+            slot,
+            register,
+            ret_stack: RetStack::new(),
+            frames: CallFrames {
+                argument_frames: vec![arguments],
+                ..Default::default()
+            },
+        });
+        return Ok(id);
+    }
+
+    /// Take the return value of a finished thread, `None` if it is still running
+    pub fn join(&mut self, id: u64) -> Result<Option<u64>, ThreadError> {
+        if let Some(value) = self.finished.remove(&id) {
+            return Ok(Some(value));
+        }
+        if !self.is_live(id) {
+            return Err(ThreadError::InvalidThread(id));
+        }
+        return Ok(None);
+    }
+
+    pub fn request(&mut self, request: ThreadRequest) {
+        self.request = Some(request);
+    }
+
+    pub fn take_request(&mut self) -> Option<ThreadRequest> {
+        return self.request.take();
+    }
+
+    /// Move the threads whose channel got a message or was closed to the ready queue
+    fn wake_receivers(&mut self) {
+        let (woken, blocked): (Vec<_>, Vec<_>) = std::mem::take(&mut self.blocked)
+            .into_iter()
+            .partition(|(_, waiting)| match waiting {
+                ThreadRequest::Recv(channel) => channel.is_ready(),
+                _ => false,
+            });
+        self.blocked = blocked;
+        self.ready
+            .extend(woken.into_iter().map(|(thread, _)| thread));
+    }
+
+    /// Whether host code or another executor could still wake a thread waiting on a receive
+    fn can_wake(&self, request: &ThreadRequest) -> bool {
+        return self
+            .blocked
+            .iter()
+            .map(|(_, waiting)| waiting)
+            .chain([request])
+            .any(|waiting| match waiting {
+                ThreadRequest::Recv(channel) => channel.is_shared(),
+                _ => false,
+            });
+    }
+
+    /// Switch threads for `request`, the running thread's state is swapped with the state of
+    /// the next thread in line. A yield without other threads to run keeps the current one.
+    /// When every thread waits on a receive this blocks until a message arrives from outside
+    pub fn schedule(
+        &mut self,
+        request: ThreadRequest,
+        register: &mut RegisterFile,
+        ret_stack: &mut RetStack,
+        frames: &mut CallFrames,
+    ) -> Result<(), ThreadError> {
+        if let ThreadRequest::Finish(value) = request {
+            self.finished.insert(self.current, value);
+            self.slots[self.current_slot] = false;
+            let current = self.current;
+            let (woken, blocked): (Vec<_>, Vec<_>) =
+                std::mem::take(&mut self.blocked).into_iter().partition(
+                    |(_, waiting)| matches!(waiting, ThreadRequest::Join(id) if *id == current),
+                );
+            self.blocked = blocked;
+            self.ready
+                .extend(woken.into_iter().map(|(thread, _)| thread));
+        }
+        let mut thread = loop {
+            let generation = self.signal.generation();
+            self.wake_receivers();
+            if let Some(next) = self.ready.pop_front() {
+                break next;
+            }
+            match &request {
+                ThreadRequest::Yield => return Ok(()),
+                ThreadRequest::Recv(channel) if channel.is_ready() => return Ok(()),
+                _ if self.can_wake(&request) => self.signal.wait(generation),
+                _ => return Err(ThreadError::Deadlock),
+            }
+        };
+        std::mem::swap(register, &mut thread.register);
+        std::mem::swap(ret_stack, &mut thread.ret_stack);
+        std::mem::swap(frames, &mut thread.frames);
+        std::mem::swap(&mut self.current, &mut thread.id);
+        std::mem::swap(&mut self.current_slot, &mut thread.slot);
+        match request {
+            ThreadRequest::Yield => self.ready.push_back(thread),
+            ThreadRequest::Finish(_) => {}
+            waiting => self.blocked.push((thread, waiting)),
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt::Write;
+
+/// Format `data` as rows of 16 bytes with the offset, hex bytes and printable ascii
+///
+/// # Examples
+///
+/// ```
+/// use craion::hexdump::hexdump;
+///
+/// assert_eq!(
+///     hexdump(b"Hello, world!\n\0\x01raion"),
+///     "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 01  |Hello, world!...|\n\
+///      00000010  72 61 69 6f 6e                                    |raion|\n"
+/// );
+/// assert_eq!(hexdump(&[]), "");
+/// ```
+pub fn hexdump(data: &[u8]) -> String {
+    let mut output = String::new();
+    for (row, chunk) in data.chunks(16).enumerate() {
+        let _ = write!(output, "{:08x}  ", row * 16);
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(byte) => {
+                    let _ = write!(output, "{:02x} ", byte);
+                }
+                None => output.push_str("   "),
+            }
+            if i == 7 {
+                output.push(' ');
+            }
+        }
+        output.push_str(" |");
+        for byte in chunk {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                output.push(*byte as char);
+            } else {
+                output.push('.');
+            }
+        }
+        output.push_str("|\n");
+    }
+    return output;
+}
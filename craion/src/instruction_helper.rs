@@ -1,4 +1,7 @@
-use common::{constants::HALT_OPCODE, register::RegisterType};
+use common::{
+    constants::HALT_OPCODE, encoding::Encode, memory::buffer_writer::BufferWriter,
+    register::RegisterType,
+};
 
 use crate::memory::{address::Address, Memory};
 
@@ -10,7 +13,7 @@ pub struct InstructionHelper<'a> {
 pub struct InstructionEncoder<'a> {
     opcode: u16,
     instruction_helper: InstructionHelper<'a>,
-    args: Vec<u8>,
+    args: BufferWriter,
 }
 
 impl<'a> InstructionEncoder<'a> {
@@ -18,55 +21,51 @@ impl<'a> InstructionEncoder<'a> {
         Self {
             opcode,
             instruction_helper,
-            args: Vec::new(),
+            args: BufferWriter::new(),
         }
     }
 
     pub fn encode_register(mut self, register: RegisterType) -> Self {
-        self.args.push(register.to_byte());
+        register.encode(&mut self.args);
         self
     }
 
     pub fn encode_sub_opcode(mut self, opcode: u8) -> Self {
-        self.args.push(opcode);
+        self.args.write_u8(opcode);
         self
     }
 
     pub fn encode_u64(mut self, value: u64) -> Self {
-        self.args.extend_from_slice(&value.to_le_bytes());
+        self.args.write_u64(value);
         self
     }
 
     pub fn encode_u32(mut self, value: u32) -> Self {
-        self.args.extend_from_slice(&value.to_le_bytes());
+        self.args.write_u32(value);
         self
     }
 
     pub fn encode_u16(mut self, value: u16) -> Self {
-        self.args.extend_from_slice(&value.to_le_bytes());
+        self.args.write_u16(value);
         self
     }
 
     pub fn encode_u8(mut self, value: u8) -> Self {
-        self.args.extend_from_slice(&value.to_le_bytes());
+        self.args.write_u8(value);
         self
     }
 
     pub fn end(mut self) -> InstructionHelper<'a> {
-        let opcode = self.opcode.to_le_bytes();
-        let instruction_size = self.args.len() + 3;
+        let instruction_size = self.args.get_write_pos() + 3;
+        let mut instruction = BufferWriter::new();
+        instruction.write_u8(instruction_size as u8);
+        instruction.write_u16(self.opcode);
+        instruction.write_bytes(self.args.bytes());
         self.instruction_helper
             .memory
             .mem_sets(
                 Address::new(self.instruction_helper.write_pos),
-                &[instruction_size as u8, opcode[0], opcode[1]],
-            )
-            .unwrap();
-        self.instruction_helper
-            .memory
-            .mem_sets(
-                Address::new(self.instruction_helper.write_pos + 3),
-                &self.args,
+                instruction.bytes(),
             )
             .unwrap();
         self.instruction_helper.write_pos += instruction_size;
@@ -0,0 +1,226 @@
+//! Trace compiler for hot code
+//!
+//! A trace is the run of instructions starting at a block head (an entry point, a jump or call
+//! target, or the instruction after one the compiler can't handle), up to and including the
+//! first jump. Once a head has been reached more than `threshold` times its trace is compiled
+//! to native code with Cranelift. Compiled code works on a copy of the register file and on
+//! the memory pages directly, and checks every case that could make the interpreter fail
+//! (oversized values, out of range accesses, division by zero). When a check fails the trace returns before
+//! touching any state, and the interpreter executes that instruction itself, so errors are
+//! reported the same way in both modes.
+//!
+//! A jump back to the start of a trace loops in native code, for at most the number of passes
+//! `enter` is allowed to make.
+//!
+//! Code is assumed not to be modified while it runs, the cache is only cleared when a section
+//! is loaded.
+
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use common::constants::{
+    ADD_OPCODE, CMP_OPCODE, DIV_OPCODE, INC_OPCODE, JACC_OPCODE, JACE_OPCODE, JACN_OPCODE,
+    JACZ_OPCODE, JMC_OPCODE, JME_OPCODE, JMN_OPCODE, JMP_OPCODE, JMZ_OPCODE, MOV_OPCODE,
+    MUL_OPCODE, POP_OPCODE, PUSH_OPCODE, SUB_OPCODE,
+};
+use common::register::RegisterType;
+use cranelift_codegen::{
+    ir::types,
+    settings::{self, Configurable},
+};
+use cranelift_frontend::FunctionBuilderContext;
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::default_libcall_names;
+
+use crate::{
+    executor::registers::{flags::Flags, RegisterFile},
+    memory::{address::Address, Memory},
+    section_manager::SectionManager,
+};
+
+mod translate;
+
+pub const DEFAULT_HOT_THRESHOLD: u32 = 32;
+
+#[derive(Debug)]
+pub enum JitError {
+    UnsupportedHost(String),
+}
+
+impl Display for JitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedHost(reason) => {
+                write!(f, "The jit is not supported on this host: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for JitError {}
+
+/// State shared with compiled code, the layout is relied on by `translate`
+#[repr(C)]
+struct JitContext {
+    registers: [u64; 4],
+    sp: u64,
+    flags: u64,
+    memory: *mut Memory,
+    /// Jumps back to the start of the trace left before it has to return
+    loops: u64,
+}
+
+/// Called by compiled code for every load and store, returns null when the access has to go
+/// through the interpreter (devices, watchpoints, accesses out of range or across pages)
+extern "C" fn direct_access(memory: *mut Memory, address: u64, size: u64, write: u64) -> *mut u8 {
+    // SAFETY: `memory` is the pointer `enter` put in the context, which outlives the trace
+    let memory = unsafe { &mut *memory };
+    return memory
+        .direct(Address::new(address as usize), size as usize, write != 0)
+        .unwrap_or(std::ptr::null_mut());
+}
+
+/// Returns the address of the next instruction to interpret
+type CompiledTrace = unsafe extern "C" fn(*mut JitContext) -> u64;
+
+pub struct Jit {
+    module: JITModule,
+    function_context: FunctionBuilderContext,
+    threshold: u32,
+    counters: HashMap<usize, u32>,
+    traces: HashMap<usize, Option<CompiledTrace>>,
+}
+
+impl Jit {
+    pub fn new(threshold: u32) -> Result<Self, JitError> {
+        let mut flags = settings::builder();
+        flags
+            .set("opt_level", "speed")
+            .map_err(|e| JitError::UnsupportedHost(e.to_string()))?;
+        let isa = cranelift_native::builder()
+            .map_err(|e| JitError::UnsupportedHost(e.to_string()))?
+            .finish(settings::Flags::new(flags))
+            .map_err(|e| JitError::UnsupportedHost(e.to_string()))?;
+        if isa.pointer_type() != types::I64 {
+            return Err(JitError::UnsupportedHost(
+                "only 64 bit hosts are supported".to_string(),
+            ));
+        }
+        return Ok(Self {
+            module: JITModule::new(JITBuilder::with_isa(isa, default_libcall_names())),
+            function_context: FunctionBuilderContext::new(),
+            threshold,
+            counters: HashMap::new(),
+            traces: HashMap::new(),
+        });
+    }
+
+    pub fn threshold(&self) -> u32 {
+        return self.threshold;
+    }
+
+    /// Number of compiled traces
+    pub fn compiled(&self) -> usize {
+        return self.traces.values().filter(|trace| trace.is_some()).count();
+    }
+
+    /// Forget every trace, needed when the code they were compiled from changes
+    pub fn invalidate(&mut self) {
+        self.counters.clear();
+        self.traces.clear();
+    }
+
+    /// Whether the interpreter should treat the instruction after `opcode` as a block head
+    pub fn ends_block(opcode: u16) -> bool {
+        return !matches!(
+            opcode,
+            MOV_OPCODE
+                | PUSH_OPCODE
+                | POP_OPCODE
+                | INC_OPCODE
+                | CMP_OPCODE
+                | ADD_OPCODE
+                | SUB_OPCODE
+                | MUL_OPCODE
+                | DIV_OPCODE
+        ) || matches!(
+            opcode,
+            JMP_OPCODE
+                | JMZ_OPCODE
+                | JMN_OPCODE
+                | JACN_OPCODE
+                | JACZ_OPCODE
+                | JACC_OPCODE
+                | JACE_OPCODE
+                | JME_OPCODE
+                | JMC_OPCODE
+        );
+    }
+
+    /// Run the trace starting at the current ip if it is hot, making at most `passes` passes
+    /// through it
+    ///
+    /// Returns the number of passes made, None when no trace ran. The interpreter has to
+    /// execute the instruction at ip itself if ip didn't change
+    pub fn enter(
+        &mut self,
+        register: &mut RegisterFile,
+        memory: &mut Memory,
+        section_manager: &SectionManager,
+        passes: u64,
+    ) -> Option<u64> {
+        let ip = register.get_ip().get_raw();
+        if passes == 0 {
+            return None;
+        }
+        let trace = match self.traces.get(&ip) {
+            Some(Some(trace)) => *trace,
+            Some(None) => return None,
+            None => {
+                let count = self.counters.entry(ip).or_insert(0);
+                *count += 1;
+                if *count <= self.threshold {
+                    return None;
+                }
+                self.counters.remove(&ip);
+                let trace = translate::compile(
+                    &mut self.module,
+                    &mut self.function_context,
+                    memory,
+                    section_manager,
+                    ip,
+                );
+                self.traces.insert(ip, trace);
+                trace?
+            }
+        };
+        let mut context = JitContext {
+            registers: [
+                register.get_general(&RegisterType::A64).unwrap_or(0),
+                register.get_general(&RegisterType::B64).unwrap_or(0),
+                register.get_general(&RegisterType::C64).unwrap_or(0),
+                register.get_general(&RegisterType::D64).unwrap_or(0),
+            ],
+            sp: register.get_sp().get_raw() as u64,
+            flags: register.get_flags().bits().into(),
+            memory,
+            loops: passes - 1,
+        };
+        // SAFETY: the trace only accesses `context` and pointers handed out by `direct_access`
+        let next = unsafe { trace(&mut context) };
+        for (register_type, value) in [
+            RegisterType::A64,
+            RegisterType::B64,
+            RegisterType::C64,
+            RegisterType::D64,
+        ]
+        .iter()
+        .zip(context.registers)
+        {
+            let _ = register.set_general(register_type, value);
+        }
+        register.set_sp(Address::new(context.sp as usize));
+        register.set_flags(Flags::from_bits_retain(context.flags as u16));
+        register.set_ip(Address::new(next as usize));
+        return Some(passes - context.loops);
+    }
+}
@@ -0,0 +1,806 @@
+use std::mem::offset_of;
+
+use common::{
+    constants::{
+        ADD_OPCODE, ADD_REG_W_NUM, ADD_REG_W_REG, ADD_SP_W_NUM, CMP_OPCODE, DIV_OPCODE, INC_OPCODE,
+        JACC_OPCODE, JACE_OPCODE, JACN_OPCODE, JACZ_OPCODE, JMC_OPCODE, JME_OPCODE, JMN_OPCODE,
+        JMP_OPCODE, JMZ_OPCODE, MOV_ADD2SP, MOV_DEREF_REG2REG, MOV_DEREF_REG_WITH_OFFSET2REG,
+        MOV_NUM2DEREF_REG, MOV_NUM2DEREF_REG_WITH_OFFSET, MOV_NUM2REG, MOV_OPCODE,
+        MOV_REG2DEREF_REG, MOV_REG2DEREF_REG_WITH_OFFSET, MOV_REG2REG, MOV_REG2SP, MUL_OPCODE,
+        POP_OPCODE, PUSH_OPCODE, SUB_OPCODE, SUB_REG_W_NUM, SUB_REG_W_REG, SUB_SP_W_NUM,
+    },
+    isa::decoded::Decoded,
+    register::{RegisterSizes, RegisterType, RegisterTypeGroup},
+};
+use cranelift_codegen::ir::{
+    condcodes::IntCC, types, AbiParam, Block, Endianness, InstBuilder, MemFlags, SigRef, Signature,
+    Value,
+};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::JITModule;
+use cranelift_module::Module;
+
+use crate::{decoder::argument::Argument, memory::Memory, section_manager::SectionManager};
+
+use super::{direct_access, CompiledTrace, JitContext};
+
+const MAX_TRACE_LENGTH: usize = 256;
+
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Register(RegisterType),
+    Immediate(u64),
+}
+
+/// `base + offset` or `base - offset`, base is a general register or sp
+#[derive(Debug, Clone, Copy)]
+struct Deref {
+    base: RegisterType,
+    offset: u64,
+    is_add: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Arithmetic {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Condition {
+    Always,
+    Zero,
+    Negative,
+    Carry,
+    NoFlags,
+    CompareCarry(RegisterType, RegisterType),
+    CompareAbove(RegisterType, RegisterType),
+    CompareNegative(RegisterType, RegisterType),
+    CompareZero(RegisterType, RegisterType),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    /// Clears the whole register group before writing, like `mov reg, reg`
+    Move(RegisterType, Operand),
+    MoveSp(Operand),
+    Load(RegisterType, Deref, usize),
+    Store(Deref, Operand, usize),
+    Arithmetic(Arithmetic, RegisterType, Operand),
+    ArithmeticSp(Arithmetic, u64),
+    Inc(RegisterType),
+    Cmp(RegisterType, RegisterType),
+    Push(RegisterType),
+    Pop(RegisterType),
+    Jump(Condition, usize),
+}
+
+fn general(register: RegisterType) -> Option<RegisterType> {
+    return match register {
+        RegisterType::Ip | RegisterType::Sp | RegisterType::Flags => None,
+        register => Some(register),
+    };
+}
+
+fn general_register(argument: &mut Argument) -> Option<RegisterType> {
+    return general(argument.parse_register().ok()?);
+}
+
+fn deref(argument: &mut Argument) -> Option<Deref> {
+    let (base, offset, is_add) = argument.parse_deref_offset().ok()?;
+    if base != RegisterType::Sp {
+        general(base)?;
+    }
+    return Some(Deref {
+        base,
+        offset: offset.into(),
+        is_add,
+    });
+}
+
+fn deref_register(argument: &mut Argument) -> Option<RegisterType> {
+    return general(argument.parse_deref().ok()?);
+}
+
+fn jump_target(
+    ip: usize,
+    argument: &mut Argument,
+    section_manager: &SectionManager,
+) -> Option<usize> {
+    let (section, offset) = argument.parse_jump().ok()?;
+    let section = section_manager.jump_section(ip.into(), section)?;
+    return Some(section.mem_start().get_raw() + offset as usize);
+}
+
+fn compare(argument: &mut Argument) -> Option<(RegisterType, RegisterType)> {
+    return Some((general_register(argument)?, general_register(argument)?));
+}
+
+/// `add` and `sub` share a layout, `sub_opcodes` is reg with reg, reg with num, sp with num
+fn add_sub(arithmetic: Arithmetic, sub_opcodes: [u8; 3], argument: &mut Argument) -> Option<Op> {
+    let sub_opcode = argument.parse_sub_opcode().ok()?;
+    if sub_opcode == sub_opcodes[0] {
+        let dst = general_register(argument)?;
+        return Some(Op::Arithmetic(
+            arithmetic,
+            dst,
+            Operand::Register(general_register(argument)?),
+        ));
+    } else if sub_opcode == sub_opcodes[1] {
+        let dst = general_register(argument)?;
+        let value = argument.parse_u64().ok()?;
+        return Some(Op::Arithmetic(arithmetic, dst, Operand::Immediate(value)));
+    } else if sub_opcode == sub_opcodes[2] {
+        argument.parse_register().ok()?;
+        return Some(Op::ArithmeticSp(arithmetic, argument.parse_u64().ok()?));
+    }
+    return None;
+}
+
+/// Decode an instruction into an operation the translator supports, operands are read in the
+/// same order as the interpreter so anything that would fail there is left to it
+fn decode(
+    ip: usize,
+    opcode: u16,
+    argument: &mut Argument,
+    section_manager: &SectionManager,
+) -> Option<Op> {
+    let op = match opcode {
+        MOV_OPCODE => match argument.parse_sub_opcode().ok()? {
+            MOV_REG2REG => {
+                let dst = general_register(argument)?;
+                Op::Move(dst, Operand::Register(general_register(argument)?))
+            }
+            MOV_NUM2REG => {
+                let dst = general_register(argument)?;
+                Op::Move(dst, Operand::Immediate(argument.parse_immediate().ok()?))
+            }
+            MOV_ADD2SP => {
+                argument.parse_register().ok()?;
+                Op::MoveSp(Operand::Immediate(argument.parse_u64().ok()?))
+            }
+            MOV_REG2SP => {
+                argument.parse_register().ok()?;
+                Op::MoveSp(Operand::Register(general_register(argument)?))
+            }
+            MOV_REG2DEREF_REG => {
+                let value = general_register(argument)?;
+                let base = deref_register(argument)?;
+                let deref = Deref {
+                    base,
+                    offset: 0,
+                    is_add: true,
+                };
+                Op::Store(deref, Operand::Register(value), value.size().byte())
+            }
+            MOV_DEREF_REG2REG => {
+                let dst = general_register(argument)?;
+                let base = deref_register(argument)?;
+                let deref = Deref {
+                    base,
+                    offset: 0,
+                    is_add: true,
+                };
+                Op::Load(dst, deref, dst.size().byte())
+            }
+            MOV_NUM2DEREF_REG => {
+                let base = deref_register(argument)?;
+                let deref = Deref {
+                    base,
+                    offset: 0,
+                    is_add: true,
+                };
+                Op::Store(deref, Operand::Immediate(argument.parse_u64().ok()?), 8)
+            }
+            MOV_NUM2DEREF_REG_WITH_OFFSET => {
+                let deref = deref(argument)?;
+                Op::Store(deref, Operand::Immediate(argument.parse_u64().ok()?), 8)
+            }
+            MOV_REG2DEREF_REG_WITH_OFFSET => {
+                let deref = deref(argument)?;
+                let value = argument.parse_register().ok()?;
+                if value != RegisterType::Sp {
+                    general(value)?;
+                }
+                Op::Store(deref, Operand::Register(value), value.size().byte())
+            }
+            MOV_DEREF_REG_WITH_OFFSET2REG => {
+                let dst = general_register(argument)?;
+                Op::Load(dst, deref(argument)?, dst.size().byte())
+            }
+            _ => return None,
+        },
+        ADD_OPCODE => add_sub(
+            Arithmetic::Add,
+            [ADD_REG_W_REG, ADD_REG_W_NUM, ADD_SP_W_NUM],
+            argument,
+        )?,
+        SUB_OPCODE => add_sub(
+            Arithmetic::Sub,
+            [SUB_REG_W_REG, SUB_REG_W_NUM, SUB_SP_W_NUM],
+            argument,
+        )?,
+        MUL_OPCODE | DIV_OPCODE => {
+            let arithmetic = if opcode == MUL_OPCODE {
+                Arithmetic::Mul
+            } else {
+                Arithmetic::Div
+            };
+            let dst = general_register(argument)?;
+            Op::Arithmetic(
+                arithmetic,
+                dst,
+                Operand::Register(general_register(argument)?),
+            )
+        }
+        INC_OPCODE => Op::Inc(general_register(argument)?),
+        CMP_OPCODE => {
+            let (a, b) = compare(argument)?;
+            Op::Cmp(a, b)
+        }
+        PUSH_OPCODE => Op::Push(general_register(argument)?),
+        POP_OPCODE => Op::Pop(general_register(argument)?),
+        JMP_OPCODE | JMZ_OPCODE | JMN_OPCODE | JME_OPCODE | JMC_OPCODE => {
+            let condition = match opcode {
+                JMP_OPCODE => Condition::Always,
+                JMZ_OPCODE => Condition::Zero,
+                JMN_OPCODE => Condition::Negative,
+                JME_OPCODE => Condition::NoFlags,
+                _ => Condition::Carry,
+            };
+            Op::Jump(condition, jump_target(ip, argument, section_manager)?)
+        }
+        JACC_OPCODE | JACE_OPCODE | JACN_OPCODE | JACZ_OPCODE => {
+            let (a, b) = compare(argument)?;
+            let condition = match opcode {
+                JACC_OPCODE => Condition::CompareCarry(a, b),
+                JACE_OPCODE => Condition::CompareAbove(a, b),
+                JACN_OPCODE => Condition::CompareNegative(a, b),
+                _ => Condition::CompareZero(a, b),
+            };
+            Op::Jump(condition, jump_target(ip, argument, section_manager)?)
+        }
+        _ => return None,
+    };
+    return Some(op);
+}
+
+/// Decode the longest supported run of instructions starting at `start`
+fn decode_trace(
+    memory: &Memory,
+    section_manager: &SectionManager,
+    start: usize,
+) -> Vec<(usize, usize, Op)> {
+    let mut ops = Vec::new();
+    let mut ip = start;
+    while ops.len() < MAX_TRACE_LENGTH {
+        let Ok(length) = memory.mem_gets(ip.into(), 1) else {
+            break;
+        };
+        let length = length[0] as usize;
+        let Ok(instruction) = memory.mem_gets(ip.into(), length) else {
+            break;
+        };
+        let Ok(decoded) = Decoded::decode(&instruction) else {
+            break;
+        };
+        let opcode = decoded.form().opcode();
+        let Some(op) = decode(ip, opcode, &mut Argument::new(decoded), section_manager) else {
+            break;
+        };
+        ops.push((ip, length, op));
+        ip += length;
+        if let Op::Jump(..) = op {
+            break;
+        }
+    }
+    return ops;
+}
+
+fn mask(size: RegisterSizes) -> u64 {
+    return match size {
+        RegisterSizes::SizeU8 => 0xFF,
+        RegisterSizes::SizeU16 => 0xFFFF,
+        RegisterSizes::SizeU32 => 0xFFFFFFFF,
+        RegisterSizes::SizeU64 => u64::MAX,
+    };
+}
+
+struct Translator<'a> {
+    builder: FunctionBuilder<'a>,
+    context: Value,
+    memory: Value,
+    /// Signature of `direct_access`
+    direct_access: SigRef,
+    registers: [Variable; 4],
+    sp: Variable,
+    flags: Variable,
+    /// Exit blocks and the ip they return, filled in once the trace is done
+    exits: Vec<(Block, u64)>,
+    /// Ip of the instruction being translated and its exit block once a guard needs it
+    current: (usize, Option<Block>),
+    /// Block jumps back to the start go through, filled in once the trace is done
+    back_edge: Option<Block>,
+}
+
+impl<'a> Translator<'a> {
+    fn flags() -> MemFlags {
+        return MemFlags::trusted().with_endianness(Endianness::Little);
+    }
+
+    fn variable(&self, register: RegisterType) -> Variable {
+        return match register.group() {
+            RegisterTypeGroup::A => self.registers[0],
+            RegisterTypeGroup::B => self.registers[1],
+            RegisterTypeGroup::C => self.registers[2],
+            RegisterTypeGroup::D => self.registers[3],
+            _ => self.sp,
+        };
+    }
+
+    fn read(&mut self, register: RegisterType) -> Value {
+        let value = self.builder.use_var(self.variable(register));
+        return match register.size() {
+            RegisterSizes::SizeU64 => value,
+            size => self.builder.ins().band_imm(value, mask(size) as i64),
+        };
+    }
+
+    fn operand(&mut self, operand: Operand) -> Value {
+        return match operand {
+            Operand::Register(register) => self.read(register),
+            Operand::Immediate(value) => self.builder.ins().iconst(types::I64, value as i64),
+        };
+    }
+
+    /// Write the low bits of a register and keep the rest, like `RegisterFile::set_general`
+    fn write(&mut self, register: RegisterType, value: Value) {
+        let variable = self.variable(register);
+        let value = match register.size() {
+            RegisterSizes::SizeU64 => value,
+            size => {
+                let old = self.builder.use_var(variable);
+                let old = self.builder.ins().band_imm(old, !mask(size) as i64);
+                let value = self.builder.ins().band_imm(value, mask(size) as i64);
+                self.builder.ins().bor(old, value)
+            }
+        };
+        self.builder.def_var(variable, value);
+    }
+
+    fn exit_block(&mut self, ip: u64) -> Block {
+        let block = self.builder.create_block();
+        self.exits.push((block, ip));
+        return block;
+    }
+
+    /// Exit that returns to the interpreter at the current instruction
+    fn exit(&mut self) -> Block {
+        if let (ip, None) = self.current {
+            let block = self.exit_block(ip as u64);
+            self.current.1 = Some(block);
+        }
+        return self.current.1.unwrap();
+    }
+
+    /// Leave the trace before the current instruction when `condition` is set
+    fn guard(&mut self, condition: Value) {
+        let exit = self.exit();
+        let next = self.builder.create_block();
+        self.builder.ins().brif(condition, exit, &[], next, &[]);
+        self.builder.switch_to_block(next);
+    }
+
+    /// Leave the trace when `value` doesn't fit in `register`
+    fn guard_fits(&mut self, register: RegisterType, value: Value) {
+        if let RegisterSizes::SizeU64 = register.size() {
+            return;
+        }
+        let oversized = self.builder.ins().icmp_imm(
+            IntCC::UnsignedGreaterThan,
+            value,
+            mask(register.size()) as i64,
+        );
+        self.guard(oversized);
+    }
+
+    /// Compute the address of `deref` and leave the trace when it overflows
+    fn address(&mut self, deref: Deref) -> Value {
+        let base = self.read(deref.base);
+        let offset = self.builder.ins().iconst(types::I64, deref.offset as i64);
+        let address = if deref.is_add {
+            let address = self.builder.ins().iadd(base, offset);
+            let overflow = self
+                .builder
+                .ins()
+                .icmp(IntCC::UnsignedLessThan, address, base);
+            self.guard(overflow);
+            address
+        } else {
+            let underflow = self
+                .builder
+                .ins()
+                .icmp(IntCC::UnsignedLessThan, base, offset);
+            self.guard(underflow);
+            self.builder.ins().isub(base, offset)
+        };
+        return address;
+    }
+
+    /// Get a host pointer to `size` bytes at `address`, leave the trace when the memory
+    /// can't be accessed directly so the interpreter does it
+    fn pointer(&mut self, address: Value, size: usize, write: bool) -> Value {
+        let callee = self
+            .builder
+            .ins()
+            .iconst(types::I64, direct_access as *const () as i64);
+        let size = self.builder.ins().iconst(types::I64, size as i64);
+        let write = self.builder.ins().iconst(types::I64, write as i64);
+        let call = self.builder.ins().call_indirect(
+            self.direct_access,
+            callee,
+            &[self.memory, address, size, write],
+        );
+        let pointer = self.builder.inst_results(call)[0];
+        let null = self.builder.ins().icmp_imm(IntCC::Equal, pointer, 0);
+        self.guard(null);
+        return pointer;
+    }
+
+    fn load(&mut self, address: Value, size: usize) -> Value {
+        let pointer = self.pointer(address, size, false);
+        let flags = Self::flags();
+        return match size {
+            1 => self.builder.ins().uload8(types::I64, flags, pointer, 0),
+            2 => self.builder.ins().uload16(types::I64, flags, pointer, 0),
+            4 => self.builder.ins().uload32(flags, pointer, 0),
+            _ => self.builder.ins().load(types::I64, flags, pointer, 0),
+        };
+    }
+
+    fn store(&mut self, address: Value, value: Value, size: usize) {
+        let pointer = self.pointer(address, size, true);
+        let flags = Self::flags();
+        match size {
+            1 => self.builder.ins().istore8(flags, value, pointer, 0),
+            2 => self.builder.ins().istore16(flags, value, pointer, 0),
+            4 => self.builder.ins().istore32(flags, value, pointer, 0),
+            _ => self.builder.ins().store(flags, value, pointer, 0),
+        };
+    }
+
+    /// Set zero, carry and negative from `result`, other flags are kept
+    fn set_flags(&mut self, result: Value, carry: Value) {
+        let zero = self.builder.ins().icmp_imm(IntCC::Equal, result, 0);
+        let zero = self.builder.ins().uextend(types::I64, zero);
+        let carry = self.builder.ins().uextend(types::I64, carry);
+        let carry = self.builder.ins().ishl_imm(carry, 1);
+        let negative = self.builder.ins().ushr_imm(result, 63);
+        let negative = self.builder.ins().ishl_imm(negative, 2);
+        let flags = self.builder.use_var(self.flags);
+        let flags = self.builder.ins().band_imm(flags, !0b111);
+        let flags = self.builder.ins().bor(flags, zero);
+        let flags = self.builder.ins().bor(flags, carry);
+        let flags = self.builder.ins().bor(flags, negative);
+        self.builder.def_var(self.flags, flags);
+    }
+
+    /// Returns the wrapped result and the carry
+    fn arithmetic(&mut self, arithmetic: Arithmetic, a: Value, b: Value) -> (Value, Value) {
+        return match arithmetic {
+            Arithmetic::Add => {
+                let result = self.builder.ins().iadd(a, b);
+                let carry = self.builder.ins().icmp(IntCC::UnsignedLessThan, result, a);
+                (result, carry)
+            }
+            Arithmetic::Sub => {
+                let result = self.builder.ins().isub(a, b);
+                let carry = self.builder.ins().icmp(IntCC::UnsignedLessThan, a, b);
+                (result, carry)
+            }
+            Arithmetic::Mul => {
+                let result = self.builder.ins().imul(a, b);
+                let high = self.builder.ins().umulhi(a, b);
+                let carry = self.builder.ins().icmp_imm(IntCC::NotEqual, high, 0);
+                (result, carry)
+            }
+            Arithmetic::Div => {
+                let result = self.builder.ins().udiv(a, b);
+                let carry = self.builder.ins().iconst(types::I8, 0);
+                (result, carry)
+            }
+        };
+    }
+
+    fn condition(&mut self, condition: Condition) -> Value {
+        let flags = self.builder.use_var(self.flags);
+        return match condition {
+            Condition::Always => self.builder.ins().iconst(types::I8, 1),
+            Condition::Zero | Condition::Carry | Condition::Negative | Condition::NoFlags => {
+                let (bits, cc) = match condition {
+                    Condition::Zero => (0b001, IntCC::NotEqual),
+                    Condition::Carry => (0b010, IntCC::NotEqual),
+                    Condition::Negative => (0b100, IntCC::NotEqual),
+                    _ => (0b111, IntCC::Equal),
+                };
+                let bits = self.builder.ins().band_imm(flags, bits);
+                self.builder.ins().icmp_imm(cc, bits, 0)
+            }
+            Condition::CompareCarry(a, b)
+            | Condition::CompareAbove(a, b)
+            | Condition::CompareNegative(a, b)
+            | Condition::CompareZero(a, b) => {
+                let a = self.read(a);
+                let b = self.read(b);
+                match condition {
+                    Condition::CompareCarry(..) => {
+                        self.builder.ins().icmp(IntCC::UnsignedLessThan, a, b)
+                    }
+                    Condition::CompareZero(..) => self.builder.ins().icmp(IntCC::Equal, a, b),
+                    Condition::CompareNegative(..) => {
+                        let result = self.builder.ins().isub(a, b);
+                        self.builder
+                            .ins()
+                            .icmp_imm(IntCC::SignedLessThan, result, 0)
+                    }
+                    _ => {
+                        let result = self.builder.ins().isub(a, b);
+                        let carry = self.builder.ins().icmp(IntCC::UnsignedLessThan, a, b);
+                        let not_positive =
+                            self.builder
+                                .ins()
+                                .icmp_imm(IntCC::SignedLessThanOrEqual, result, 0);
+                        let fails = self.builder.ins().bor(carry, not_positive);
+                        self.builder.ins().bxor_imm(fails, 1)
+                    }
+                }
+            }
+        };
+    }
+
+    /// Emit one instruction, every guard comes before the first write so an exit leaves the
+    /// state as it was before the instruction
+    fn translate(&mut self, ip: usize, op: Op, start: usize) {
+        self.current = (ip, None);
+        match op {
+            Op::Move(dst, src) => {
+                let value = self.operand(src);
+                self.guard_fits(dst, value);
+                self.builder.def_var(self.variable(dst), value);
+            }
+            Op::MoveSp(src) => {
+                let value = self.operand(src);
+                self.builder.def_var(self.sp, value);
+            }
+            Op::Load(dst, deref, size) => {
+                let address = self.address(deref);
+                let value = self.load(address, size);
+                self.write(dst, value);
+            }
+            Op::Store(deref, value, size) => {
+                let value = self.operand(value);
+                let address = self.address(deref);
+                self.store(address, value, size);
+            }
+            Op::Arithmetic(arithmetic, dst, src) => {
+                let a = self.read(dst);
+                let b = self.operand(src);
+                if let Arithmetic::Div = arithmetic {
+                    let by_zero = self.builder.ins().icmp_imm(IntCC::Equal, b, 0);
+                    self.guard(by_zero);
+                }
+                let (result, carry) = self.arithmetic(arithmetic, a, b);
+                self.guard_fits(dst, result);
+                self.set_flags(result, carry);
+                self.write(dst, result);
+            }
+            Op::ArithmeticSp(arithmetic, value) => {
+                let sp = self.builder.use_var(self.sp);
+                let value = self.builder.ins().iconst(types::I64, value as i64);
+                let (result, carry) = self.arithmetic(arithmetic, sp, value);
+                self.set_flags(result, carry);
+                self.builder.def_var(self.sp, result);
+            }
+            Op::Inc(register) => {
+                let a = self.read(register);
+                let one = self.builder.ins().iconst(types::I64, 1);
+                let (result, carry) = self.arithmetic(Arithmetic::Add, a, one);
+                self.guard_fits(register, result);
+                self.set_flags(result, carry);
+                self.write(register, result);
+            }
+            Op::Cmp(a, b) => {
+                let a = self.read(a);
+                let b = self.read(b);
+                let (result, carry) = self.arithmetic(Arithmetic::Sub, a, b);
+                self.set_flags(result, carry);
+            }
+            Op::Push(register) => {
+                let size = register.size().byte();
+                let value = self.read(register);
+                let sp = self.builder.use_var(self.sp);
+                let underflow =
+                    self.builder
+                        .ins()
+                        .icmp_imm(IntCC::UnsignedLessThan, sp, size as i64);
+                self.guard(underflow);
+                let sp = self.builder.ins().iadd_imm(sp, -(size as i64));
+                self.store(sp, value, size);
+                self.builder.def_var(self.sp, sp);
+            }
+            Op::Pop(register) => {
+                let size = register.size().byte();
+                let sp = self.builder.use_var(self.sp);
+                let value = self.load(sp, size);
+                self.write(register, value);
+                let sp = self.builder.ins().iadd_imm(sp, size as i64);
+                self.builder.def_var(self.sp, sp);
+            }
+            Op::Jump(condition, target) => {
+                let taken = if target == start {
+                    *self
+                        .back_edge
+                        .get_or_insert_with(|| self.builder.create_block())
+                } else {
+                    self.exit_block(target as u64)
+                };
+                if let Condition::Always = condition {
+                    self.builder.ins().jump(taken, &[]);
+                    return;
+                }
+                let condition = self.condition(condition);
+                let next = self.builder.create_block();
+                self.builder.ins().brif(condition, taken, &[], next, &[]);
+                self.builder.switch_to_block(next);
+            }
+        }
+    }
+
+    /// Fill the back edge, it loops to `body` while `JitContext::loops` isn't zero and exits
+    /// at the start of the trace otherwise
+    fn finish_back_edge(&mut self, start: usize, body: Block) {
+        let Some(block) = self.back_edge else {
+            return;
+        };
+        let exit = self.exit_block(start as u64);
+        let again = self.builder.create_block();
+        self.builder.switch_to_block(block);
+        let loops = self.builder.ins().load(
+            types::I64,
+            MemFlags::trusted(),
+            self.context,
+            offset_of!(JitContext, loops) as i32,
+        );
+        self.builder.ins().brif(loops, again, &[], exit, &[]);
+        self.builder.switch_to_block(again);
+        let loops = self.builder.ins().iadd_imm(loops, -1);
+        self.builder.ins().store(
+            MemFlags::trusted(),
+            loops,
+            self.context,
+            offset_of!(JitContext, loops) as i32,
+        );
+        self.builder.ins().jump(body, &[]);
+    }
+
+    /// Fill the exit blocks, each one writes the state back and returns its ip
+    fn finish_exits(&mut self) {
+        for (block, ip) in std::mem::take(&mut self.exits) {
+            self.builder.switch_to_block(block);
+            for (i, variable) in self.registers.into_iter().enumerate() {
+                let value = self.builder.use_var(variable);
+                self.builder.ins().store(
+                    MemFlags::trusted(),
+                    value,
+                    self.context,
+                    (offset_of!(JitContext, registers) + i * 8) as i32,
+                );
+            }
+            let sp = self.builder.use_var(self.sp);
+            self.builder.ins().store(
+                MemFlags::trusted(),
+                sp,
+                self.context,
+                offset_of!(JitContext, sp) as i32,
+            );
+            let flags = self.builder.use_var(self.flags);
+            self.builder.ins().store(
+                MemFlags::trusted(),
+                flags,
+                self.context,
+                offset_of!(JitContext, flags) as i32,
+            );
+            let ip = self.builder.ins().iconst(types::I64, ip as i64);
+            self.builder.ins().return_(&[ip]);
+        }
+    }
+}
+
+/// Compile the trace starting at `start`, returns None if no instruction there is supported
+pub(super) fn compile(
+    module: &mut JITModule,
+    function_context: &mut FunctionBuilderContext,
+    memory: &Memory,
+    section_manager: &SectionManager,
+    start: usize,
+) -> Option<CompiledTrace> {
+    let ops = decode_trace(memory, section_manager, start);
+    let (last_ip, last_length, _) = *ops.last()?;
+
+    let mut context = module.make_context();
+    context
+        .func
+        .signature
+        .params
+        .push(AbiParam::new(types::I64));
+    context
+        .func
+        .signature
+        .returns
+        .push(AbiParam::new(types::I64));
+
+    let mut builder = FunctionBuilder::new(&mut context.func, function_context);
+    let entry = builder.create_block();
+    builder.append_block_params_for_function_params(entry);
+    builder.switch_to_block(entry);
+    let pointer = builder.block_params(entry)[0];
+    let variables: Vec<Variable> = (0..6).map(Variable::from_u32).collect();
+    for (i, variable) in variables.iter().enumerate() {
+        builder.declare_var(*variable, types::I64);
+        let value = builder
+            .ins()
+            .load(types::I64, MemFlags::trusted(), pointer, (i * 8) as i32);
+        builder.def_var(*variable, value);
+    }
+    let memory = builder.ins().load(
+        types::I64,
+        MemFlags::trusted(),
+        pointer,
+        offset_of!(JitContext, memory) as i32,
+    );
+    let mut signature = Signature::new(builder.func.signature.call_conv);
+    signature.params.extend([AbiParam::new(types::I64); 4]);
+    signature.returns.push(AbiParam::new(types::I64));
+    let direct_access = builder.import_signature(signature);
+    let body = builder.create_block();
+    builder.ins().jump(body, &[]);
+    builder.switch_to_block(body);
+
+    let mut translator = Translator {
+        builder,
+        context: pointer,
+        memory,
+        direct_access,
+        registers: [variables[0], variables[1], variables[2], variables[3]],
+        sp: variables[4],
+        flags: variables[5],
+        exits: Vec::new(),
+        current: (start, None),
+        back_edge: None,
+    };
+    let mut filled = false;
+    for (ip, _, op) in ops.iter() {
+        translator.translate(*ip, *op, start);
+        filled = matches!(op, Op::Jump(Condition::Always, _));
+    }
+    if !filled {
+        let end = translator.exit_block((last_ip + last_length) as u64);
+        translator.builder.ins().jump(end, &[]);
+    }
+    translator.finish_back_edge(start, body);
+    translator.finish_exits();
+    translator.builder.seal_all_blocks();
+    translator.builder.finalize();
+
+    let id = module
+        .declare_anonymous_function(&context.func.signature)
+        .ok()?;
+    module.define_function(id, &mut context).ok()?;
+    module.clear_context(&mut context);
+    module.finalize_definitions().ok()?;
+    let code = module.get_finalized_function(id);
+    // SAFETY: the function was built with the signature of `CompiledTrace`
+    return Some(unsafe { std::mem::transmute::<*const u8, CompiledTrace>(code) });
+}
@@ -0,0 +1,247 @@
+use std::{error::Error, fmt::Display};
+
+use crate::memory::address::Address;
+
+/// Memory size used by `craion run` when none is given, its devices are mapped right above
+/// the end of memory
+pub const DEFAULT_MEMORY_SIZE: usize = 0x100000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutError {
+    /// A region (name, start, size) doesn't fit in memory
+    OutOfMemory(&'static str, Address, usize),
+    /// Two regions overlap
+    Overlap(&'static str, &'static str),
+    /// A section (hash, start, size) overlaps the named region or lies outside of memory
+    SectionOverlap(u64, Address, usize, &'static str),
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfMemory(name, start, size) => write!(
+                f,
+                "The {} does not fit in memory. from address: {}, to address: {:#x}",
+                name,
+                start,
+                start.get_raw() as u128 + *size as u128
+            ),
+            Self::Overlap(a, b) => write!(f, "The {} overlaps the {}", a, b),
+            Self::SectionOverlap(hash, start, size, name) => write!(
+                f,
+                "Section {:#018x} overlaps the {}. from address: {}, to address: {:#x}",
+                hash,
+                name,
+                start,
+                start.get_raw() as u128 + *size as u128
+            ),
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+/// Where things are placed in guest memory
+///
+/// Procedures are loaded upward from the code base and constants upward from the constant
+/// base, the stack grows down from the stack top and the heap is left to the program
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    memory_size: usize,
+    code_base: Address,
+    constant_base: Address,
+    stack_top: Address,
+    stack_size: usize,
+    thread_stack_size: usize,
+    heap_start: Address,
+    heap_end: Address,
+}
+
+impl Layout {
+    /// Split `memory_size` bytes into code, constants, heap and stack quarters, in that order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::layout::Layout;
+    /// use craion::memory::address::Address;
+    /// let layout = Layout::new(0x1000).with_stack_size(0x100);
+    ///
+    /// assert_eq!(Address::new(0x400), layout.constant_base());
+    /// assert_eq!(Address::new(0xF00), layout.stack_bottom());
+    /// assert_eq!(Ok(()), layout.validate());
+    /// ```
+    pub fn new(memory_size: usize) -> Self {
+        let quarter = memory_size / 4;
+        Self {
+            memory_size,
+            code_base: Address::new(0),
+            constant_base: Address::new(quarter),
+            stack_top: Address::new(memory_size),
+            stack_size: memory_size - quarter * 3,
+            thread_stack_size: (memory_size - quarter * 3) / 16,
+            heap_start: Address::new(quarter * 2),
+            heap_end: Address::new(quarter * 3),
+        }
+    }
+
+    pub fn with_code_base(mut self, code_base: Address) -> Self {
+        self.code_base = code_base;
+        return self;
+    }
+
+    pub fn with_constant_base(mut self, constant_base: Address) -> Self {
+        self.constant_base = constant_base;
+        return self;
+    }
+
+    pub fn with_stack_top(mut self, stack_top: Address) -> Self {
+        self.stack_top = stack_top;
+        return self;
+    }
+
+    pub fn with_stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = stack_size;
+        return self;
+    }
+
+    /// Size of the stack slot of each spawned thread, slots are taken from the stack region
+    /// below the slot of the main thread
+    pub fn with_thread_stack_size(mut self, thread_stack_size: usize) -> Self {
+        self.thread_stack_size = thread_stack_size;
+        return self;
+    }
+
+    pub fn with_heap(mut self, heap_start: Address, heap_end: Address) -> Self {
+        self.heap_start = heap_start;
+        self.heap_end = heap_end;
+        return self;
+    }
+
+    pub fn memory_size(&self) -> usize {
+        return self.memory_size;
+    }
+
+    pub fn code_base(&self) -> Address {
+        return self.code_base;
+    }
+
+    pub fn constant_base(&self) -> Address {
+        return self.constant_base;
+    }
+
+    pub fn stack_top(&self) -> Address {
+        return self.stack_top;
+    }
+
+    pub fn stack_size(&self) -> usize {
+        return self.stack_size;
+    }
+
+    pub fn thread_stack_size(&self) -> usize {
+        return self.thread_stack_size;
+    }
+
+    /// Lowest address of the stack
+    pub fn stack_bottom(&self) -> Address {
+        return Address::new(self.stack_top.get_raw().saturating_sub(self.stack_size));
+    }
+
+    pub fn heap_start(&self) -> Address {
+        return self.heap_start;
+    }
+
+    pub fn heap_end(&self) -> Address {
+        return self.heap_end;
+    }
+
+    fn regions(&self) -> [(&'static str, usize, usize); 2] {
+        return [
+            (
+                "stack",
+                self.stack_bottom().get_raw(),
+                self.stack_top.get_raw(),
+            ),
+            ("heap", self.heap_start.get_raw(), self.heap_end.get_raw()),
+        ];
+    }
+
+    /// Check that the stack and the heap are in memory and apart, and that both bases are in
+    /// memory and outside of them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::layout::{Layout, LayoutError};
+    /// use craion::memory::address::Address;
+    /// let layout = Layout::new(0x1000).with_heap(Address::new(0x800), Address::new(0xE00));
+    ///
+    /// assert_eq!(Err(LayoutError::Overlap("stack", "heap")), layout.validate());
+    /// ```
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        if self.stack_size > self.stack_top.get_raw() || self.stack_top.get_raw() > self.memory_size
+        {
+            return Err(LayoutError::OutOfMemory(
+                "stack",
+                self.stack_bottom(),
+                self.stack_size,
+            ));
+        }
+        if self.thread_stack_size > self.stack_size {
+            return Err(LayoutError::OutOfMemory(
+                "thread stack",
+                self.stack_bottom(),
+                self.thread_stack_size,
+            ));
+        }
+        if self.heap_start.get_raw() > self.heap_end.get_raw()
+            || self.heap_end.get_raw() > self.memory_size
+        {
+            return Err(LayoutError::OutOfMemory(
+                "heap",
+                self.heap_start,
+                self.heap_end
+                    .get_raw()
+                    .saturating_sub(self.heap_start.get_raw()),
+            ));
+        }
+        let [(stack, stack_start, stack_end), (heap, heap_start, heap_end)] = self.regions();
+        if stack_start < heap_end && heap_start < stack_end {
+            return Err(LayoutError::Overlap(stack, heap));
+        }
+        for (name, base) in [
+            ("code base", self.code_base),
+            ("constant base", self.constant_base),
+        ] {
+            if base.get_raw() >= self.memory_size {
+                return Err(LayoutError::OutOfMemory(name, base, 0));
+            }
+            for (region, start, end) in self.regions() {
+                if start <= base.get_raw() && base.get_raw() < end {
+                    return Err(LayoutError::Overlap(name, region));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    /// Check that a section placed at `start` stays in memory and out of the stack and heap
+    pub fn check_section(&self, hash: u64, start: Address, size: usize) -> Result<(), LayoutError> {
+        let end = start.get_raw().checked_add(size);
+        if end.is_none_or(|end| end > self.memory_size) {
+            return Err(LayoutError::SectionOverlap(
+                hash,
+                start,
+                size,
+                "end of memory",
+            ));
+        }
+        let end = start.get_raw() + size;
+        for (region, region_start, region_end) in self.regions() {
+            if start.get_raw() < region_end && region_start < end {
+                return Err(LayoutError::SectionOverlap(hash, start, size, region));
+            }
+        }
+        return Ok(());
+    }
+}
@@ -1,8 +1,14 @@
 #![deny(warnings)]
 
+pub mod assembler;
+pub mod core_dump;
 pub mod decoder;
 pub mod executor;
+pub mod hexdump;
 pub mod instruction_helper;
+pub mod jit;
+pub mod layout;
 pub mod memory;
 pub mod ret_stack;
 pub mod section_manager;
+pub mod verifier;
@@ -1,42 +1,381 @@
 #![deny(warnings)]
 #![feature(test)]
 
+use std::collections::HashMap;
 use std::env;
 use std::fs::File;
 use std::io::Read;
 use std::process::ExitCode;
 
 use common::commands::{Command, CommandExecutor};
+use common::constants::{MAGIC_1, MAGIC_2, MAGIC_3, MAGIC_4};
+use common::isa::decoded::{Decoded, Value};
+use common::sin::debug_info::{DebugInfo, DEBUG_SECTION_NAME};
 use common::sin::sections::SectionType;
+use common::sin::type_info::TYPE_SECTION_NAME;
 use common::sin::Sin;
-use craion::executor::Executor;
+use craion::core_dump::CoreDump;
+use craion::executor::builder::{BuildError, ExecutorBuilder};
+use craion::hexdump::hexdump;
+use craion::jit::DEFAULT_HOT_THRESHOLD;
+use craion::layout::{Layout, DEFAULT_MEMORY_SIZE};
 use craion::memory::address::Address;
+use craion::memory::device::{console::Console, random::Random, timer::Timer};
+use craion::memory::watchpoint::WatchKind;
+
+use xxhash_rust::xxh3::xxh3_64;
 
 extern crate test;
 
+/// Offsets of the devices from the end of memory, so they never shadow RAM
+const CONSOLE_OFFSET: usize = 0x0;
+const TIMER_OFFSET: usize = 0x8;
+const RANDOM_OFFSET: usize = 0x10;
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    let result = match value.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    return result.map_err(|e| format!("invalid number {value}: {e}"));
+}
+
+/// Parse `<address>:<size>[:r|w|rw]`
+fn parse_watchpoint(value: &str) -> Result<(Address, usize, WatchKind), String> {
+    let mut parts = value.split(':');
+    let (Some(address), Some(size)) = (parts.next(), parts.next()) else {
+        return Err(format!(
+            "invalid watchpoint {value}, expected <address>:<size>[:r|w|rw]"
+        ));
+    };
+    let kind = match parts.next() {
+        Some("r") => WatchKind::Read,
+        Some("w") => WatchKind::Write,
+        Some("rw") | None => WatchKind::ReadWrite,
+        Some(kind) => return Err(format!("invalid watchpoint kind {kind}")),
+    };
+    return Ok((
+        Address::new(parse_number(address)?),
+        parse_number(size)?,
+        kind,
+    ));
+}
+
+fn next_number(arg: &str, args: &mut env::Args) -> Result<usize, String> {
+    let value = args.next().ok_or(format!("{arg} expects a number"))?;
+    return parse_number(&value);
+}
+
 fn command_run(_command_name: &str, args: &mut env::Args) -> Result<(), String> {
-    let mut executor = Executor::new(0xFFFFF);
-    let file = args.next().ok_or("no sin file is provided".to_string())?;
-    let mut sin = File::open(&file).map_err(|e| format!("couldn't read {file}: {e}"))?;
+    let mut file = None;
+    let mut jit_threshold = None;
+    let mut watchpoints = Vec::new();
+    let mut memory_size = DEFAULT_MEMORY_SIZE;
+    let mut code_base = None;
+    let mut constant_base = None;
+    let mut stack_top = None;
+    let mut stack_size = None;
+    let mut thread_stack_size = None;
+    let mut heap_start = None;
+    let mut heap_end = None;
+    let mut core_file = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--watch" => {
+                let watchpoint = args.next().ok_or("--watch expects a range".to_string())?;
+                watchpoints.push(parse_watchpoint(&watchpoint)?);
+            }
+            "--memory-size" => memory_size = next_number(&arg, args)?,
+            "--code-base" => code_base = Some(next_number(&arg, args)?),
+            "--constant-base" => constant_base = Some(next_number(&arg, args)?),
+            "--stack-top" => stack_top = Some(next_number(&arg, args)?),
+            "--stack-size" => stack_size = Some(next_number(&arg, args)?),
+            "--thread-stack-size" => thread_stack_size = Some(next_number(&arg, args)?),
+            "--heap-start" => heap_start = Some(next_number(&arg, args)?),
+            "--heap-end" => heap_end = Some(next_number(&arg, args)?),
+            "--core" => {
+                core_file = Some(args.next().ok_or("--core expects a file".to_string())?);
+            }
+            "--jit" => jit_threshold = Some(jit_threshold.unwrap_or(DEFAULT_HOT_THRESHOLD)),
+            "--jit-threshold" => {
+                let threshold = args
+                    .next()
+                    .ok_or("--jit-threshold expects a number".to_string())?;
+                jit_threshold = Some(
+                    threshold
+                        .parse()
+                        .map_err(|e| format!("invalid jit threshold {threshold}: {e}"))?,
+                );
+            }
+            _ if file.is_none() && !arg.starts_with("--") => file = Some(arg),
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    let file = file.ok_or("no sin file is provided".to_string())?;
+    let mut layout = Layout::new(memory_size);
+    if let Some(code_base) = code_base {
+        layout = layout.with_code_base(Address::new(code_base));
+    }
+    if let Some(constant_base) = constant_base {
+        layout = layout.with_constant_base(Address::new(constant_base));
+    }
+    if let Some(stack_top) = stack_top {
+        layout = layout.with_stack_top(Address::new(stack_top));
+    }
+    if let Some(stack_size) = stack_size {
+        layout = layout.with_stack_size(stack_size);
+    }
+    if let Some(thread_stack_size) = thread_stack_size {
+        layout = layout.with_thread_stack_size(thread_stack_size);
+    }
+    layout = layout.with_heap(
+        heap_start.map_or(layout.heap_start(), Address::new),
+        heap_end.map_or(layout.heap_end(), Address::new),
+    );
+    let buf = read_file(&file)?;
+    let devices = layout.memory_size();
+    let mut builder = ExecutorBuilder::new()
+        .layout(layout)
+        .bytes(&buf)
+        .device(Address::new(devices + CONSOLE_OFFSET), Console::stdio())
+        .device(Address::new(devices + TIMER_OFFSET), Timer::new())
+        .device(Address::new(devices + RANDOM_OFFSET), Random::from_time());
+    if let Some(threshold) = jit_threshold {
+        builder = builder.jit(threshold);
+    }
+    if let Some(core_file) = core_file {
+        builder = builder.core_file(core_file);
+    }
+    let mut executor = match builder.build() {
+        Ok(executor) => executor,
+        Err(BuildError::Verify(errors)) => {
+            for error in errors.iter() {
+                eprintln!("{error}");
+            }
+            return Err(format!(
+                "couldn't verify the provided sin file, found {} errors",
+                errors.len()
+            ));
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    for (address, size, kind) in watchpoints {
+        executor.add_watchpoint(address, size, kind);
+    }
+    if executor.execute() {
+        return Ok(());
+    }
+    if executor.watchpoint_stop().is_some() {
+        return Err("execution stopped on a watchpoint".to_string());
+    }
+    return Err("execution failed".to_string());
+}
+
+fn read_file(file: &str) -> Result<Vec<u8>, String> {
+    let mut sin = File::open(file).map_err(|e| format!("couldn't read {file}: {e}"))?;
     let mut buf = Vec::new();
     sin.read_to_end(&mut buf)
         .map_err(|e| format!("failed to read {file}: {e}"))?;
+    return Ok(buf);
+}
+
+/// Names of the sections that are known from the debug info, the fixed section names and the
+/// names given on the command line
+fn section_names(sin: &Sin, args: &mut env::Args) -> HashMap<u64, String> {
+    let mut names = HashMap::new();
+    names.insert(
+        xxh3_64(DEBUG_SECTION_NAME.as_bytes()),
+        DEBUG_SECTION_NAME.to_string(),
+    );
+    names.insert(
+        xxh3_64(TYPE_SECTION_NAME.as_bytes()),
+        TYPE_SECTION_NAME.to_string(),
+    );
+    for section in sin.sections() {
+        if section.section_type() != SectionType::Debug {
+            continue;
+        }
+        let debug_info = sin
+            .data()
+            .get(section.start() as usize..section.end() as usize)
+            .and_then(|data| DebugInfo::from_bytes(data).ok());
+        for procedure in debug_info.iter().flat_map(|e| e.procedures()) {
+            names.insert(procedure.hash(), procedure.name().to_string());
+        }
+    }
+    for name in args {
+        names.insert(xxh3_64(name.as_bytes()), name);
+    }
+    return names;
+}
+
+fn command_info(_command_name: &str, args: &mut env::Args) -> Result<(), String> {
+    let file = args.next().ok_or("no sin file is provided".to_string())?;
+    let buf = read_file(&file)?;
     let sin =
         Sin::from_bytes(&buf).map_err(|e| format!("couldn't parse the provided sin file: {e}"))?;
+    let names = section_names(&sin, args);
+
+    println!(
+        "magic: {:02x} {:02x} {:02x} {:02x}",
+        MAGIC_1, MAGIC_2, MAGIC_3, MAGIC_4
+    );
+    println!("section count: {}", sin.sections().len());
+    println!("data size: {} bytes", sin.data().len());
     for section in sin.sections() {
-        executor.load_section(section, sin.data());
+        println!();
+        print!(
+            "section {:#018x} {:?}",
+            section.hash(),
+            section.section_type()
+        );
+        if let Some(name) = names.get(&section.hash()) {
+            print!(" `{name}`");
+        }
+        println!(
+            " start: {:#x}, end: {:#x}, size: {} bytes",
+            section.start(),
+            section.end(),
+            section.end().saturating_sub(section.start())
+        );
+        match sin
+            .data()
+            .get(section.start() as usize..section.end() as usize)
+        {
+            Some(data) => print!("{}", hexdump(data)),
+            None => println!("section lies outside of the sin data"),
+        }
     }
-    let entry = if let Some(entry) = executor.section_manager().get_section("start") {
-        if entry.section_type() != SectionType::Procedure {
-            return Err("entry point is not a procedure".to_string());
+    return Ok(());
+}
+
+fn command_disasm(_command_name: &str, args: &mut env::Args) -> Result<(), String> {
+    let file = args.next().ok_or("no sin file is provided".to_string())?;
+    let buf = read_file(&file)?;
+    let sin =
+        Sin::from_bytes(&buf).map_err(|e| format!("couldn't parse the provided sin file: {e}"))?;
+    let names = section_names(&sin, args);
+
+    for section in sin.sections() {
+        if section.section_type() != SectionType::Procedure {
+            continue;
         }
-        entry.mem_start()
-    } else {
-        return Err("entry point not found".to_string());
-    };
-    executor.registers().set_ip(entry);
-    executor.registers().set_sp(Address::new(0xFFFE));
-    executor.execute();
+        match names.get(&section.hash()) {
+            Some(name) => println!("proc {:#018x} `{name}`:", section.hash()),
+            None => println!("proc {:#018x}:", section.hash()),
+        }
+        let Some(data) = sin
+            .data()
+            .get(section.start() as usize..section.end() as usize)
+        else {
+            println!("    section lies outside of the sin data");
+            continue;
+        };
+        let mut offset = 0;
+        while offset < data.len() {
+            match Decoded::decode(&data[offset..]) {
+                Ok(decoded) => {
+                    print!("    {:#06x}  {}", offset, decoded);
+                    let references = decoded
+                        .values()
+                        .iter()
+                        .filter_map(|value| match value {
+                            Value::Section(hash) | Value::Procedure(hash) => names.get(hash),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    if !references.is_empty() {
+                        print!(
+                            "  ; {}",
+                            references
+                                .iter()
+                                .map(|name| name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        );
+                    }
+                    println!();
+                    offset += decoded.length();
+                }
+                Err(e) => {
+                    println!("    {:#06x}  {}", offset, e);
+                    break;
+                }
+            }
+        }
+    }
+    return Ok(());
+}
+
+fn print_registers(core: &CoreDump) {
+    for (name, value) in ["a64", "b64", "c64", "d64"]
+        .iter()
+        .zip(core.general_registers())
+    {
+        println!("{name}: {value:#018x} ({value})");
+    }
+    println!("ip: {}", core.ip());
+    println!("sp: {}", core.sp());
+    println!("flags: {:?}", core.flags());
+    for (name, saved) in ["a", "b", "c", "d"].iter().zip(core.saved_registers()) {
+        if !saved.is_empty() {
+            let saved = saved
+                .iter()
+                .map(|value| format!("{value:#x}"))
+                .collect::<Vec<_>>();
+            println!("saved {name}: {}", saved.join(", "));
+        }
+    }
+}
+
+fn print_sections(core: &CoreDump) {
+    for section in core.sections() {
+        print!(
+            "section {:#018x} {:?} {}..={}",
+            section.hash(),
+            section.section_type(),
+            section.start(),
+            section.end()
+        );
+        if let Some(procedure) = core.debug_info().procedure(section.hash()) {
+            print!(" `{}`", procedure.name());
+        }
+        if section.retired() {
+            print!(" (retired)");
+        }
+        println!();
+    }
+}
+
+fn command_inspect_core(_command_name: &str, args: &mut env::Args) -> Result<(), String> {
+    let file = args.next().ok_or("no core file is provided".to_string())?;
+    let buf = read_file(&file)?;
+    let core = CoreDump::from_bytes(&buf)
+        .map_err(|e| format!("couldn't parse the provided core file: {e}"))?;
+    match args.next().as_deref() {
+        None => {
+            println!("{}, {}", core.error(), core.frame(core.ip()));
+            print_registers(&core);
+            print!("{}", core.backtrace());
+        }
+        Some("registers") => print_registers(&core),
+        Some("backtrace") => print!("{}", core.backtrace()),
+        Some("sections") => print_sections(&core),
+        Some("hexdump") => {
+            let address = args
+                .next()
+                .ok_or("hexdump expects an address".to_string())?;
+            let address = Address::new(parse_number(&address)?);
+            let size = match args.next() {
+                Some(size) => parse_number(&size)?,
+                None => 0x100,
+            };
+            let data = core.read(address, size).map_err(|e| e.to_string())?;
+            println!("{} ({}):", address, core.frame(address));
+            print!("{}", hexdump(&data));
+        }
+        Some(view) => return Err(format!("unknown view {view}")),
+    }
     return Ok(());
 }
 
@@ -45,8 +384,29 @@ fn main() -> ExitCode {
         .new_command(Command::new(
             "run",
             "run the provided sin file",
-            "<sin_file>",
+            "<sin_file> [--jit] [--jit-threshold <count>] [--watch <address>:<size>[:r|w|rw]]... \
+             [--memory-size <size>] [--code-base <address>] [--constant-base <address>] \
+             [--stack-top <address>] [--stack-size <size>] [--thread-stack-size <size>] \
+             [--heap-start <address>] [--heap-end <address>] [--core <core_file>]",
             command_run,
         ))
+        .new_command(Command::new(
+            "info",
+            "print the header, sections and a hexdump of the provided sin file",
+            "<sin_file> [section_names...]",
+            command_info,
+        ))
+        .new_command(Command::new(
+            "disasm",
+            "print the instructions of every procedure in the provided sin file",
+            "<sin_file> [section_names...]",
+            command_disasm,
+        ))
+        .new_command(Command::new(
+            "inspect-core",
+            "print the error, registers and backtrace of a core file written by `run --core`",
+            "<core_file> [registers|backtrace|sections|hexdump <address> [size]]",
+            command_inspect_core,
+        ))
         .run();
 }
@@ -1,18 +1,31 @@
 use std::{
+    borrow::Cow,
+    cmp::Ordering,
     error::Error,
     fmt::{Debug, Display},
+    sync::Arc,
     usize,
 };
 
-use self::address::Address;
+use common::{inline_if, no_hash_hashmap::NoHashHashMap};
+
+use self::{
+    address::Address,
+    device::Device,
+    watchpoint::{Access, Watchpoints},
+};
 
 pub mod address;
-pub mod argument_memory;
+pub mod device;
+pub mod watchpoint;
 
 #[derive(Debug, PartialEq)]
 pub enum MemoryError {
     InvalidAddr(Address),
     OutOfRange(Address, usize),
+    DeviceOverlap(Address, usize),
+    /// A device of size zero, or one that goes past the address space
+    InvalidDevice(Address, usize),
 }
 
 impl Display for MemoryError {
@@ -33,36 +46,102 @@ impl Display for MemoryError {
                     address.get_raw() + offset
                 )
             }
+            MemoryError::DeviceOverlap(address, size) => {
+                write!(
+                    f,
+                    "Trying to map a device over another device. from address: {}, to address: {:#x}",
+                    address,
+                    address.get_raw() + size
+                )
+            }
+            MemoryError::InvalidDevice(address, size) => {
+                write!(
+                    f,
+                    "Trying to map a device of {} bytes at address: {}",
+                    size, address
+                )
+            }
         }
     }
 }
 
 impl Error for MemoryError {}
 
+struct MappedDevice {
+    start: Address,
+    device: Box<dyn Device>,
+}
+
+/// Size of a page, pages are allocated the first time they are written to and copied the
+/// first time a page shared by `fork` is written to
+pub const PAGE_SIZE: usize = 0x1000;
+
+static ZERO_PAGE: [u8; PAGE_SIZE] = [0; PAGE_SIZE];
+
+type Page = Arc<[u8; PAGE_SIZE]>;
+
 #[derive(Debug)]
 pub struct Memory {
-    data: Vec<u8>,
+    size: usize,
+    pages: NoHashHashMap<u64, Page>,
+    devices: Vec<MappedDevice>,
+    buffer: Vec<u8>,
+    watchpoints: Watchpoints,
+}
+
+/// The pages of a memory at one point, it can be sent to and shared between threads
+#[derive(Debug, Clone)]
+pub struct MemorySnapshot {
+    size: usize,
+    pages: NoHashHashMap<u64, Page>,
+}
+
+impl MemorySnapshot {
+    pub fn size(&self) -> usize {
+        return self.size;
+    }
+
+    pub fn allocated_pages(&self) -> usize {
+        return self.pages.len();
+    }
+}
+
+impl Debug for MappedDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MappedDevice({}..{:#x})",
+            self.start,
+            self.start.get_raw() + self.device.size()
+        )
+    }
 }
 
 impl From<&[u8]> for Memory {
     fn from(value: &[u8]) -> Self {
-        Self {
-            data: Vec::from(value),
-        }
+        let mut memory = Self::new(value.len());
+        Self::write_pages(&mut memory.pages, 0, value);
+        return memory;
     }
 }
 
 impl<const N: usize> From<&[u8; N]> for Memory {
     fn from(value: &[u8; N]) -> Self {
-        Self {
-            data: Vec::from(value),
-        }
+        Self::from(value.as_slice())
     }
 }
 
 impl From<Vec<u8>> for Memory {
     fn from(value: Vec<u8>) -> Self {
-        Self { data: value }
+        Self::from(value.as_slice())
+    }
+}
+
+impl From<&MemorySnapshot> for Memory {
+    fn from(value: &MemorySnapshot) -> Self {
+        let mut memory = Self::new(value.size);
+        memory.pages = value.pages.clone();
+        return memory;
     }
 }
 
@@ -73,23 +152,257 @@ impl From<usize> for Memory {
 }
 
 impl Memory {
-    /// Create new instance of memory with perfered capacity and initialize it with zero
+    /// Create a memory with an address space of `size` bytes that reads as zero, nothing is
+    /// allocated until a page is written
     ///
     /// # Examples
     ///
     /// ```
     /// use craion::memory::Memory;
     /// use craion::memory::address::Address;
-    /// let memory = Memory::new(4);
+    /// let mut memory = Memory::new(1 << 40);
     ///
-    /// assert_eq!(Ok(vec![0, 0, 0, 0].as_slice()), memory.mem_gets(Address::new(0), 4));
+    /// assert_eq!(Ok(vec![0, 0, 0, 0].as_slice()), memory.mem_gets(Address::new(0), 4).as_deref());
+    /// assert_eq!(0, memory.allocated_pages());
     /// ```
     pub fn new(size: usize) -> Self {
-        let mut memory = Vec::with_capacity(size);
-        for _ in 0..size {
-            memory.push(0);
+        Self {
+            size,
+            pages: NoHashHashMap::default(),
+            devices: Vec::new(),
+            buffer: Vec::new(),
+            watchpoints: Watchpoints::new(),
+        }
+    }
+
+    /// Size of the address space
+    pub fn size(&self) -> usize {
+        return self.size;
+    }
+
+    pub fn allocated_pages(&self) -> usize {
+        return self.pages.len();
+    }
+
+    /// Take the contents of the memory, memories made from the snapshot share its pages
+    /// until they write to them. Devices and watchpoints are not part of it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// let mut memory = Memory::new(0x2000);
+    /// memory.mem_sets(Address::new(0xFFE), &[1, 2, 3, 4]).unwrap();
+    /// let snapshot = memory.snapshot();
+    /// let mut copy = Memory::from(&snapshot);
+    /// copy.mem_set(Address::new(0xFFF), 9).unwrap();
+    ///
+    /// assert_eq!(Ok([1, 9, 3, 4].as_slice()), copy.mem_gets(Address::new(0xFFE), 4).as_deref());
+    /// assert_eq!(Ok([1, 2, 3, 4].as_slice()), memory.mem_gets(Address::new(0xFFE), 4).as_deref());
+    /// assert_eq!(2, snapshot.allocated_pages());
+    /// ```
+    pub fn snapshot(&self) -> MemorySnapshot {
+        return MemorySnapshot {
+            size: self.size,
+            pages: self.pages.clone(),
+        };
+    }
+
+    /// The allocated pages in address order, the rest of memory reads as zero
+    pub fn pages(&self) -> Vec<(Address, &[u8])> {
+        let mut pages = self
+            .pages
+            .iter()
+            .map(|(number, page)| (Address::new(*number as usize * PAGE_SIZE), page.as_slice()))
+            .collect::<Vec<_>>();
+        pages.sort_by_key(|(address, _)| address.get_raw());
+        return pages;
+    }
+
+    /// Zero `address..address + size` and free the pages that end up empty, devices and
+    /// watchpoints are not involved
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// let mut memory = Memory::new(0x4000);
+    /// memory.mem_sets(Address::new(0xFF0), &[1; 0x20]).unwrap();
+    /// memory.discard(Address::new(0xFF0), 0x18);
+    ///
+    /// assert_eq!(1, memory.allocated_pages());
+    /// assert_eq!(Ok([0, 1].as_slice()), memory.mem_gets(Address::new(0x1007), 2).as_deref());
+    /// ```
+    pub fn discard(&mut self, address: Address, size: usize) {
+        let start = address.get_raw().min(self.size);
+        let end = start.saturating_add(size).min(self.size);
+        let mut address = start;
+        while address < end {
+            let number = (address / PAGE_SIZE) as u64;
+            let offset = address % PAGE_SIZE;
+            let len = (PAGE_SIZE - offset).min(end - address);
+            if let Some(page) = self.pages.get_mut(&number) {
+                let page = Arc::make_mut(page);
+                page[offset..offset + len].fill(0);
+                if page.iter().all(|byte| *byte == 0) {
+                    self.pages.remove(&number);
+                }
+            }
+            address += len;
+        }
+    }
+
+    /// Map a device at `start`, loads and stores to its range are sent to the device
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// use craion::memory::device::framebuffer::Framebuffer;
+    /// let mut memory = Memory::new(4);
+    /// let framebuffer = Framebuffer::new(2, 2);
+    /// let pixels = framebuffer.pixels();
+    /// memory.map_device(Address::new(0x100), Box::new(framebuffer)).unwrap();
+    /// memory.mem_sets(Address::new(0x101), &[7, 9]).unwrap();
+    /// assert_eq!(vec![0, 7, 9, 0], *pixels.lock().unwrap());
+    /// assert_eq!(Ok(vec![7, 9].as_slice()), memory.mem_reads(Address::new(0x101), 2));
+    /// ```
+    pub fn map_device(
+        &mut self,
+        start: Address,
+        device: Box<dyn Device>,
+    ) -> Result<(), MemoryError> {
+        let size = device.size();
+        if size == 0 || start.get_raw().checked_add(size).is_none() {
+            return Err(MemoryError::InvalidDevice(start, size));
+        }
+        let overlap = self.devices.iter().any(|mapped| {
+            start.get_raw() < mapped.start.get_raw() + mapped.device.size()
+                && mapped.start.get_raw() < start.get_raw() + size
+        });
+        if overlap {
+            return Err(MemoryError::DeviceOverlap(start, size));
+        }
+        self.devices.push(MappedDevice { start, device });
+        return Ok(());
+    }
+
+    pub fn watchpoints(&mut self) -> &mut Watchpoints {
+        return &mut self.watchpoints;
+    }
+
+    pub fn watchpoints_ref(&self) -> &Watchpoints {
+        return &self.watchpoints;
+    }
+
+    /// Returns a pointer to `size` bytes at `address` if they lie in a single page and no
+    /// device or watchpoint has to see the access. Pages are allocated when `write` is set,
+    /// otherwise an untouched page is backed by a shared zero page that must not be written
+    pub(crate) fn direct(&mut self, address: Address, size: usize, write: bool) -> Option<*mut u8> {
+        if !self.watchpoints.is_empty() || !self.in_range(address, size) {
+            return None;
+        }
+        let offset = address.get_raw() % PAGE_SIZE;
+        if offset + size > PAGE_SIZE || !matches!(self.find_device(address, size), Ok(None)) {
+            return None;
+        }
+        let number = (address.get_raw() / PAGE_SIZE) as u64;
+        if write {
+            return Some(Self::page_mut(&mut self.pages, number)[offset..].as_mut_ptr());
+        }
+        return Some(Self::page(&self.pages, number)[offset..].as_ptr() as *mut u8);
+    }
+
+    fn in_range(&self, address: Address, size: usize) -> bool {
+        return address
+            .get_raw()
+            .checked_add(size)
+            .is_some_and(|end| end <= self.size);
+    }
+
+    fn page(pages: &NoHashHashMap<u64, Page>, number: u64) -> &[u8; PAGE_SIZE] {
+        return pages.get(&number).map(|page| &**page).unwrap_or(&ZERO_PAGE);
+    }
+
+    fn page_mut(pages: &mut NoHashHashMap<u64, Page>, number: u64) -> &mut [u8; PAGE_SIZE] {
+        return Arc::make_mut(
+            pages
+                .entry(number)
+                .or_insert_with(|| Arc::new([0; PAGE_SIZE])),
+        );
+    }
+
+    /// Returns the range if it doesn't cross a page boundary
+    fn page_slice(pages: &NoHashHashMap<u64, Page>, address: usize, size: usize) -> Option<&[u8]> {
+        let offset = address % PAGE_SIZE;
+        if offset + size > PAGE_SIZE {
+            return None;
+        }
+        let page = Self::page(pages, (address / PAGE_SIZE) as u64);
+        return Some(&page[offset..offset + size]);
+    }
+
+    fn read_pages(pages: &NoHashHashMap<u64, Page>, mut address: usize, buffer: &mut [u8]) {
+        let mut read = 0;
+        while read < buffer.len() {
+            let offset = address % PAGE_SIZE;
+            let len = (PAGE_SIZE - offset).min(buffer.len() - read);
+            let page = Self::page(pages, (address / PAGE_SIZE) as u64);
+            buffer[read..read + len].copy_from_slice(&page[offset..offset + len]);
+            read += len;
+            address += len;
         }
-        Self { data: memory }
+    }
+
+    fn write_pages(pages: &mut NoHashHashMap<u64, Page>, mut address: usize, data: &[u8]) {
+        let mut written = 0;
+        while written < data.len() {
+            let offset = address % PAGE_SIZE;
+            let len = (PAGE_SIZE - offset).min(data.len() - written);
+            let page = Self::page_mut(pages, (address / PAGE_SIZE) as u64);
+            page[offset..offset + len].copy_from_slice(&data[written..written + len]);
+            written += len;
+            address += len;
+        }
+    }
+
+    /// Fails unless `address..address + size` is in memory or inside a single device
+    fn check_range(&self, address: Address, size: usize) -> Result<(), MemoryError> {
+        if address.get_raw().checked_add(size).is_none() {
+            return Err(MemoryError::OutOfRange(address, size));
+        }
+        if self.in_range(address, size) || self.find_device(address, size)?.is_some() {
+            return Ok(());
+        }
+        return Err(MemoryError::OutOfRange(address, size));
+    }
+
+    /// Returns the index of the device and the offset into it if the access hits a device
+    fn find_device(
+        &self,
+        address: Address,
+        size: usize,
+    ) -> Result<Option<(usize, usize)>, MemoryError> {
+        let start = address.get_raw();
+        let end = start
+            .checked_add(size.max(1))
+            .ok_or(MemoryError::OutOfRange(address, size))?;
+        for (i, mapped) in self.devices.iter().enumerate() {
+            // Mapping checks that devices end inside the address space
+            let device_start = mapped.start.get_raw();
+            let device_end = device_start + mapped.device.size();
+            if end <= device_start || device_end <= start {
+                continue;
+            }
+            if start < device_start || device_end < start + size {
+                return Err(MemoryError::OutOfRange(address, size));
+            }
+            return Ok(Some((i, start - device_start)));
+        }
+        return Ok(None);
     }
 
     /// Set a single byte of memory
@@ -103,13 +416,22 @@ impl Memory {
     /// assert_eq!(Ok(1), memory.mem_set(Address::new(0), 1));
     /// assert_eq!(Ok(5), memory.mem_set(Address::new(1), 5));
     /// assert_eq!(Ok(7), memory.mem_set(Address::new(2), 7));
-    /// assert_eq!(Ok(vec![1, 5, 7, 0].as_slice()), memory.mem_gets(Address::new(0), 4));
+    /// assert_eq!(Ok(vec![1, 5, 7, 0].as_slice()), memory.mem_gets(Address::new(0), 4).as_deref());
     /// ```
     pub fn mem_set(&mut self, address: Address, data: u8) -> Result<u8, MemoryError> {
-        let a_data = match self.data.get_mut(address.get_raw()) {
-            Some(data) => data,
-            None => return Err(MemoryError::InvalidAddr(address)),
-        };
+        if let Some((index, offset)) = self.find_device(address, 1)? {
+            self.devices[index].device.write(offset, &[data]);
+            return Ok(data);
+        }
+        if !self.in_range(address, 1) {
+            return Err(MemoryError::InvalidAddr(address));
+        }
+        let page = Self::page_mut(&mut self.pages, (address.get_raw() / PAGE_SIZE) as u64);
+        let a_data = &mut page[address.get_raw() % PAGE_SIZE];
+        if !self.watchpoints.is_empty() {
+            self.watchpoints
+                .check(address, Access::Write, &[*a_data], &[data]);
+        }
         *a_data = data;
         return Ok(*a_data);
     }
@@ -126,9 +448,9 @@ impl Memory {
     /// use craion::memory::MemoryError;
     /// let mut memory = Memory::from(4);
     /// assert_eq!(Ok(vec![2, 1, 2, 3].as_slice()), memory.mem_sets(Address::new(0), &[2,1,2,3]));
-    /// assert_eq!(Ok(vec![2, 1, 2, 3].as_slice()), memory.mem_gets(Address::new(0), 4));
+    /// assert_eq!(Ok(vec![2, 1, 2, 3].as_slice()), memory.mem_gets(Address::new(0), 4).as_deref());
     /// assert_eq!(Ok(vec![1, 2, 4].as_slice()), memory.mem_sets(Address::new(1), &[1,2,4]));
-    /// assert_eq!(Ok(vec![2, 1, 2, 4].as_slice()), memory.mem_gets(Address::new(0), 4));
+    /// assert_eq!(Ok(vec![2, 1, 2, 4].as_slice()), memory.mem_gets(Address::new(0), 4).as_deref());
     /// assert_eq!(Err(MemoryError::OutOfRange(Address::new(1), 4)), memory.mem_sets(Address::new(1), &[1,2,3,4]));
     /// ```
 
@@ -137,20 +459,24 @@ impl Memory {
         address: Address,
         datas: &'a [u8],
     ) -> Result<&'a [u8], MemoryError> {
-        let a_data = match self
-            .data
-            .get_mut(address.get_raw()..address.get_raw() + datas.len())
-        {
-            Some(data) => data,
-            None => return Err(MemoryError::OutOfRange(address, datas.len())),
-        };
-        for (i, data) in datas.iter().enumerate() {
-            a_data[i] = *data;
+        if let Some((index, offset)) = self.find_device(address, datas.len())? {
+            self.devices[index].device.write(offset, datas);
+            return Ok(datas);
+        }
+        if !self.in_range(address, datas.len()) {
+            return Err(MemoryError::OutOfRange(address, datas.len()));
         }
+        if !self.watchpoints.is_empty() {
+            let mut old = vec![0; datas.len()];
+            Self::read_pages(&self.pages, address.get_raw(), &mut old);
+            self.watchpoints.check(address, Access::Write, &old, datas);
+        }
+        Self::write_pages(&mut self.pages, address.get_raw(), datas);
         return Ok(datas);
     }
 
-    /// Returns a u8 of a single byte of memory
+    /// Returns a u8 of a single byte of memory, devices and watchpoints don't see the read,
+    /// see `mem_read`
     ///
     /// # Examples
     ///
@@ -165,15 +491,15 @@ impl Memory {
     /// assert_eq!(Err(MemoryError::InvalidAddr(Address::new(4))), memory.mem_get(Address::new(4)));
     /// ```
     pub fn mem_get(&self, address: Address) -> Result<u8, MemoryError> {
-        let data = match self.data.get(address.get_raw()) {
-            Some(data) => data,
-            None => return Err(MemoryError::InvalidAddr(address)),
-        };
-
-        return Ok(*data);
+        if !self.in_range(address, 1) {
+            return Err(MemoryError::InvalidAddr(address));
+        }
+        let page = Self::page(&self.pages, (address.get_raw() / PAGE_SIZE) as u64);
+        return Ok(page[address.get_raw() % PAGE_SIZE]);
     }
 
-    /// Returns a reference to a range of a memory
+    /// Returns a range of memory, ranges that cross a page boundary are copied. Devices and
+    /// watchpoints don't see the read, see `mem_reads`
     ///
     /// # Examples
     ///
@@ -182,16 +508,200 @@ impl Memory {
     /// use craion::memory::address::Address;
     /// use craion::memory::MemoryError;
     /// let memory = Memory::from(&[1, 2, 3, 4]);
-    /// assert_eq!(Ok(vec![1, 2, 3, 4].as_slice()), memory.mem_gets(Address::new(0), 4));
-    /// assert_eq!(Ok(vec![2, 3, 4].as_slice()), memory.mem_gets(Address::new(1), 3));
+    /// assert_eq!(Ok([1, 2, 3, 4].as_slice()), memory.mem_gets(Address::new(0), 4).as_deref());
+    /// assert_eq!(Ok([2, 3, 4].as_slice()), memory.mem_gets(Address::new(1), 3).as_deref());
     /// assert_eq!(Err(MemoryError::OutOfRange(Address::new(1), 4)), memory.mem_gets(Address::new(1), 4));
+    ///
+    /// let mut memory = Memory::new(0x2000);
+    /// memory.mem_sets(Address::new(0xFFE), &[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(Ok([1, 2, 3, 4].as_slice()), memory.mem_gets(Address::new(0xFFE), 4).as_deref());
+    /// assert_eq!(2, memory.allocated_pages());
+    /// ```
+    pub fn mem_gets(&self, address: Address, size: usize) -> Result<Cow<'_, [u8]>, MemoryError> {
+        if !self.in_range(address, size) {
+            return Err(MemoryError::OutOfRange(address, size));
+        }
+        if let Some(data) = Self::page_slice(&self.pages, address.get_raw(), size) {
+            return Ok(Cow::Borrowed(data));
+        }
+        let mut data = vec![0; size];
+        Self::read_pages(&self.pages, address.get_raw(), &mut data);
+        return Ok(Cow::Owned(data));
+    }
+
+    /// Read a single byte the way the program does, a device mapped at `address` serves the
+    /// read and watchpoints see it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// use craion::memory::MemoryError;
+    /// let mut memory = Memory::from(&[1, 2, 3, 4]);
+    /// assert_eq!(Ok(2), memory.mem_read(Address::new(1)));
+    /// assert_eq!(Err(MemoryError::InvalidAddr(Address::new(4))), memory.mem_read(Address::new(4)));
+    /// ```
+    pub fn mem_read(&mut self, address: Address) -> Result<u8, MemoryError> {
+        if let Some((index, offset)) = self.find_device(address, 1)? {
+            let mut data = [0];
+            self.devices[index].device.read(offset, &mut data);
+            return Ok(data[0]);
+        }
+        let data = self.mem_get(address)?;
+        if !self.watchpoints.is_empty() {
+            self.watchpoints
+                .check(address, Access::Read, &[data], &[data]);
+        }
+        return Ok(data);
+    }
+
+    /// Read a range the way the program does, a device mapped at `address` serves the read and
+    /// watchpoints see it
+    ///
+    /// Ranges that cross a page boundary and reads from a device are copied into a buffer
+    /// owned by the memory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// use craion::memory::MemoryError;
+    /// let mut memory = Memory::from(&[1, 2, 3, 4]);
+    /// assert_eq!(Ok(vec![2, 3, 4].as_slice()), memory.mem_reads(Address::new(1), 3));
+    /// assert_eq!(Err(MemoryError::OutOfRange(Address::new(1), 4)), memory.mem_reads(Address::new(1), 4));
     /// ```
-    pub fn mem_gets(&self, address: Address, size: usize) -> Result<&[u8], MemoryError> {
-        let data = match self.data.get(address.get_raw()..address.get_raw() + size) {
+    pub fn mem_reads(&mut self, address: Address, size: usize) -> Result<&[u8], MemoryError> {
+        if let Some((index, offset)) = self.find_device(address, size)? {
+            self.buffer.resize(size, 0);
+            self.devices[index]
+                .device
+                .read(offset, &mut self.buffer[..size]);
+            return Ok(&self.buffer[..size]);
+        }
+        if !self.in_range(address, size) {
+            return Err(MemoryError::OutOfRange(address, size));
+        }
+        let data = match Self::page_slice(&self.pages, address.get_raw(), size) {
             Some(data) => data,
-            None => return Err(MemoryError::OutOfRange(address, size)),
+            None => {
+                self.buffer.resize(size, 0);
+                Self::read_pages(&self.pages, address.get_raw(), &mut self.buffer[..size]);
+                &self.buffer[..size]
+            }
         };
+        if !self.watchpoints.is_empty() {
+            self.watchpoints.check(address, Access::Read, data, data);
+        }
 
         return Ok(data);
     }
+
+    /// Copy `size` bytes from `source` to `destination`, the ranges may overlap
+    ///
+    /// Nothing is written if either range is out of memory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// use craion::memory::MemoryError;
+    /// let mut memory = Memory::from(&[1, 2, 3, 4, 5, 0]);
+    /// assert_eq!(Ok(()), memory.mem_copy(Address::new(1), Address::new(0), 4));
+    /// assert_eq!(Ok(vec![1, 1, 2, 3, 4, 0].as_slice()), memory.mem_gets(Address::new(0), 6).as_deref());
+    /// assert_eq!(Err(MemoryError::OutOfRange(Address::new(3), 4)), memory.mem_copy(Address::new(3), Address::new(0), 4));
+    /// ```
+    pub fn mem_copy(
+        &mut self,
+        destination: Address,
+        source: Address,
+        size: usize,
+    ) -> Result<(), MemoryError> {
+        self.check_range(source, size)?;
+        self.check_range(destination, size)?;
+        // Copy from the end when the destination is after the source so overlapping bytes are
+        // read before they are overwritten
+        let backward = destination.get_raw() > source.get_raw();
+        let mut chunk = Vec::with_capacity(size.min(PAGE_SIZE));
+        let mut copied = 0;
+        while copied < size {
+            let len = PAGE_SIZE.min(size - copied);
+            let offset = inline_if!(backward, size - copied - len, copied);
+            chunk.clear();
+            chunk.extend_from_slice(self.mem_reads(source + offset, len)?);
+            self.mem_sets(destination + offset, &chunk)?;
+            copied += len;
+        }
+        return Ok(());
+    }
+
+    /// Set `size` bytes starting at `address` to `value`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// use craion::memory::MemoryError;
+    /// let mut memory = Memory::new(0x2000);
+    /// assert_eq!(Ok(()), memory.mem_fill(Address::new(0xFFF), 7, 2));
+    /// assert_eq!(Ok(vec![0, 7, 7, 0].as_slice()), memory.mem_gets(Address::new(0xFFE), 4).as_deref());
+    /// assert_eq!(Err(MemoryError::OutOfRange(Address::new(0x1FFF), 2)), memory.mem_fill(Address::new(0x1FFF), 7, 2));
+    /// ```
+    pub fn mem_fill(
+        &mut self,
+        address: Address,
+        value: u8,
+        size: usize,
+    ) -> Result<(), MemoryError> {
+        self.check_range(address, size)?;
+        let chunk = vec![value; size.min(PAGE_SIZE)];
+        let mut filled = 0;
+        while filled < size {
+            let len = PAGE_SIZE.min(size - filled);
+            self.mem_sets(address + filled, &chunk[..len])?;
+            filled += len;
+        }
+        return Ok(());
+    }
+
+    /// Compare `size` bytes at `left` with `size` bytes at `right` the way `memcmp` does
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use craion::memory::Memory;
+    /// use craion::memory::address::Address;
+    /// use craion::memory::MemoryError;
+    /// let mut memory = Memory::from(&[1, 2, 3, 1, 2, 4]);
+    /// assert_eq!(Ok(Ordering::Equal), memory.mem_compare(Address::new(0), Address::new(3), 2));
+    /// assert_eq!(Ok(Ordering::Less), memory.mem_compare(Address::new(0), Address::new(3), 3));
+    /// assert_eq!(Ok(Ordering::Greater), memory.mem_compare(Address::new(2), Address::new(0), 1));
+    /// assert_eq!(Err(MemoryError::OutOfRange(Address::new(4), 3)), memory.mem_compare(Address::new(0), Address::new(4), 3));
+    /// ```
+    pub fn mem_compare(
+        &mut self,
+        left: Address,
+        right: Address,
+        size: usize,
+    ) -> Result<Ordering, MemoryError> {
+        self.check_range(left, size)?;
+        self.check_range(right, size)?;
+        let mut chunk = Vec::with_capacity(size.min(PAGE_SIZE));
+        let mut compared = 0;
+        while compared < size {
+            let len = PAGE_SIZE.min(size - compared);
+            chunk.clear();
+            chunk.extend_from_slice(self.mem_reads(left + compared, len)?);
+            let ordering = chunk.as_slice().cmp(self.mem_reads(right + compared, len)?);
+            if ordering != Ordering::Equal {
+                return Ok(ordering);
+            }
+            compared += len;
+        }
+        return Ok(Ordering::Equal);
+    }
 }
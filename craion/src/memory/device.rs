@@ -0,0 +1,16 @@
+pub mod console;
+pub mod framebuffer;
+pub mod random;
+pub mod timer;
+
+/// A device that claims a range of guest memory
+///
+/// Offsets are relative to the start of the mapped range, and `buffer`/`data` never go past
+/// `size()`
+pub trait Device {
+    fn size(&self) -> usize;
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]);
+
+    fn write(&mut self, offset: usize, data: &[u8]);
+}
@@ -0,0 +1,40 @@
+use std::io::{self, Read, Write};
+
+use super::Device;
+
+/// A single byte console, stores print the byte and loads read the next byte of input
+///
+/// A load returns 0 once the input has ended
+pub struct Console {
+    input: Box<dyn Read>,
+    output: Box<dyn Write>,
+}
+
+impl Console {
+    pub fn new(input: Box<dyn Read>, output: Box<dyn Write>) -> Self {
+        Self { input, output }
+    }
+
+    pub fn stdio() -> Self {
+        Self::new(Box::new(io::stdin()), Box::new(io::stdout()))
+    }
+}
+
+impl Device for Console {
+    fn size(&self) -> usize {
+        return 1;
+    }
+
+    fn read(&mut self, _offset: usize, buffer: &mut [u8]) {
+        let mut byte = [0];
+        if self.input.read_exact(&mut byte).is_err() {
+            byte[0] = 0;
+        }
+        buffer.fill(byte[0]);
+    }
+
+    fn write(&mut self, _offset: usize, data: &[u8]) {
+        let _ = self.output.write_all(data);
+        let _ = self.output.flush();
+    }
+}
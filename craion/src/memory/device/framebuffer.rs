@@ -0,0 +1,49 @@
+use std::sync::{Arc, Mutex};
+
+use super::Device;
+
+/// A framebuffer of `width * height` bytes that the embedder reads through a shared handle
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: Arc::new(Mutex::new(vec![0; width * height])),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    /// Returns a handle to the pixels that stays valid after the device is mapped
+    pub fn pixels(&self) -> Arc<Mutex<Vec<u8>>> {
+        return self.pixels.clone();
+    }
+}
+
+impl Device for Framebuffer {
+    fn size(&self) -> usize {
+        return self.width * self.height;
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) {
+        let pixels = self.pixels.lock().unwrap();
+        buffer.copy_from_slice(&pixels[offset..offset + buffer.len()]);
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) {
+        let mut pixels = self.pixels.lock().unwrap();
+        pixels[offset..offset + data.len()].copy_from_slice(data);
+    }
+}
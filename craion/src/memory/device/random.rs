@@ -0,0 +1,48 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Device;
+
+/// A pseudo random source, every load returns fresh bytes and a u64 store reseeds it
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    /// Create a random source seeded from the system clock
+    pub fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(1);
+        Self::new(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        // xorshift64
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state;
+    }
+}
+
+impl Device for Random {
+    fn size(&self) -> usize {
+        return 8;
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) {
+        let value = self.next().to_le_bytes();
+        buffer.copy_from_slice(&value[offset..offset + buffer.len()]);
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) {
+        let mut seed = self.state.to_le_bytes();
+        seed[offset..offset + data.len()].copy_from_slice(data);
+        self.state = u64::from_le_bytes(seed).max(1);
+    }
+}
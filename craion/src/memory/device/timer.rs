@@ -0,0 +1,35 @@
+use std::time::Instant;
+
+use super::Device;
+
+/// A monotonic timer, loads return the nanoseconds since the timer was created as a u64
+pub struct Timer {
+    start: Instant,
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Device for Timer {
+    fn size(&self) -> usize {
+        return 8;
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) {
+        let now = (self.start.elapsed().as_nanos() as u64).to_le_bytes();
+        buffer.copy_from_slice(&now[offset..offset + buffer.len()]);
+    }
+
+    fn write(&mut self, _offset: usize, _data: &[u8]) {}
+}
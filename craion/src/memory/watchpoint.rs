@@ -0,0 +1,183 @@
+use std::fmt::Display;
+
+use super::address::Address;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Watchpoint {
+    id: usize,
+    start: Address,
+    size: usize,
+    kind: WatchKind,
+}
+
+/// A memory access that touched a watched range
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchpointHit {
+    id: usize,
+    address: Address,
+    access: Access,
+    old: Vec<u8>,
+    new: Vec<u8>,
+}
+
+/// Collects the watched ranges and the hits recorded since the last `take_hits`
+#[derive(Debug)]
+pub struct Watchpoints {
+    watchpoints: Vec<Watchpoint>,
+    hits: Vec<WatchpointHit>,
+    next_id: usize,
+}
+
+impl WatchKind {
+    fn matches(&self, access: Access) -> bool {
+        return match self {
+            WatchKind::Read => access == Access::Read,
+            WatchKind::Write => access == Access::Write,
+            WatchKind::ReadWrite => true,
+        };
+    }
+}
+
+impl Watchpoint {
+    pub fn id(&self) -> usize {
+        return self.id;
+    }
+
+    pub fn start(&self) -> Address {
+        return self.start;
+    }
+
+    pub fn size(&self) -> usize {
+        return self.size;
+    }
+
+    pub fn kind(&self) -> WatchKind {
+        return self.kind;
+    }
+
+    fn overlaps(&self, address: Address, size: usize) -> bool {
+        return address.get_raw() < self.start.get_raw() + self.size
+            && self.start.get_raw() < address.get_raw() + size;
+    }
+}
+
+impl WatchpointHit {
+    pub fn id(&self) -> usize {
+        return self.id;
+    }
+
+    pub fn address(&self) -> Address {
+        return self.address;
+    }
+
+    pub fn access(&self) -> Access {
+        return self.access;
+    }
+
+    /// The bytes before the access, equal to `new_value` for reads
+    pub fn old_value(&self) -> &[u8] {
+        return &self.old;
+    }
+
+    pub fn new_value(&self) -> &[u8] {
+        return &self.new;
+    }
+}
+
+impl Default for Watchpoints {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Watchpoints {
+    pub fn new() -> Self {
+        Self {
+            watchpoints: Vec::new(),
+            hits: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn add(&mut self, start: Address, size: usize, kind: WatchKind) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.watchpoints.push(Watchpoint {
+            id,
+            start,
+            size,
+            kind,
+        });
+        return id;
+    }
+
+    pub fn remove(&mut self, id: usize) -> bool {
+        let len = self.watchpoints.len();
+        self.watchpoints.retain(|watchpoint| watchpoint.id != id);
+        return self.watchpoints.len() != len;
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        return &self.watchpoints;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.watchpoints.is_empty();
+    }
+
+    /// Record a hit for every watchpoint that `address..address + old.len()` overlaps
+    pub fn check(&mut self, address: Address, access: Access, old: &[u8], new: &[u8]) {
+        for watchpoint in self.watchpoints.iter() {
+            if watchpoint.kind.matches(access) && watchpoint.overlaps(address, old.len()) {
+                self.hits.push(WatchpointHit {
+                    id: watchpoint.id,
+                    address,
+                    access,
+                    old: old.to_vec(),
+                    new: new.to_vec(),
+                });
+            }
+        }
+    }
+
+    pub fn take_hits(&mut self) -> Vec<WatchpointHit> {
+        return std::mem::take(&mut self.hits);
+    }
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Access::Read => write!(f, "read"),
+            Access::Write => write!(f, "write"),
+        }
+    }
+}
+
+impl Display for WatchpointHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Watchpoint {} hit by a {} of {} bytes at {}, old: {:02x?}, new: {:02x?}",
+            self.id,
+            self.access,
+            self.new.len(),
+            self.address,
+            self.old,
+            self.new
+        )
+    }
+}
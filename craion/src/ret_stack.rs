@@ -17,4 +17,22 @@ impl RetStack {
     pub fn pop(&mut self) -> Option<Address> {
         self.data.pop()
     }
+
+    /// Drop the return addresses above the first `len`
+    pub fn truncate(&mut self, len: usize) {
+        self.data.truncate(len);
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Iterate the return addresses from the most recent call to the oldest
+    pub fn frames(&self) -> impl Iterator<Item = Address> + '_ {
+        self.data.iter().rev().copied()
+    }
 }
@@ -1,10 +1,17 @@
 use common::{
     no_hash_hashmap::NoHashHashMap,
-    sin::sections::{SectionType, SinSection},
+    sin::{
+        sections::{SectionType, SinSection},
+        SinError,
+    },
 };
 use xxhash_rust::xxh3::xxh3_64;
 
-use crate::memory::{address::Address, Memory};
+use crate::{
+    executor::LoadError,
+    layout::{Layout, LayoutError},
+    memory::{address::Address, Memory},
+};
 
 #[derive(Debug, Clone)]
 pub struct LoadedSection {
@@ -13,13 +20,33 @@ pub struct LoadedSection {
     mem_end: Address,
 }
 
-#[derive(Debug)]
+/// Where sections of one kind are placed, freed ranges are reused before the end is bumped
+#[derive(Debug, Clone)]
+struct Region {
+    pos: Address,
+    free: Vec<(Address, usize)>,
+}
+
+#[derive(Debug, Clone)]
 pub struct SectionManager {
     sections: NoHashHashMap<u64, LoadedSection>,
-    write_pos: Address,
+    /// Sections that were replaced or unloaded, they can't be reached by hash anymore.
+    /// Procedures are freed by `reclaim`, constants by `free_constants`
+    retired: Vec<(u64, LoadedSection)>,
+    layout: Layout,
+    code: Region,
+    constants: Region,
 }
 
 impl LoadedSection {
+    pub(crate) fn new(ty: SectionType, mem_start: Address, mem_end: Address) -> Self {
+        Self {
+            ty,
+            mem_start,
+            mem_end,
+        }
+    }
+
     pub fn section_type(&self) -> SectionType {
         return self.ty;
     }
@@ -31,16 +58,81 @@ impl LoadedSection {
     pub fn mem_end(&self) -> Address {
         return self.mem_end;
     }
+
+    fn size(&self) -> usize {
+        return self.mem_end.get_raw() + 1 - self.mem_start.get_raw();
+    }
+
+    fn contains(&self, address: Address) -> bool {
+        return self.mem_start.get_raw() <= address.get_raw()
+            && address.get_raw() <= self.mem_end.get_raw();
+    }
+}
+
+impl Region {
+    fn new(base: Address) -> Self {
+        Self {
+            pos: base,
+            free: Vec::new(),
+        }
+    }
+
+    /// Where a section of `size` bytes goes, the first freed range it fits in or the end
+    fn allocate(&self, size: usize) -> Address {
+        return self
+            .free
+            .iter()
+            .find(|(_, free)| *free >= size)
+            .map_or(self.pos, |(start, _)| *start);
+    }
+
+    /// Take `start..start + size` from the free ranges or move the end past it
+    fn commit(&mut self, start: Address, size: usize) {
+        match self.free.iter().position(|(free, _)| *free == start) {
+            Some(index) if self.free[index].1 == size => {
+                self.free.remove(index);
+            }
+            Some(index) => self.free[index] = (start + size, self.free[index].1 - size),
+            None => self.pos = start + size,
+        }
+    }
+
+    fn release(&mut self, mut start: Address, mut size: usize) {
+        self.free.retain(|(free_start, free_size)| {
+            if free_start.get_raw() + free_size == start.get_raw() {
+                start = *free_start;
+                size += free_size;
+                return false;
+            }
+            if start.get_raw() + size == free_start.get_raw() {
+                size += free_size;
+                return false;
+            }
+            return true;
+        });
+        if start.get_raw() + size == self.pos.get_raw() {
+            self.pos = start;
+        } else {
+            self.free.push((start, size));
+        }
+    }
 }
 
 impl SectionManager {
-    pub fn new() -> Self {
+    pub fn new(layout: Layout) -> Self {
         Self {
             sections: NoHashHashMap::default(),
-            write_pos: Address::new(0),
+            retired: Vec::new(),
+            layout,
+            code: Region::new(layout.code_base()),
+            constants: Region::new(layout.constant_base()),
         }
     }
 
+    pub fn layout(&self) -> &Layout {
+        return &self.layout;
+    }
+
     pub fn get_section_hash(&self, hash: u64) -> Option<&LoadedSection> {
         return self.sections.get(&hash);
     }
@@ -49,30 +141,140 @@ impl SectionManager {
         return self.get_section_hash(xxh3_64(name.as_ref().as_bytes()));
     }
 
+    /// Find the loaded or retired section that contains `address`
+    pub fn find_section(&self, address: Address) -> Option<(u64, &LoadedSection)> {
+        return self
+            .sections
+            .iter()
+            .map(|(hash, section)| (*hash, section))
+            .chain(self.retired.iter().map(|(hash, section)| (*hash, section)))
+            .find(|(_, section)| section.contains(address));
+    }
+
+    /// Resolve the section a jump at `ip` to section `hash` lands in, code of a replaced
+    /// procedure keeps jumping inside itself until it returns
+    pub fn jump_section(&self, ip: Address, hash: u64) -> Option<&LoadedSection> {
+        return match self.find_section(ip) {
+            Some((current, section)) if current == hash => Some(section),
+            _ => self.get_section_hash(hash),
+        };
+    }
+
+    /// Sections reachable by hash
+    pub fn sections(&self) -> impl Iterator<Item = (u64, &LoadedSection)> + '_ {
+        return self.sections.iter().map(|(hash, section)| (*hash, section));
+    }
+
+    /// Sections that were replaced or unloaded and are still in memory
+    pub fn retired(&self) -> impl Iterator<Item = (u64, &LoadedSection)> + '_ {
+        return self.retired.iter().map(|(hash, section)| (*hash, section));
+    }
+
     pub fn set_section_hash(&mut self, hash: u64, section: LoadedSection) {
         self.sections.insert(hash, section);
     }
 
+    fn region(&mut self, ty: SectionType) -> &mut Region {
+        return match ty {
+            SectionType::Constant => &mut self.constants,
+            _ => &mut self.code,
+        };
+    }
+
+    /// Load a section, procedures go to the code region and constants to the constant region.
+    /// A section with the hash of a loaded one replaces it, the old one is retired. Empty
+    /// sections are rejected
     pub fn load_section(
         &mut self,
         section: &SinSection,
         data: &[u8],
         memory: &mut Memory,
-    ) -> &LoadedSection {
-        let data = &data[section.start() as usize..section.end() as usize];
+    ) -> Result<&LoadedSection, LoadError> {
+        let data = data
+            .get(section.start() as usize..section.end() as usize)
+            .ok_or(SinError::InvalidSection)?;
+        if data.is_empty() {
+            return Err(LoadError::EmptySection(section.hash()));
+        }
+        let start = self.region(section.section_type()).allocate(data.len());
+        self.place(section.hash(), start, data.len())?;
+        self.region(section.section_type())
+            .commit(start, data.len());
         memory
-            .mem_sets(self.write_pos, data)
-            .expect("Not enough memory to load the section");
+            .mem_sets(start, data)
+            .expect("The layout makes sure the section fits in memory");
+        self.unload_section(section.hash());
         self.set_section_hash(
             section.hash(),
             LoadedSection {
                 ty: section.section_type(),
-                mem_start: self.write_pos,
-                mem_end: self.write_pos + data.len() - 1,
+                mem_start: start,
+                mem_end: start + data.len() - 1,
             },
         );
 
-        self.write_pos += data.len();
-        return self.get_section_hash(section.hash()).unwrap();
+        return Ok(self.get_section_hash(section.hash()).unwrap());
+    }
+
+    fn place(&self, hash: u64, start: Address, size: usize) -> Result<(), LayoutError> {
+        self.layout.check_section(hash, start, size)?;
+        let end = start.get_raw() + size;
+        if self
+            .sections
+            .values()
+            .chain(self.retired.iter().map(|(_, section)| section))
+            .any(|loaded| {
+                start.get_raw() <= loaded.mem_end.get_raw() && loaded.mem_start.get_raw() < end
+            })
+        {
+            return Err(LayoutError::SectionOverlap(
+                hash,
+                start,
+                size,
+                "previously loaded sections",
+            ));
+        }
+        return Ok(());
+    }
+
+    /// Make a section unreachable by hash, the memory of a procedure is freed by `reclaim`
+    /// once no code runs in it, the one of a constant by `free_constants`. Returns false if it
+    /// isn't loaded
+    pub fn unload_section(&mut self, hash: u64) -> bool {
+        let Some(section) = self.sections.remove(&hash) else {
+            return false;
+        };
+        self.retired.push((hash, section));
+        return true;
+    }
+
+    /// Free the retired procedures none of `live` points into, returns the number of bytes
+    /// freed
+    pub fn reclaim(&mut self, live: &[Address], memory: &mut Memory) -> usize {
+        return self.free(memory, |section| {
+            section.ty == SectionType::Procedure
+                && !live.iter().any(|address| section.contains(*address))
+        });
+    }
+
+    /// Free every retired constant section, returns the number of bytes freed. Registers and
+    /// memory can still hold addresses into them, so this is never done implicitly
+    pub fn free_constants(&mut self, memory: &mut Memory) -> usize {
+        return self.free(memory, |section| section.ty != SectionType::Procedure);
+    }
+
+    fn free(&mut self, memory: &mut Memory, freed: impl Fn(&LoadedSection) -> bool) -> usize {
+        let (freed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.retired)
+            .into_iter()
+            .partition(|(_, section)| freed(section));
+        self.retired = kept;
+        let mut total = 0;
+        for (_, section) in freed {
+            let size = section.size();
+            memory.discard(section.mem_start, size);
+            self.region(section.ty).release(section.mem_start, size);
+            total += size;
+        }
+        return total;
     }
 }
@@ -0,0 +1,292 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+};
+
+use common::{
+    isa::decoded::{DecodeError, Decoded, Value},
+    sin::{
+        sections::{SectionType, SinSection},
+        Sin,
+    },
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum VerifierErrorKind {
+    SectionOutOfBounds,
+    InvalidInstructionLength(usize),
+    TruncatedInstruction(usize),
+    UnknownOpCode(u16),
+    UnknownSubOpCode(u16, u8),
+    TruncatedOperands(u16),
+    TrailingOperands(u16, usize),
+    InvalidRegister(u8),
+    InvalidBoolean(u8),
+    UndefinedSection(u64),
+    NotProcedureSection(u64),
+    JumpOutOfSection(u64, u16),
+    JumpNotOnBoundary(u64, u16),
+}
+
+/// A problem found in a sin file, located by the section hash and the byte offset inside it
+#[derive(Debug)]
+pub struct VerifierError {
+    section: u64,
+    offset: usize,
+    kind: VerifierErrorKind,
+}
+
+impl VerifierError {
+    fn new(section: u64, offset: usize, kind: VerifierErrorKind) -> Self {
+        Self {
+            section,
+            offset,
+            kind,
+        }
+    }
+
+    pub fn section(&self) -> u64 {
+        return self.section;
+    }
+
+    pub fn offset(&self) -> usize {
+        return self.offset;
+    }
+
+    pub fn kind(&self) -> &VerifierErrorKind {
+        return &self.kind;
+    }
+}
+
+impl Display for VerifierErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SectionOutOfBounds => write!(f, "section lies outside of the sin data"),
+            Self::InvalidInstructionLength(length) => {
+                write!(f, "invalid instruction length: {}", length)
+            }
+            Self::TruncatedInstruction(length) => write!(
+                f,
+                "instruction length {} goes past the end of the section",
+                length
+            ),
+            Self::UnknownOpCode(opcode) => write!(f, "unknown op code: {}", opcode),
+            Self::UnknownSubOpCode(opcode, sub_opcode) => write!(
+                f,
+                "unknown sub op code {} for op code {}",
+                sub_opcode, opcode
+            ),
+            Self::TruncatedOperands(opcode) => {
+                write!(f, "not enough operand bytes for op code {}", opcode)
+            }
+            Self::TrailingOperands(opcode, count) => write!(
+                f,
+                "{} unexpected trailing operand bytes for op code {}",
+                count, opcode
+            ),
+            Self::InvalidRegister(byte) => write!(f, "invalid register byte: {}", byte),
+            Self::InvalidBoolean(byte) => write!(f, "invalid boolean byte: {}", byte),
+            Self::UndefinedSection(hash) => write!(f, "reference to undefined section {:#x}", hash),
+            Self::NotProcedureSection(hash) => {
+                write!(
+                    f,
+                    "call or jump target {:#x} is not a procedure section",
+                    hash
+                )
+            }
+            Self::JumpOutOfSection(hash, offset) => write!(
+                f,
+                "jump target {} is outside of section {:#x}",
+                offset, hash
+            ),
+            Self::JumpNotOnBoundary(hash, offset) => write!(
+                f,
+                "jump target {} in section {:#x} is not on an instruction boundary",
+                offset, hash
+            ),
+        }
+    }
+}
+
+impl Display for VerifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "section {:#x} at offset {}: {}",
+            self.section, self.offset, self.kind
+        )
+    }
+}
+
+impl Error for VerifierError {}
+
+impl From<DecodeError> for VerifierErrorKind {
+    fn from(value: DecodeError) -> Self {
+        match value {
+            DecodeError::InvalidInstructionLength(length) => Self::InvalidInstructionLength(length),
+            DecodeError::TruncatedInstruction(length) => Self::TruncatedInstruction(length),
+            DecodeError::UnknownOpCode(opcode) => Self::UnknownOpCode(opcode),
+            DecodeError::UnknownSubOpCode(opcode, sub_opcode) => {
+                Self::UnknownSubOpCode(opcode, sub_opcode)
+            }
+            DecodeError::TruncatedOperands(opcode) => Self::TruncatedOperands(opcode),
+            DecodeError::TrailingOperands(opcode, count) => Self::TrailingOperands(opcode, count),
+            DecodeError::InvalidRegister(byte) => Self::InvalidRegister(byte),
+            DecodeError::InvalidBoolean(byte) => Self::InvalidBoolean(byte),
+        }
+    }
+}
+
+struct JumpTarget {
+    section: u64,
+    offset: usize,
+    target_section: u64,
+    target_offset: u16,
+}
+
+/// Checks a sin file before it is loaded so malformed code is rejected up front
+///
+/// The first pass decodes every procedure section, recording instruction boundaries and
+/// jump targets. The second pass checks that every jump lands on one of those boundaries.
+pub struct Verifier<'a> {
+    sin: &'a Sin<'a>,
+    section_types: HashMap<u64, SectionType>,
+    boundaries: HashMap<u64, (usize, HashSet<usize>)>,
+    jumps: Vec<JumpTarget>,
+    errors: Vec<VerifierError>,
+}
+
+impl<'a> Verifier<'a> {
+    pub fn new(sin: &'a Sin<'a>) -> Self {
+        Self {
+            sin,
+            section_types: sin
+                .sections()
+                .iter()
+                .map(|section| (section.hash(), section.section_type()))
+                .collect(),
+            boundaries: HashMap::new(),
+            jumps: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn verify(mut self) -> Result<(), Vec<VerifierError>> {
+        for section in self.sin.sections() {
+            let Some(data) = self
+                .sin
+                .data()
+                .get(section.start() as usize..section.end() as usize)
+            else {
+                self.errors.push(VerifierError::new(
+                    section.hash(),
+                    0,
+                    VerifierErrorKind::SectionOutOfBounds,
+                ));
+                continue;
+            };
+            if section.section_type() == SectionType::Procedure {
+                self.verify_procedure(section, data);
+            }
+        }
+
+        for jump in self.jumps.iter() {
+            let kind = match self.boundaries.get(&jump.target_section) {
+                Some((length, _)) if jump.target_offset as usize >= *length => {
+                    VerifierErrorKind::JumpOutOfSection(jump.target_section, jump.target_offset)
+                }
+                Some((_, boundaries)) if boundaries.contains(&(jump.target_offset as usize)) => {
+                    continue;
+                }
+                Some(_) => {
+                    VerifierErrorKind::JumpNotOnBoundary(jump.target_section, jump.target_offset)
+                }
+                None if self.section_types.contains_key(&jump.target_section) => {
+                    VerifierErrorKind::NotProcedureSection(jump.target_section)
+                }
+                None => VerifierErrorKind::UndefinedSection(jump.target_section),
+            };
+            self.errors
+                .push(VerifierError::new(jump.section, jump.offset, kind));
+        }
+
+        if self.errors.is_empty() {
+            return Ok(());
+        } else {
+            return Err(self.errors);
+        }
+    }
+
+    fn verify_procedure(&mut self, section: &SinSection, data: &[u8]) {
+        let mut boundaries = HashSet::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            boundaries.insert(offset);
+            let length = data[offset] as usize;
+            match Decoded::decode(&data[offset..]) {
+                Ok(decoded) => {
+                    if let Err(kind) = self.verify_instruction(section.hash(), offset, &decoded) {
+                        self.errors
+                            .push(VerifierError::new(section.hash(), offset, kind));
+                    }
+                }
+                Err(
+                    error @ (DecodeError::InvalidInstructionLength(_)
+                    | DecodeError::TruncatedInstruction(_)),
+                ) => {
+                    self.errors
+                        .push(VerifierError::new(section.hash(), offset, error.into()));
+                    break;
+                }
+                Err(error) => {
+                    self.errors
+                        .push(VerifierError::new(section.hash(), offset, error.into()));
+                }
+            }
+            offset += length;
+        }
+        self.boundaries
+            .insert(section.hash(), (data.len(), boundaries));
+    }
+
+    fn verify_instruction(
+        &mut self,
+        section: u64,
+        offset: usize,
+        decoded: &Decoded,
+    ) -> Result<(), VerifierErrorKind> {
+        for value in decoded.values() {
+            match value {
+                Value::Section(hash) => {
+                    if !self.section_types.contains_key(hash) {
+                        return Err(VerifierErrorKind::UndefinedSection(*hash));
+                    }
+                }
+                Value::Procedure(hash) => match self.section_types.get(hash) {
+                    Some(SectionType::Procedure) => {}
+                    Some(_) => return Err(VerifierErrorKind::NotProcedureSection(*hash)),
+                    None => return Err(VerifierErrorKind::UndefinedSection(*hash)),
+                },
+                Value::Jump(target_section, target_offset) => self.jumps.push(JumpTarget {
+                    section,
+                    offset,
+                    target_section: *target_section,
+                    target_offset: *target_offset,
+                }),
+                Value::JumpTable(target_section, table) => {
+                    for target_offset in table {
+                        self.jumps.push(JumpTarget {
+                            section,
+                            offset,
+                            target_section: *target_section,
+                            target_offset: *target_offset,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        return Ok(());
+    }
+}
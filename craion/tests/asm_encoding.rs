@@ -0,0 +1,49 @@
+use raion::compiler::CompilerError;
+
+mod modes;
+
+use modes::assemble;
+
+/// `mov` immediates take the width of their register and jumps patch a u16 label offset
+const SOURCE: &str = r#"
+proc start -> {
+    mov a8, 200
+    jmp skip
+    mov a8, 1
+skip:
+    mov b64, 7
+    add a64, b64
+    ret
+}
+"#;
+
+#[test]
+fn immediate_width() {
+    let (_, data) = assemble(SOURCE).unwrap();
+    // Length byte, opcode, sub opcode, register and one byte of immediate
+    assert_eq!(data[0], 6);
+    assert_eq!(data[5], 200);
+}
+
+#[test]
+fn label_offset() {
+    for mut executor in modes::executors(0xFFFF) {
+        let (sections, data) = assemble(SOURCE).unwrap();
+        for section in sections.iter() {
+            executor.load_section(section, &data).unwrap();
+        }
+        assert_eq!(executor.call("start", &[]).unwrap(), 207);
+    }
+}
+
+#[test]
+fn label_out_of_range() {
+    let far = format!(
+        "proc start -> {{\n    jmp far\n{}far:\n    ret\n}}\n",
+        "    mov a64, b64\n".repeat(0x3000)
+    );
+    assert!(matches!(
+        assemble(&far),
+        Err(CompilerError::InvalidArgument(_))
+    ));
+}
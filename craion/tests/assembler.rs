@@ -0,0 +1,150 @@
+use common::{register::RegisterType::*, sin::Sin};
+use craion::{
+    assembler::{Assembler, AssemblerError},
+    executor::{builder::ExecutorBuilder, Executor, LoadError},
+};
+
+#[test]
+fn labels() {
+    let mut assembler = Assembler::new();
+    assembler
+        .procedure("main")
+        .mov_reg_num(A64, 0)
+        .mov_reg_num(B64, 3)
+        .jmp("start")
+        .label("back")
+        .add_reg_num(A64, 100)
+        .jmp("end")
+        .label("start")
+        .mov_reg_num(C64, 2)
+        .jtab(C64, &["first", "second", "back"])
+        .label("first")
+        .inc(A64)
+        .label("second")
+        .inc(A64)
+        .label("end")
+        .ret();
+    let mut executor = Executor::new(0xFFFF);
+    assembler.load(&mut executor).unwrap();
+    assert_eq!(executor.call("main", &[]).unwrap(), 100);
+}
+
+#[test]
+fn sections() {
+    let mut assembler = Assembler::new();
+    assembler
+        .procedure("main")
+        .mov_reg_section(B64, "values")
+        .mov_reg_offset(A64, B64, 8)
+        .call("double")
+        .ret()
+        .procedure("double")
+        .mov_reg_reg(B64, A64)
+        .add_reg_reg(A64, B64)
+        .ret()
+        .constant("values")
+        .bytes(&1u64.to_le_bytes())
+        .bytes(&21u64.to_le_bytes());
+    let program = assembler.finish().unwrap();
+    assert_eq!(program.sections().len(), 3);
+    assert_eq!(program.sections()[2].end() as usize, program.data().len());
+
+    let bytes = program.to_bytes();
+    let sin = Sin::from_bytes(&bytes).unwrap();
+    assert_eq!(sin.sections(), program.sections());
+    let mut executor = ExecutorBuilder::new()
+        .bytes(&bytes)
+        .entry("main")
+        .build()
+        .unwrap();
+    assert_eq!(executor.call("main", &[]).unwrap(), 42);
+
+    let mut executor = Executor::new(0xFFFF);
+    program.load(&mut executor).unwrap();
+    assert_eq!(executor.call("double", &[]).unwrap(), 0);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        Assembler::new()
+            .procedure("main")
+            .jmp("nowhere")
+            .finish()
+            .unwrap_err(),
+        AssemblerError::UndefinedLabel("main".to_string(), "nowhere".to_string())
+    );
+    assert_eq!(
+        Assembler::new()
+            .procedure("main")
+            .label("a")
+            .ret()
+            .label("a")
+            .finish()
+            .unwrap_err(),
+        AssemblerError::DuplicateLabel("main".to_string(), "a".to_string())
+    );
+    assert_eq!(
+        Assembler::new()
+            .procedure("main")
+            .procedure("main")
+            .finish()
+            .unwrap_err(),
+        AssemblerError::DuplicateSection("main".to_string())
+    );
+    assert_eq!(
+        Assembler::new().ret().finish().unwrap_err(),
+        AssemblerError::NoSection
+    );
+    assert_eq!(
+        Assembler::new()
+            .procedure("main")
+            .mov_reg_num(A8, 256)
+            .finish()
+            .unwrap_err(),
+        AssemblerError::ImmediateTooLarge(A8, 256)
+    );
+    let labels = vec!["a"; 200];
+    assert!(matches!(
+        Assembler::new()
+            .procedure("main")
+            .label("a")
+            .jtab(A64, &labels)
+            .finish(),
+        Err(AssemblerError::InstructionTooLong(..))
+    ));
+    let mut assembler = Assembler::new();
+    assembler
+        .procedure("main")
+        .bytes(&[0; 0x10000])
+        .label("far")
+        .jmp("far");
+    assert_eq!(
+        assembler.finish().unwrap_err(),
+        AssemblerError::LabelOutOfRange("main".to_string(), "far".to_string())
+    );
+
+    let mut assembler = Assembler::new();
+    assembler.procedure("main").mov_reg_num(A8, 256);
+    assert_eq!(
+        assembler.finish().unwrap_err(),
+        assembler.finish().unwrap_err()
+    );
+
+    let mut assembler = Assembler::new();
+    assembler.procedure("main");
+    for _ in 0..100 {
+        assembler.mov_reg_num(A64, 1);
+    }
+    assert!(matches!(
+        assembler.load(&mut Executor::new(0x200)),
+        Err(AssemblerError::Load(LoadError::Layout(..)))
+    ));
+
+    let mut assembler = Assembler::new();
+    assembler.procedure("empty");
+    assert!(matches!(
+        assembler.load(&mut Executor::new(0x200)),
+        Err(AssemblerError::Load(LoadError::EmptySection(..)))
+    ));
+}
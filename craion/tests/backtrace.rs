@@ -0,0 +1,52 @@
+use common::{
+    constants::{CALL_OPCODE, HALT_OPCODE, INC_OPCODE, RESTR_OPCODE},
+    register::RegisterType,
+    sin::sections::{SectionType, SinSection},
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::instruction;
+
+#[test]
+fn backtrace_through_call() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start_hash = xxh3_64(b"start");
+        let fail_hash = xxh3_64(b"fail");
+        let mut data = instruction(CALL_OPCODE, &fail_hash.to_le_bytes());
+        data.extend(instruction(HALT_OPCODE, &[]));
+        let start_end = data.len() as u64;
+        data.extend(instruction(INC_OPCODE, &[RegisterType::A64.to_byte()]));
+        data.extend(instruction(RESTR_OPCODE, &[RegisterType::Sp.to_byte()]));
+        data.extend(instruction(HALT_OPCODE, &[]));
+        executor
+            .load_section(
+                &SinSection::new(SectionType::Procedure, start_hash, 0, start_end),
+                &data,
+            )
+            .unwrap();
+        executor
+            .load_section(
+                &SinSection::new(
+                    SectionType::Procedure,
+                    fail_hash,
+                    start_end,
+                    data.len() as u64,
+                ),
+                &data,
+            )
+            .unwrap();
+        let entry = executor.section_manager().get_section("start").unwrap();
+        let entry = entry.mem_start();
+        executor.registers().set_ip(entry);
+        executor.execute();
+
+        assert_eq!(executor.ret_stack().frames().count(), 1);
+        let backtrace = executor.backtrace(executor.registers_ref().get_ip());
+        let frames = backtrace.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].section(), Some((fail_hash, 8)));
+        assert_eq!(frames[1].section(), Some((start_hash, 10)));
+    }
+}
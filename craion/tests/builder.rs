@@ -0,0 +1,161 @@
+use common::{
+    constants::{
+        ARG_NUM, ARG_OPCODE, CALL_OPCODE, HALT_OPCODE, INC_OPCODE, JMP_OPCODE, LARG_OPCODE,
+        RET_OPCODE,
+    },
+    register::RegisterType,
+    sin::{
+        sections::{SectionType, SinSection},
+        Sin,
+    },
+};
+use craion::{
+    executor::{
+        builder::{BuildError, ExecutorBuilder},
+        ExecutionError,
+    },
+    layout::Layout,
+    memory::address::Address,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::instruction;
+
+fn sections(procedures: &[(&str, Vec<u8>)]) -> (Vec<SinSection>, Vec<u8>) {
+    let mut data = Vec::new();
+    let mut sections = Vec::new();
+    for (name, code) in procedures {
+        let start = data.len() as u64;
+        data.extend_from_slice(code);
+        sections.push(SinSection::new(
+            SectionType::Procedure,
+            xxh3_64(name.as_bytes()),
+            start,
+            data.len() as u64,
+        ));
+    }
+    return (sections, data);
+}
+
+/// `main` calls `inc` with 41 and halts with the result in a64
+fn program() -> (Vec<SinSection>, Vec<u8>) {
+    let mut arg = vec![ARG_NUM];
+    arg.extend_from_slice(&0u32.to_le_bytes());
+    arg.extend_from_slice(&41u64.to_le_bytes());
+    let main = [
+        instruction(ARG_OPCODE, &arg),
+        instruction(CALL_OPCODE, &xxh3_64(b"inc").to_le_bytes()),
+        instruction(HALT_OPCODE, &[]),
+    ]
+    .concat();
+    let inc = [
+        instruction(LARG_OPCODE, &[RegisterType::A64.to_byte(), 0, 0, 0, 0]),
+        instruction(INC_OPCODE, &[RegisterType::A64.to_byte()]),
+        instruction(RET_OPCODE, &[]),
+    ]
+    .concat();
+    return sections(&[("main", main), ("inc", inc)]);
+}
+
+#[test]
+fn build_and_run() {
+    let (sections, data) = program();
+    let bytes = Sin::new(sections, &data).to_bytes();
+    for threshold in [None, Some(0)] {
+        for from_bytes in [false, true] {
+            let mut builder = ExecutorBuilder::new()
+                .layout(Layout::new(0x10000).with_stack_size(0x1000))
+                .entry("main");
+            builder = match from_bytes {
+                true => builder.bytes(&bytes),
+                false => builder.sin(Sin::new(program().0, &data)),
+            };
+            if let Some(threshold) = threshold {
+                builder = builder.jit(threshold);
+            }
+            let mut executor = builder.build().unwrap();
+
+            assert_eq!(executor.registers_ref().get_sp(), Address::new(0x10000));
+            executor.execute();
+            assert!(executor.registers_ref().get_halt());
+            assert_eq!(
+                executor
+                    .registers()
+                    .get_general(&RegisterType::A64)
+                    .unwrap(),
+                42
+            );
+        }
+    }
+}
+
+#[test]
+fn build_errors() {
+    let (_, data) = program();
+    let build = |entry: &str| {
+        return ExecutorBuilder::new()
+            .memory_size(0x10000)
+            .sin(Sin::new(program().0, &data))
+            .entry(entry)
+            .build();
+    };
+
+    assert!(matches!(build("start"), Err(BuildError::EntryNotFound(name)) if name == "start"));
+    assert!(matches!(
+        ExecutorBuilder::new()
+            .memory_size(0x10000)
+            .stack(Address::new(0x20000), 0x1000)
+            .build(),
+        Err(BuildError::Layout(_))
+    ));
+    let (sections, data) = sections_with_bad_opcode();
+    assert!(matches!(
+        ExecutorBuilder::new()
+            .sin(Sin::new(sections, &data))
+            .build(),
+        Err(BuildError::Verify(errors)) if errors.len() == 1
+    ));
+}
+
+fn sections_with_bad_opcode() -> (Vec<SinSection>, Vec<u8>) {
+    return sections(&[("start", instruction(0xFFFE, &[]))]);
+}
+
+/// `start` increments a64 forever
+fn endless_loop() -> (Vec<SinSection>, Vec<u8>) {
+    let mut jump = xxh3_64(b"start").to_le_bytes().to_vec();
+    jump.extend_from_slice(&0u16.to_le_bytes());
+    let start = [
+        instruction(INC_OPCODE, &[RegisterType::A64.to_byte()]),
+        instruction(JMP_OPCODE, &jump),
+    ]
+    .concat();
+    return sections(&[("start", start)]);
+}
+
+#[test]
+fn step_limit() {
+    let (_, data) = endless_loop();
+    for threshold in [None, Some(0)] {
+        let mut builder = ExecutorBuilder::new()
+            .memory_size(0x10000)
+            .sin(Sin::new(endless_loop().0, &data))
+            .step_limit(100);
+        if let Some(threshold) = threshold {
+            builder = builder.jit(threshold);
+        }
+        let mut executor = builder.build().unwrap();
+
+        assert!(matches!(
+            executor.call("start", &[]),
+            Err(ExecutionError::StepLimit(_))
+        ));
+        assert_eq!(executor.steps(), 100);
+        executor.set_step_limit(Some(150));
+        executor.execute();
+        assert_eq!(executor.steps(), 150);
+        assert!(!executor.registers_ref().get_halt());
+    }
+}
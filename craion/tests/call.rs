@@ -0,0 +1,203 @@
+use common::{
+    constants::{
+        ADD_OPCODE, ADD_REG_W_NUM, ADD_REG_W_REG, ARG_NUM, ARG_OPCODE, CALL_OPCODE, HALT_OPCODE,
+        RET_OPCODE,
+    },
+    register::RegisterType,
+};
+use craion::{
+    executor::{ExecutionError, Executor},
+    memory::address::Address,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, larg, load_procedures};
+
+fn arg(index: u32, value: u64) -> Vec<u8> {
+    let mut args = vec![ARG_NUM];
+    args.extend_from_slice(&index.to_le_bytes());
+    args.extend_from_slice(&value.to_le_bytes());
+    return instruction(ARG_OPCODE, &args);
+}
+
+fn call(name: &str) -> Vec<u8> {
+    return instruction(CALL_OPCODE, &xxh3_64(name.as_bytes()).to_le_bytes());
+}
+
+#[test]
+fn nested_call_keeps_arguments() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start = [arg(0, 1), call("outer"), instruction(HALT_OPCODE, &[])].concat();
+        let outer = [
+            arg(0, 2),
+            call("inner"),
+            larg(RegisterType::C64, 0),
+            instruction(RET_OPCODE, &[]),
+        ]
+        .concat();
+        let inner = [larg(RegisterType::B64, 0), instruction(RET_OPCODE, &[])].concat();
+        load_procedures(
+            &mut executor,
+            &[("start", start), ("outer", outer), ("inner", inner)],
+        );
+        executor.execute();
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::B64)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::C64)
+                .unwrap(),
+            1
+        );
+    }
+}
+
+#[test]
+fn arguments_do_not_leak_across_calls() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start = [
+            arg(0, 5),
+            call("first"),
+            call("second"),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        let first = instruction(RET_OPCODE, &[]);
+        let second = [larg(RegisterType::A64, 0), instruction(RET_OPCODE, &[])].concat();
+        load_procedures(
+            &mut executor,
+            &[("start", start), ("first", first), ("second", second)],
+        );
+        executor.execute();
+        assert_eq!(executor.registers().get_halt(), false);
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            0
+        );
+    }
+}
+
+fn add_procedure() -> Vec<u8> {
+    return [
+        larg(RegisterType::A64, 0),
+        larg(RegisterType::B64, 1),
+        instruction(
+            ADD_OPCODE,
+            &[
+                ADD_REG_W_REG,
+                RegisterType::A64.to_byte(),
+                RegisterType::B64.to_byte(),
+            ],
+        ),
+        instruction(RET_OPCODE, &[]),
+    ]
+    .concat();
+}
+
+#[test]
+fn call_from_host() {
+    for mut executor in modes::executors(0xFFFF) {
+        let mut add_one = vec![ADD_REG_W_NUM, RegisterType::A64.to_byte()];
+        add_one.extend_from_slice(&1u64.to_le_bytes());
+        let twice = [
+            larg(RegisterType::C64, 0),
+            arg(0, 5),
+            arg(1, 6),
+            call("add"),
+            instruction(ADD_OPCODE, &add_one),
+            instruction(RET_OPCODE, &[]),
+        ]
+        .concat();
+        let start = instruction(HALT_OPCODE, &[]);
+        load_procedures(
+            &mut executor,
+            &[("start", start), ("add", add_procedure()), ("twice", twice)],
+        );
+        let ip = executor.registers_ref().get_ip();
+
+        assert_eq!(executor.call("add", &[3, 4]).unwrap(), 7);
+        assert_eq!(executor.call("twice", &[9]).unwrap(), 12);
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::C64)
+                .unwrap(),
+            9
+        );
+        assert_eq!(executor.registers_ref().get_ip(), ip);
+        assert!(!executor.registers_ref().get_halt());
+    }
+}
+
+#[test]
+fn call_from_host_errors() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start = instruction(HALT_OPCODE, &[]);
+        let halts = instruction(HALT_OPCODE, &[]);
+        let missing = [call("missing"), instruction(RET_OPCODE, &[])].concat();
+        load_procedures(
+            &mut executor,
+            &[
+                ("start", start),
+                ("halts", halts),
+                ("calls_missing", missing),
+            ],
+        );
+
+        assert!(matches!(
+            executor.call("missing", &[]),
+            Err(ExecutionError::UndefinedProcedure(name)) if name == "missing"
+        ));
+        let ip = executor.registers_ref().get_ip();
+        assert!(matches!(
+            executor.call("halts", &[]),
+            Err(ExecutionError::Halted(0))
+        ));
+        assert_eq!(executor.ret_stack().len(), 0);
+        assert_eq!(executor.registers_ref().get_ip(), ip);
+        assert!(matches!(
+            executor.call("halts", &[]),
+            Err(ExecutionError::Halted(0))
+        ));
+        let section = executor
+            .section_manager()
+            .get_section("calls_missing")
+            .unwrap()
+            .mem_start();
+        assert!(matches!(
+            executor.call("calls_missing", &[]),
+            Err(ExecutionError::Instruction { ip, .. }) if ip == section
+        ));
+    }
+}
+
+/// A host call pushes the ip as its return address, which is 0 on a fresh executor
+#[test]
+fn backtrace_from_address_zero() {
+    let mut executor = Executor::new(0xFFFF);
+    load_procedures(
+        &mut executor,
+        &[
+            ("start", instruction(HALT_OPCODE, &[])),
+            (
+                "calls_missing",
+                [call("missing"), instruction(RET_OPCODE, &[])].concat(),
+            ),
+        ],
+    );
+    executor.registers().set_ip(Address::new(0));
+    let error = executor.call("calls_missing", &[]).unwrap_err();
+    let backtrace = executor.backtrace(error.ip().unwrap());
+    assert_eq!(backtrace.frames().len(), 2);
+}
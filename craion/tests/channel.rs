@@ -0,0 +1,158 @@
+use std::{thread, time::Duration};
+
+use common::{
+    constants::{
+        ADD_OPCODE, ADD_REG_W_REG, ARG_OPCODE, ARG_REG, CHAN_OPCODE, CLOSE_OPCODE, HALT_OPCODE,
+        JMC_OPCODE, JMP_OPCODE, JOIN_OPCODE, LARG_OPCODE, RECV_OPCODE, RET_OPCODE, SEND_OPCODE,
+        SPAWN_OPCODE,
+    },
+    register::RegisterType,
+};
+use craion::executor::channel::ChannelError;
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, jump, load_procedures, mov_num};
+
+fn registers(opcode: u16, registers: &[RegisterType]) -> Vec<u8> {
+    let args: Vec<u8> = registers
+        .iter()
+        .map(|register| register.to_byte())
+        .collect();
+    return instruction(opcode, &args);
+}
+
+/// Sum the messages of the channel in b64 into a64 until it is closed, then run `end`. `base`
+/// is the offset of the returned code in `procedure`
+fn sum(procedure: &str, base: usize, end: Vec<u8>) -> Vec<u8> {
+    let head = mov_num(RegisterType::A64, 0);
+    let recv = registers(RECV_OPCODE, &[RegisterType::C64, RegisterType::B64]);
+    let add = instruction(
+        ADD_OPCODE,
+        &[
+            ADD_REG_W_REG,
+            RegisterType::A64.to_byte(),
+            RegisterType::C64.to_byte(),
+        ],
+    );
+    let jump_back = jump(JMP_OPCODE, procedure, base + head.len());
+    let done = base + head.len() + recv.len() + jump_back.len() * 2 + add.len();
+    return [
+        head,
+        recv,
+        jump(JMC_OPCODE, procedure, done),
+        add,
+        jump_back,
+        end,
+    ]
+    .concat();
+}
+
+#[test]
+fn guest_producer_consumer() {
+    for mut executor in modes::executors(0xFFFF) {
+        let mut start = registers(CHAN_OPCODE, &[RegisterType::B64]);
+        start.extend(instruction(
+            ARG_OPCODE,
+            &[ARG_REG, 0, 0, 0, 0, RegisterType::B64.to_byte()],
+        ));
+        let mut spawn = vec![RegisterType::D64.to_byte()];
+        spawn.extend_from_slice(&xxh3_64(b"consumer").to_le_bytes());
+        start.extend(instruction(SPAWN_OPCODE, &spawn));
+        for value in [3, 5, 7] {
+            start.extend(mov_num(RegisterType::C64, value));
+            start.extend(registers(
+                SEND_OPCODE,
+                &[RegisterType::B64, RegisterType::C64],
+            ));
+        }
+        start.extend(registers(CLOSE_OPCODE, &[RegisterType::B64]));
+        start.extend(registers(
+            JOIN_OPCODE,
+            &[RegisterType::A64, RegisterType::D64],
+        ));
+        start.extend(instruction(HALT_OPCODE, &[]));
+        let larg = instruction(LARG_OPCODE, &[RegisterType::B64.to_byte(), 0, 0, 0, 0]);
+        let body = sum("consumer", larg.len(), instruction(RET_OPCODE, &[]));
+        let consumer = [larg, body].concat();
+        load_procedures(&mut executor, &[("start", start), ("consumer", consumer)]);
+        executor.execute();
+
+        assert!(executor.registers_ref().get_halt());
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            15
+        );
+    }
+}
+
+#[test]
+fn host_sends_into_running_executor() {
+    for mut executor in modes::executors(0xFFFF) {
+        let id = executor.create_channel();
+        let head = mov_num(RegisterType::B64, id);
+        let body = sum("start", head.len(), instruction(HALT_OPCODE, &[]));
+        let start = [head, body].concat();
+        load_procedures(&mut executor, &[("start", start)]);
+        let channel = executor.channel(id).unwrap();
+        let host = thread::spawn(move || {
+            for value in 1..=4 {
+                thread::sleep(Duration::from_millis(5));
+                channel.send(value).unwrap();
+            }
+            channel.close();
+            assert_eq!(channel.send(5), Err(ChannelError::Closed));
+        });
+        executor.execute();
+        host.join().unwrap();
+
+        assert!(executor.registers_ref().get_halt());
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            10
+        );
+    }
+}
+
+#[test]
+fn guest_sends_to_host() {
+    for mut executor in modes::executors(0xFFFF) {
+        let id = executor.create_channel();
+        let start = [
+            mov_num(RegisterType::B64, id),
+            mov_num(RegisterType::C64, 9),
+            registers(SEND_OPCODE, &[RegisterType::B64, RegisterType::C64]),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        load_procedures(&mut executor, &[("start", start)]);
+        executor.execute();
+
+        let channel = executor.channel(id).unwrap();
+        assert_eq!(channel.try_recv(), Ok(Some(9)));
+        assert_eq!(channel.try_recv(), Ok(None));
+    }
+}
+
+#[test]
+fn recv_without_senders_deadlocks() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start = [
+            registers(CHAN_OPCODE, &[RegisterType::B64]),
+            registers(RECV_OPCODE, &[RegisterType::A64, RegisterType::B64]),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        load_procedures(&mut executor, &[("start", start)]);
+        executor.execute();
+
+        assert!(!executor.registers_ref().get_halt());
+    }
+}
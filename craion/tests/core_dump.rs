@@ -0,0 +1,164 @@
+use common::{
+    encoding::EncodingError,
+    register::{RegisterType::*, RegisterTypeGroup},
+};
+use craion::{
+    assembler::{Assembler, Program},
+    core_dump::{CoreDump, CoreDumpError, CORE_MAGIC},
+    executor::{builder::ExecutorBuilder, Executor},
+    memory::address::Address,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+fn program() -> Program {
+    return Assembler::new()
+        .procedure("main")
+        .mov_reg_num(C64, 7)
+        .savr(C64)
+        .call("fault")
+        .ret()
+        .procedure("fault")
+        .mov_reg_section(B64, "message")
+        .mov_reg_num(A64, 0xFFFFFF)
+        .mov_deref_num(A64, 1)
+        .ret()
+        .constant("message")
+        .bytes(b"core")
+        .finish()
+        .unwrap();
+}
+
+#[test]
+fn capture() {
+    let program = program();
+    let mut executor = Executor::new(0xFFFF);
+    program.load(&mut executor).unwrap();
+    let error = executor.call("main", &[]).unwrap_err();
+    let core = CoreDump::capture(&executor, &error);
+
+    assert_eq!(CoreDump::from_bytes(&core.to_bytes()), Ok(core.clone()));
+    assert_eq!(core.error(), error.to_string());
+    assert_eq!(core.ip(), error.ip().unwrap());
+    assert_eq!(
+        core.general_registers(),
+        &[0xFFFFFF, core.general_registers()[1], 7, 0]
+    );
+    assert!(core.saved_registers()[0].is_empty());
+    assert_eq!(core.saved_registers()[2], [7]);
+    assert_eq!(
+        core.saved_registers()[2],
+        executor
+            .registers_ref()
+            .saved_registers(&RegisterTypeGroup::C)
+    );
+    assert_eq!(core.sp(), executor.registers_ref().get_sp());
+    assert_eq!(core.sections().len(), 3);
+    assert_eq!(core.layout(), *executor.layout());
+
+    let message = Address::new(core.general_registers()[1] as usize);
+    assert_eq!(core.read(message, 4).unwrap(), b"core");
+    assert_eq!(core.read(Address::new(0x8000), 2).unwrap(), [0, 0]);
+    assert!(core.read(Address::new(0xFFFF), 2).is_err());
+
+    let backtrace = core.backtrace();
+    assert_eq!(backtrace.frames().len(), 3);
+    assert_eq!(
+        backtrace.frames()[0].section().unwrap().0,
+        xxh3_64(b"fault")
+    );
+    assert_eq!(backtrace.frames()[1].section().unwrap().0, xxh3_64(b"main"));
+    assert_eq!(
+        backtrace.to_string(),
+        executor.backtrace(core.ip()).to_string()
+    );
+}
+
+#[test]
+fn invalid() {
+    let bytes = CoreDump::capture(
+        &Executor::new(0x100),
+        &craion::executor::ExecutionError::StepLimit(Address::new(0)),
+    )
+    .to_bytes();
+    assert_eq!(&bytes[..4], &CORE_MAGIC);
+    assert_eq!(
+        CoreDump::from_bytes(b"sin\0\x01\0"),
+        Err(CoreDumpError::InvalidMagic)
+    );
+    let mut version = bytes.clone();
+    version[4] = 9;
+    assert_eq!(
+        CoreDump::from_bytes(&version),
+        Err(CoreDumpError::UnsupportedVersion(9))
+    );
+    assert_eq!(
+        CoreDump::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(CoreDumpError::Encoding(EncodingError::UnexpectedEnd))
+    );
+}
+
+#[test]
+fn core_file() {
+    let path = std::env::temp_dir().join(format!("craion-core-{}", std::process::id()));
+    let bytes = program().to_bytes();
+    let mut executor = ExecutorBuilder::new()
+        .memory_size(0xFFFF)
+        .bytes(&bytes)
+        .entry("main")
+        .core_file(&path)
+        .build()
+        .unwrap();
+    executor.execute();
+    let core = CoreDump::from_bytes(&std::fs::read(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(core.general_registers()[0], 0xFFFFFF);
+    assert_eq!(core.backtrace().frames().len(), 2);
+}
+
+#[test]
+fn partial_page() {
+    let mut executor = Executor::new(0xFFFF);
+    Assembler::new()
+        .procedure("main")
+        .push(A64)
+        .mov_reg_num(A64, 0xFFFFFF)
+        .mov_deref_num(A64, 1)
+        .load(&mut executor)
+        .unwrap();
+    let error = executor.call("main", &[]).unwrap_err();
+    let core = CoreDump::from_bytes(&CoreDump::capture(&executor, &error).to_bytes()).unwrap();
+    assert_eq!(core.read(Address::new(0xFFE0), 0x10).unwrap(), [0; 0x10]);
+
+    // Shrink the memory size of the layout below the stack page
+    let mut corrupted = core.to_bytes();
+    let layout = [0xFFFFu64, 0, 0x3FFF]
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect::<Vec<_>>();
+    let size = corrupted
+        .windows(layout.len())
+        .position(|bytes| bytes == layout)
+        .unwrap();
+    corrupted[size..size + 8].copy_from_slice(&0x100u64.to_le_bytes());
+    assert!(matches!(
+        CoreDump::from_bytes(&corrupted),
+        Err(CoreDumpError::InvalidPage(..))
+    ));
+}
+
+#[test]
+fn call_core_file() {
+    let path = std::env::temp_dir().join(format!("craion-call-core-{}", std::process::id()));
+    let bytes = program().to_bytes();
+    let mut executor = ExecutorBuilder::new()
+        .memory_size(0xFFFF)
+        .bytes(&bytes)
+        .entry("main")
+        .core_file(&path)
+        .build()
+        .unwrap();
+    assert!(executor.call("main", &[]).is_err());
+    let core = CoreDump::from_bytes(&std::fs::read(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(core.general_registers()[0], 0xFFFFFF);
+}
@@ -0,0 +1,71 @@
+use craion::{executor::Executor, memory::address::Address};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::assemble;
+
+const SOURCE: &str = r#"
+proc start -> {
+    .name "start"
+    .loc "main.rin", 2, 5
+    call fault
+    halt
+}
+proc fault -> {
+    .name "fault"
+    .loc "main.rin", 7, 3
+    mov b64, 4294967295
+    .loc "main.rin", 8, 9
+    mov [b64], 1
+    ret
+}
+"#;
+
+fn executor() -> Executor {
+    let (sections, data) = assemble(SOURCE).unwrap();
+    let mut executor = Executor::new(0xFFFF);
+    for section in sections.iter() {
+        executor.load_section(section, &data).unwrap();
+    }
+    return executor;
+}
+
+#[test]
+fn describe_address() {
+    let executor = executor();
+    let fault = executor
+        .section_manager_ref()
+        .get_section("fault")
+        .unwrap()
+        .mem_start();
+    assert_eq!(
+        executor.describe_address(fault),
+        "proc fault at main.rin:7:3"
+    );
+    let info = executor.debug_info().procedure(xxh3_64(b"fault")).unwrap();
+    assert_eq!((info.name(), info.file()), ("fault", "main.rin"));
+    let store = info.lines().last().unwrap();
+    assert_eq!((store.line(), store.column()), (8, 9));
+    assert_eq!(
+        executor.describe_address(fault + store.offset() as usize),
+        "proc fault at main.rin:8:9"
+    );
+    assert_eq!(
+        executor.describe_address(Address::new(0x8000)),
+        "instruction pointer: 0x8000"
+    );
+}
+
+#[test]
+fn backtrace() {
+    let mut executor = executor();
+    let error = executor.call("start", &[]).unwrap_err();
+    let backtrace = executor.backtrace(error.ip().unwrap()).to_string();
+    let lines = backtrace.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "Backtrace:");
+    assert!(lines[1].starts_with("  #0: proc fault+"));
+    assert!(lines[1].ends_with(" at main.rin:8:9"));
+    assert!(lines[2].starts_with("  #1: proc start+"));
+    assert!(lines[2].ends_with(" at main.rin:2:5"));
+}
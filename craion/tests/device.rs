@@ -0,0 +1,132 @@
+use common::{
+    constants::{MOV_DEREF_REG2REG, MOV_OPCODE, MOV_REG2DEREF_REG},
+    register::RegisterType,
+};
+use craion::{
+    executor::Executor,
+    instruction_helper::InstructionHelper,
+    memory::{
+        address::Address,
+        device::{framebuffer::Framebuffer, Device},
+        MemoryError,
+    },
+};
+
+mod modes;
+
+struct Counter {
+    reads: u64,
+}
+
+impl Device for Counter {
+    fn size(&self) -> usize {
+        return 8;
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) {
+        self.reads += 1;
+        let bytes = self.reads.to_le_bytes();
+        buffer.copy_from_slice(&bytes[offset..offset + buffer.len()]);
+    }
+
+    fn write(&mut self, _offset: usize, _data: &[u8]) {}
+}
+
+#[test]
+fn store_to_framebuffer() {
+    for mut executor in modes::executors(0xFFFF) {
+        let framebuffer = Framebuffer::new(4, 2);
+        let pixels = framebuffer.pixels();
+        executor
+            .map_device(Address::new(0x10000), framebuffer)
+            .unwrap();
+        InstructionHelper::new(&mut executor.memory())
+            .encode(MOV_OPCODE)
+            .encode_sub_opcode(MOV_REG2DEREF_REG)
+            .encode_register(RegisterType::A32)
+            .encode_register(RegisterType::B64)
+            .end()
+            .halt();
+        executor
+            .registers()
+            .set_general(&RegisterType::A32, 0x04030201)
+            .unwrap();
+        executor
+            .registers()
+            .set_general(&RegisterType::B64, 0x10002)
+            .unwrap();
+        executor.execute();
+        assert_eq!(*pixels.lock().unwrap(), vec![0, 0, 1, 2, 3, 4, 0, 0]);
+    }
+}
+
+#[test]
+fn load_from_device() {
+    for mut executor in modes::executors(0xFFFF) {
+        executor
+            .map_device(Address::new(0x100), Counter { reads: 0 })
+            .unwrap();
+        InstructionHelper::new(&mut executor.memory())
+            .encode(MOV_OPCODE)
+            .encode_sub_opcode(MOV_DEREF_REG2REG)
+            .encode_register(RegisterType::A64)
+            .encode_register(RegisterType::B64)
+            .end()
+            .halt();
+        executor
+            .registers()
+            .set_general(&RegisterType::B64, 0x100)
+            .unwrap();
+        executor.execute();
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            1
+        );
+    }
+}
+
+#[test]
+fn overlapping_devices() {
+    let mut executor = Executor::new(0xFFFF);
+    executor
+        .map_device(Address::new(0x100), Counter { reads: 0 })
+        .unwrap();
+    assert!(matches!(
+        executor.map_device(Address::new(0x104), Framebuffer::new(2, 2)),
+        Err(MemoryError::DeviceOverlap(_, 4))
+    ));
+    assert!(matches!(
+        executor.memory().mem_reads(Address::new(0xFC), 8),
+        Err(MemoryError::OutOfRange(_, 8))
+    ));
+    assert!(matches!(
+        executor.memory().mem_reads(Address::new(usize::MAX), 2),
+        Err(MemoryError::OutOfRange(_, 2))
+    ));
+    assert!(matches!(
+        executor.map_device(Address::new(0x200), Framebuffer::new(0, 0)),
+        Err(MemoryError::InvalidDevice(_, 0))
+    ));
+    assert!(matches!(
+        executor.map_device(Address::new(usize::MAX - 3), Counter { reads: 0 }),
+        Err(MemoryError::InvalidDevice(_, 8))
+    ));
+}
+
+#[test]
+fn reads_without_devices() {
+    let mut executor = Executor::new(0xFFFF);
+    executor
+        .map_device(Address::new(0x100), Counter { reads: 0 })
+        .unwrap();
+    let memory = executor.memory_ref();
+    assert_eq!(memory.mem_get(Address::new(0x100)), Ok(0));
+    assert_eq!(
+        memory.mem_gets(Address::new(0x100), 8).as_deref(),
+        Ok([0; 8].as_slice())
+    );
+    assert_eq!(executor.memory().mem_read(Address::new(0x100)), Ok(1));
+}
@@ -0,0 +1,28 @@
+use common::register::RegisterType::*;
+use craion::{
+    assembler::Assembler, decoder::instruction::InstructionError, executor::ExecutionError,
+};
+
+mod modes;
+
+#[test]
+fn div() {
+    let mut assembler = Assembler::new();
+    assembler
+        .procedure("main")
+        .larg(A64, 0)
+        .larg(B64, 1)
+        .div(A64, B64)
+        .ret();
+    for mut executor in modes::executors(0xFFFF) {
+        assembler.load(&mut executor).unwrap();
+        assert_eq!(executor.call("main", &[42, 5]).unwrap(), 8);
+        assert!(matches!(
+            executor.call("main", &[42, 0]),
+            Err(ExecutionError::Instruction {
+                error: InstructionError::DivisionByZero,
+                ..
+            })
+        ));
+    }
+}
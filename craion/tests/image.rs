@@ -0,0 +1,112 @@
+use common::register::RegisterType::*;
+use craion::{
+    assembler::{Assembler, Program},
+    executor::{builder::ExecutorBuilder, image::ProgramImage, Executor},
+    layout::Layout,
+};
+
+/// `bump` increments the counter in `counter` and returns it
+fn program() -> Program {
+    return Assembler::new()
+        .procedure("bump")
+        .mov_reg_section(B64, "counter")
+        .mov_reg_deref(A64, B64)
+        .inc(A64)
+        .mov_deref_reg(B64, A64)
+        .ret()
+        .procedure("answer")
+        .mov_reg_num(A64, 42)
+        .ret()
+        .constant("counter")
+        .bytes(&0u64.to_le_bytes())
+        .finish()
+        .unwrap();
+}
+
+fn assert_shareable<T: Send + Sync>() {}
+
+#[test]
+fn executors() {
+    assert_shareable::<ProgramImage>();
+    let program = program();
+    let image = ProgramImage::new(Layout::new(0x10000), &program.sin()).unwrap();
+    let size = image.size();
+
+    let mut first = Executor::from_image(&image);
+    assert_eq!(first.call("bump", &[]).unwrap(), 1);
+    assert_eq!(first.call("bump", &[]).unwrap(), 2);
+    let mut second = Executor::from_image(&image);
+    assert_eq!(second.call("bump", &[]).unwrap(), 1);
+    assert_eq!(image.size(), size);
+    assert_eq!(first.layout(), image.layout());
+
+    let other = Assembler::new()
+        .procedure("answer")
+        .mov_reg_num(A64, 7)
+        .ret()
+        .finish()
+        .unwrap();
+    first
+        .load_section(&other.sections()[0], other.data())
+        .unwrap();
+    assert_eq!(first.call("answer", &[]).unwrap(), 7);
+    assert_eq!(second.call("answer", &[]).unwrap(), 42);
+    assert_eq!(
+        Executor::from_image(&image).call("answer", &[]).unwrap(),
+        42
+    );
+}
+
+#[test]
+fn threads() {
+    let program = program();
+    let image = ProgramImage::new(Layout::new(0x10000), &program.sin()).unwrap();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let mut executor = Executor::from_image(&image);
+                for i in 1..=10 {
+                    assert_eq!(executor.call("bump", &[]).unwrap(), i);
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn builder() {
+    let program = program();
+    let image = ProgramImage::new(Layout::new(0x10000), &program.sin()).unwrap();
+    let mut executor = ExecutorBuilder::new()
+        .memory_size(0x100)
+        .image(&image)
+        .entry("answer")
+        .step_limit(100)
+        .build()
+        .unwrap();
+    let answer = image.section_manager().get_section("answer").unwrap();
+    assert_eq!(executor.registers_ref().get_ip(), answer.mem_start());
+    assert_eq!(executor.layout(), image.layout());
+    assert_eq!(executor.call("bump", &[]).unwrap(), 1);
+    assert!(ExecutorBuilder::new()
+        .image(&image)
+        .entry("counter")
+        .build()
+        .is_err());
+
+    let counter = image.section_manager().get_section("counter").unwrap();
+    assert_eq!(
+        executor
+            .memory_ref()
+            .mem_gets(counter.mem_start(), 8)
+            .unwrap(),
+        1u64.to_le_bytes().as_slice()
+    );
+    assert_eq!(
+        Executor::from_image(&image)
+            .memory_ref()
+            .mem_gets(counter.mem_start(), 8)
+            .unwrap(),
+        0u64.to_le_bytes().as_slice()
+    );
+}
@@ -0,0 +1,130 @@
+use common::{
+    constants::{
+        CALLR_OPCODE, JMPR_OPCODE, JTAB_OPCODE, MOV_OPCODE, MOV_SECTION_ADDR_2REG, RET_OPCODE,
+    },
+    register::RegisterType,
+    sin::sections::SectionType,
+};
+use craion::{
+    decoder::instruction::InstructionError, executor::ExecutionError, memory::address::Address,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, larg, load, mov_num};
+
+/// An address in the stack, outside of every section
+const STACK: u64 = 0xF000;
+
+fn instruction_error(result: Result<u64, ExecutionError>) -> InstructionError {
+    return match result {
+        Err(ExecutionError::Instruction { error, .. }) => error,
+        other => panic!("Expected an instruction error, got {:?}", other),
+    };
+}
+
+#[test]
+fn callr() {
+    for mut executor in modes::executors(0xFFFF) {
+        let seven = [mov_num(RegisterType::A64, 7), instruction(RET_OPCODE, &[])].concat();
+        let seven_start = load(&mut executor, SectionType::Procedure, "seven", &seven);
+        let constant = load(&mut executor, SectionType::Constant, "constant", &[0; 8]);
+
+        let mut mov = vec![MOV_SECTION_ADDR_2REG, RegisterType::B64.to_byte()];
+        mov.extend_from_slice(&xxh3_64(b"seven").to_le_bytes());
+        let caller = [
+            instruction(MOV_OPCODE, &mov),
+            instruction(CALLR_OPCODE, &[RegisterType::B64.to_byte()]),
+            instruction(RET_OPCODE, &[]),
+        ]
+        .concat();
+        load(&mut executor, SectionType::Procedure, "caller", &caller);
+        assert_eq!(executor.call("caller", &[]).unwrap(), 7);
+
+        let call_arg = [
+            larg(RegisterType::B64, 0),
+            instruction(CALLR_OPCODE, &[RegisterType::B64.to_byte()]),
+            instruction(RET_OPCODE, &[]),
+        ]
+        .concat();
+        load(&mut executor, SectionType::Procedure, "call_arg", &call_arg);
+        let middle = seven_start + seven[0] as usize;
+        assert!(matches!(
+            instruction_error(executor.call("call_arg", &[middle.get_raw() as u64])),
+            InstructionError::InvalidCallTarget(address) if address == middle
+        ));
+        assert!(matches!(
+            instruction_error(executor.call("call_arg", &[constant.get_raw() as u64])),
+            InstructionError::NotProcedureSection
+        ));
+        assert!(matches!(
+            instruction_error(executor.call("call_arg", &[STACK])),
+            InstructionError::InvalidCallTarget(address) if address == Address::new(STACK as usize)
+        ));
+    }
+}
+
+#[test]
+fn jmpr() {
+    for mut executor in modes::executors(0xFFFF) {
+        let set_one = mov_num(RegisterType::A64, 1);
+        let set_two = mov_num(RegisterType::A64, 2);
+        let jump = [
+            larg(RegisterType::B64, 0),
+            set_one.clone(),
+            instruction(JMPR_OPCODE, &[RegisterType::B64.to_byte()]),
+            set_two.clone(),
+            instruction(RET_OPCODE, &[]),
+        ]
+        .concat();
+        let start = load(&mut executor, SectionType::Procedure, "jump", &jump);
+        let ret = start + (jump.len() - 3);
+        let two = ret - set_two.len();
+
+        assert_eq!(executor.call("jump", &[ret.get_raw() as u64]).unwrap(), 1);
+        assert_eq!(executor.call("jump", &[two.get_raw() as u64]).unwrap(), 2);
+        assert!(matches!(
+            instruction_error(executor.call("jump", &[STACK])),
+            InstructionError::InvalidJumpTarget(address) if address == Address::new(STACK as usize)
+        ));
+    }
+}
+
+#[test]
+fn jtab() {
+    for mut executor in modes::executors(0xFFFF) {
+        let default = [mov_num(RegisterType::A64, 9), instruction(RET_OPCODE, &[])].concat();
+        let case = |value| {
+            [
+                mov_num(RegisterType::A64, value),
+                instruction(RET_OPCODE, &[]),
+            ]
+            .concat()
+        };
+
+        let mut args = vec![RegisterType::B64.to_byte()];
+        args.extend_from_slice(&xxh3_64(b"switch").to_le_bytes());
+        args.extend_from_slice(&2u16.to_le_bytes());
+        let head_len = larg(RegisterType::B64, 0).len() + args.len() + 3 + 4;
+        let case_zero = head_len + default.len();
+        let case_one = case_zero + case(10).len();
+        args.extend_from_slice(&(case_zero as u16).to_le_bytes());
+        args.extend_from_slice(&(case_one as u16).to_le_bytes());
+
+        let switch = [
+            larg(RegisterType::B64, 0),
+            instruction(JTAB_OPCODE, &args),
+            default,
+            case(10),
+            case(11),
+        ]
+        .concat();
+        load(&mut executor, SectionType::Procedure, "switch", &switch);
+
+        assert_eq!(executor.call("switch", &[0]).unwrap(), 10);
+        assert_eq!(executor.call("switch", &[1]).unwrap(), 11);
+        assert_eq!(executor.call("switch", &[2]).unwrap(), 9);
+        assert_eq!(executor.call("switch", &[u64::MAX]).unwrap(), 9);
+    }
+}
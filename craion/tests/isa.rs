@@ -0,0 +1,170 @@
+use common::{
+    constants::{
+        ADD_OPCODE, ADD_SP_W_NUM, HALT_OPCODE, JMC_OPCODE, JMZ_OPCODE, JTAB_OPCODE,
+        MOV_DEREF_REG_WITH_OFFSET2REG, MOV_OPCODE, MOV_SECTION_ADDR_2REG, YIELD_OPCODE,
+    },
+    encoding::Encode,
+    isa::{
+        self,
+        decoded::{DecodeError, Decoded, Value},
+        Operand, INSTRUCTIONS,
+    },
+    register::RegisterType,
+    sin::sections::{SectionType, SinSection},
+};
+use craion::{
+    decoder::instruction::InstructionError,
+    executor::{ExecutionError, Executor},
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::instruction;
+
+#[test]
+fn mnemonics() {
+    assert_eq!(
+        isa::instruction("jmc").unwrap().forms()[0].opcode(),
+        JMC_OPCODE
+    );
+    assert_eq!(
+        isa::instruction("jmz").unwrap().forms()[0].opcode(),
+        JMZ_OPCODE
+    );
+    assert_eq!(
+        isa::instruction("yield").unwrap().forms()[0].opcode(),
+        YIELD_OPCODE
+    );
+    assert!(isa::instruction("jmq").is_none());
+    assert_eq!(isa::instruction("mov").unwrap().mnemonic(), "mov");
+}
+
+#[test]
+fn disassemble() {
+    let mut args = vec![
+        MOV_DEREF_REG_WITH_OFFSET2REG,
+        RegisterType::A32.to_byte(),
+        RegisterType::Sp.to_byte(),
+    ];
+    args.extend_from_slice(&8u32.to_le_bytes());
+    args.push(0);
+    let mov = Decoded::decode(&instruction(MOV_OPCODE, &args)).unwrap();
+    assert_eq!(mov.to_string(), "mov a32, [sp - 8]");
+
+    let mut args = vec![MOV_SECTION_ADDR_2REG, RegisterType::A64.to_byte()];
+    args.extend_from_slice(&0x1234u64.to_le_bytes());
+    assert_eq!(
+        Decoded::decode(&instruction(MOV_OPCODE, &args))
+            .unwrap()
+            .to_string(),
+        "mov a64, section 0x0000000000001234"
+    );
+
+    let mut args = vec![ADD_SP_W_NUM, RegisterType::Sp.to_byte()];
+    args.extend_from_slice(&16u64.to_le_bytes());
+    assert_eq!(
+        Decoded::decode(&instruction(ADD_OPCODE, &args))
+            .unwrap()
+            .to_string(),
+        "add sp, 16"
+    );
+
+    let mut args = vec![RegisterType::C64.to_byte()];
+    args.extend_from_slice(&0x1234u64.to_le_bytes());
+    args.extend_from_slice(&2u16.to_le_bytes());
+    args.extend_from_slice(&4u16.to_le_bytes());
+    args.extend_from_slice(&9u16.to_le_bytes());
+    let jtab = Decoded::decode(&instruction(JTAB_OPCODE, &args)).unwrap();
+    assert_eq!(jtab.length(), args.len() + 3);
+    assert_eq!(jtab.values()[1], Value::JumpTable(0x1234, vec![4, 9]));
+    assert_eq!(jtab.to_string(), "jtab c64, 0x0000000000001234 +4 +9");
+
+    assert_eq!(
+        Decoded::decode(&instruction(HALT_OPCODE, &[]))
+            .unwrap()
+            .to_string(),
+        "halt"
+    );
+    assert_eq!(
+        Decoded::decode(&instruction(HALT_OPCODE, &[0])).unwrap_err(),
+        DecodeError::TrailingOperands(HALT_OPCODE, 1)
+    );
+    assert_eq!(
+        Decoded::decode(&instruction(MOV_OPCODE, &[0xEE])).unwrap_err(),
+        DecodeError::UnknownSubOpCode(MOV_OPCODE, 0xEE)
+    );
+    assert_eq!(
+        Decoded::decode(&instruction(0x1234, &[])).unwrap_err(),
+        DecodeError::UnknownOpCode(0x1234)
+    );
+}
+
+/// Every form in the table has a handler that reads the operands the table decodes, so running
+/// one never fails to decode or to read an operand
+#[test]
+fn handlers() {
+    for (i, form) in INSTRUCTIONS
+        .iter()
+        .flat_map(|instruction| instruction.forms())
+        .enumerate()
+    {
+        let mut executor = Executor::new(0xFFFF);
+        let name = format!("form{}", i);
+        let values = form
+            .operands()
+            .iter()
+            .map(|operand| match operand {
+                Operand::Register => Value::Register(RegisterType::A64),
+                Operand::Sp => Value::Register(RegisterType::Sp),
+                Operand::U16 => Value::U16(0),
+                Operand::U32 => Value::U32(0),
+                Operand::U64 | Operand::Hash => Value::U64(0x100),
+                Operand::Immediate => Value::Immediate(1),
+                Operand::Section => Value::Section(xxh3_64(b"data")),
+                Operand::Procedure => Value::Procedure(xxh3_64(b"target")),
+                Operand::Jump => Value::Jump(xxh3_64(name.as_bytes()), 0),
+                Operand::JumpTable => Value::JumpTable(xxh3_64(name.as_bytes()), vec![0]),
+                Operand::Deref => Value::Deref(RegisterType::B64),
+                Operand::DerefOffset => Value::DerefOffset(RegisterType::B64, 8, true),
+            })
+            .collect();
+        let code = [
+            Decoded::new(form, values).to_encoded_bytes(),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        for (ty, section, data) in [
+            (SectionType::Procedure, name.as_str(), code.as_slice()),
+            (
+                SectionType::Procedure,
+                "target",
+                &instruction(HALT_OPCODE, &[]),
+            ),
+            (SectionType::Constant, "data", &[0; 8]),
+        ] {
+            let hash = xxh3_64(section.as_bytes());
+            let section = SinSection::new(ty, hash, 0, data.len() as u64);
+            executor.load_section(&section, data).unwrap();
+        }
+        executor
+            .registers()
+            .set_general(&RegisterType::B64, 0x100)
+            .unwrap();
+        executor.set_step_limit(Some(1));
+        let result = executor.call(&name, &[0]);
+        assert!(
+            !matches!(
+                result,
+                Err(ExecutionError::Decode { .. })
+                    | Err(ExecutionError::Instruction {
+                        error: InstructionError::ArgumentParseError(_),
+                        ..
+                    })
+            ),
+            "form {:?} doesn't match its handler: {:?}",
+            form,
+            result
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use common::{
+    constants::{HALT_OPCODE, INC_OPCODE, JACC_OPCODE, MOV_OPCODE, MOV_REG2REG},
+    register::RegisterType,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, load_start, mov_num};
+
+#[test]
+fn compiles_loop() {
+    for mut executor in modes::executors(0xFFFF) {
+        let mut code = mov_num(RegisterType::A64, 0);
+        code.extend(mov_num(RegisterType::B64, 1000));
+        let head = code.len() as u16;
+        code.extend(instruction(INC_OPCODE, &[RegisterType::A64.to_byte()]));
+        let mut args = vec![RegisterType::A64.to_byte(), RegisterType::B64.to_byte()];
+        args.extend_from_slice(&xxh3_64(b"start").to_le_bytes());
+        args.extend_from_slice(&head.to_le_bytes());
+        code.extend(instruction(JACC_OPCODE, &args));
+        code.extend(instruction(HALT_OPCODE, &[]));
+        load_start(&mut executor, &code);
+        executor.execute();
+
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            1000
+        );
+        assert_eq!(executor.registers().get_zero(), false);
+        assert_eq!(executor.registers().get_carry(), false);
+        if let Some(jit) = executor.jit() {
+            assert!(jit.compiled() > 0);
+        }
+    }
+}
+
+#[test]
+fn falls_back_on_oversized_value() {
+    for mut executor in modes::executors(0xFFFF) {
+        let mut code = mov_num(RegisterType::A64, 0xFFFF);
+        code.extend(mov_num(RegisterType::B64, 300));
+        code.extend(instruction(
+            MOV_OPCODE,
+            &[
+                MOV_REG2REG,
+                RegisterType::A8.to_byte(),
+                RegisterType::B64.to_byte(),
+            ],
+        ));
+        let failing_end = code.len();
+        code.extend(instruction(HALT_OPCODE, &[]));
+        load_start(&mut executor, &code);
+        executor.execute();
+
+        assert_eq!(executor.registers().get_halt(), false);
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            0
+        );
+        assert_eq!(executor.registers_ref().get_ip().get_raw(), failing_end);
+    }
+}
@@ -0,0 +1,105 @@
+use common::sin::{
+    sections::{SectionType, SinSection},
+    SinError,
+};
+use craion::{
+    executor::{Executor, LoadError},
+    layout::{Layout, LayoutError},
+    memory::address::Address,
+};
+
+#[test]
+fn sections_load_at_their_bases() {
+    let layout = Layout::new(0x10000).with_constant_base(Address::new(0x2000));
+    let mut executor = Executor::with_layout(layout).unwrap();
+    let data = [1, 2, 3, 4, 5, 6];
+    executor
+        .load_section(&SinSection::new(SectionType::Procedure, 1, 0, 4), &data)
+        .unwrap();
+    executor
+        .load_section(&SinSection::new(SectionType::Constant, 2, 4, 6), &data)
+        .unwrap();
+    executor
+        .load_section(&SinSection::new(SectionType::Procedure, 3, 0, 2), &data)
+        .unwrap();
+
+    let section_manager = executor.section_manager();
+    assert_eq!(
+        section_manager.get_section_hash(1).unwrap().mem_start(),
+        Address::new(0)
+    );
+    assert_eq!(
+        section_manager.get_section_hash(2).unwrap().mem_start(),
+        Address::new(0x2000)
+    );
+    assert_eq!(
+        section_manager.get_section_hash(3).unwrap().mem_start(),
+        Address::new(4)
+    );
+    assert_eq!(executor.registers_ref().get_sp(), Address::new(0x10000));
+}
+
+#[test]
+fn section_overlapping_stack() {
+    let layout = Layout::new(0x1000)
+        .with_code_base(Address::new(0xEFC))
+        .with_constant_base(Address::new(0))
+        .with_heap(Address::new(0x100), Address::new(0x200))
+        .with_stack_size(0x100);
+    let mut executor = Executor::with_layout(layout).unwrap();
+    let data = [0; 8];
+    assert!(matches!(
+        executor.load_section(&SinSection::new(SectionType::Procedure, 1, 0, 8), &data),
+        Err(LoadError::Layout(LayoutError::SectionOverlap(
+            1,
+            _,
+            8,
+            "stack"
+        )))
+    ));
+}
+
+#[test]
+fn section_overlapping_section() {
+    let layout = Layout::new(0x1000).with_constant_base(Address::new(0x4));
+    let mut executor = Executor::with_layout(layout).unwrap();
+    let data = [0; 8];
+    executor
+        .load_section(&SinSection::new(SectionType::Constant, 1, 0, 4), &data)
+        .unwrap();
+    assert!(matches!(
+        executor.load_section(&SinSection::new(SectionType::Procedure, 2, 0, 8), &data),
+        Err(LoadError::Layout(LayoutError::SectionOverlap(2, _, 8, _)))
+    ));
+}
+
+#[test]
+fn invalid_layout() {
+    let layout = Layout::new(0x1000).with_stack_top(Address::new(0x2000));
+    assert!(matches!(
+        Executor::with_layout(layout),
+        Err(LayoutError::OutOfMemory("stack", _, _))
+    ));
+}
+
+#[test]
+fn section_outside_data() {
+    let mut executor = Executor::new(0x1000);
+    let data = [0; 4];
+    assert!(matches!(
+        executor.load_section(&SinSection::new(SectionType::Procedure, 1, 2, 10), &data),
+        Err(LoadError::Sin(SinError::InvalidSection))
+    ));
+}
+
+#[test]
+fn empty_section() {
+    let mut executor = Executor::new(0x1000);
+    for ty in [SectionType::Procedure, SectionType::Constant] {
+        assert!(matches!(
+            executor.load_section(&SinSection::new(ty, 1, 0, 0), &[]),
+            Err(LoadError::EmptySection(1))
+        ));
+    }
+    assert!(executor.section_manager().get_section_hash(1).is_none());
+}
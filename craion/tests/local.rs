@@ -0,0 +1,296 @@
+use common::{
+    constants::{
+        ARG_NUM, ARG_OPCODE, CALL_OPCODE, CLTY_OPCODE, FTYLL_OPCODE, HALT_OPCODE, ITYLAL_OPCODE,
+        ITYL_OPCODE, LOCAL_OPCODE, RL_OPCODE,
+    },
+    sin::{
+        sections::{SectionType, SinSection},
+        type_info::{TypeEntry, TypeInfo, TYPE_SECTION_NAME},
+        Sin,
+    },
+};
+use craion::{
+    decoder::instruction::InstructionError,
+    executor::{
+        builder::ExecutorBuilder,
+        local::{TypeError, U64_TYPE},
+        ExecutionError,
+    },
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, load};
+
+fn index(opcode: u16, index: u16) -> Vec<u8> {
+    return instruction(opcode, &index.to_le_bytes());
+}
+
+fn ityl(index: u16, ty: &str) -> Vec<u8> {
+    let mut args = index.to_le_bytes().to_vec();
+    args.extend_from_slice(&xxh3_64(ty.as_bytes()).to_le_bytes());
+    return instruction(ITYL_OPCODE, &args);
+}
+
+fn ftyll(target: u16, field: &str, source: u16) -> Vec<u8> {
+    let mut args = target.to_le_bytes().to_vec();
+    args.extend_from_slice(&xxh3_64(field.as_bytes()).to_le_bytes());
+    args.extend_from_slice(&source.to_le_bytes());
+    return instruction(FTYLL_OPCODE, &args);
+}
+
+fn type_error(result: Result<u64, ExecutionError>) -> TypeError {
+    return match result {
+        Err(ExecutionError::Instruction {
+            error: InstructionError::TypeError(error),
+            ..
+        }) => error,
+        other => panic!("Expected a type error, got {:?}", other),
+    };
+}
+
+#[test]
+fn return_local() {
+    for mut executor in modes::executors(0xFFFF) {
+        let identity = [
+            index(LOCAL_OPCODE, 2),
+            index(ITYLAL_OPCODE, 1),
+            index(RL_OPCODE, 1),
+        ]
+        .concat();
+        load(&mut executor, SectionType::Procedure, "identity", &identity);
+        assert_eq!(executor.call("identity", &[1, 42]).unwrap(), 42);
+
+        let mut arg = vec![ARG_NUM];
+        arg.extend_from_slice(&1u32.to_le_bytes());
+        arg.extend_from_slice(&7u64.to_le_bytes());
+        let outer = [
+            index(LOCAL_OPCODE, 1),
+            index(ITYLAL_OPCODE, 0),
+            instruction(ARG_OPCODE, &arg),
+            instruction(CALL_OPCODE, &xxh3_64(b"identity").to_le_bytes()),
+            index(RL_OPCODE, 0),
+        ]
+        .concat();
+        load(&mut executor, SectionType::Procedure, "outer", &outer);
+        assert_eq!(executor.call("outer", &[5]).unwrap(), 5);
+        assert!(matches!(
+            executor.call("identity", &[1]),
+            Err(ExecutionError::Instruction {
+                error: InstructionError::UndefinedArgument(1),
+                ..
+            })
+        ));
+    }
+}
+
+#[test]
+fn struct_fields() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start = [
+            index(LOCAL_OPCODE, 3),
+            ityl(0, "Point"),
+            ityl(1, "u64"),
+            ftyll(0, "x", 1),
+            ityl(2, "Point"),
+            ftyll(0, "origin", 2),
+            index(CLTY_OPCODE, 1),
+            index(CLTY_OPCODE, 2),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        load(&mut executor, SectionType::Procedure, "start", &start);
+        let entry = executor
+            .section_manager()
+            .get_section("start")
+            .unwrap()
+            .mem_start();
+        executor.registers().set_ip(entry);
+        executor.execute();
+
+        assert!(executor.registers_ref().get_halt());
+        let point = executor.local(0).unwrap();
+        assert_eq!(point.type_hash(), xxh3_64(b"Point"));
+        assert_eq!(point.field(xxh3_64(b"x")).unwrap().type_hash(), U64_TYPE);
+        assert_eq!(point.field(xxh3_64(b"x")).unwrap().as_scalar(), Ok(0));
+        assert_eq!(
+            point.field(xxh3_64(b"origin")).unwrap().type_hash(),
+            xxh3_64(b"Point")
+        );
+        assert_eq!(executor.local(1), Err(TypeError::Uninitialized(1)));
+        assert_eq!(executor.local(3).err(), Some(TypeError::OutOfRange(3, 3)));
+    }
+}
+
+#[test]
+fn type_errors() {
+    for mut executor in modes::executors(0xFFFF) {
+        let procedures: [(&str, Vec<u8>, TypeError); 6] = [
+            ("no_locals", index(RL_OPCODE, 0), TypeError::NoLocals),
+            (
+                "out_of_range",
+                [index(LOCAL_OPCODE, 1), index(ITYLAL_OPCODE, 1)].concat(),
+                TypeError::OutOfRange(1, 1),
+            ),
+            (
+                "uninitialized",
+                [index(LOCAL_OPCODE, 1), index(RL_OPCODE, 0)].concat(),
+                TypeError::Uninitialized(0),
+            ),
+            (
+                "initialized",
+                [
+                    index(LOCAL_OPCODE, 1),
+                    ityl(0, "u64"),
+                    index(ITYLAL_OPCODE, 0),
+                ]
+                .concat(),
+                TypeError::Initialized(0, U64_TYPE),
+            ),
+            (
+                "mismatch",
+                [
+                    index(LOCAL_OPCODE, 3),
+                    ityl(0, "Point"),
+                    ityl(1, "u64"),
+                    ityl(2, "Point"),
+                    ftyll(0, "x", 1),
+                    ftyll(0, "x", 2),
+                ]
+                .concat(),
+                TypeError::Mismatch {
+                    expected: U64_TYPE,
+                    found: xxh3_64(b"Point"),
+                },
+            ),
+            (
+                "not_scalar",
+                [
+                    index(LOCAL_OPCODE, 1),
+                    ityl(0, "Point"),
+                    index(RL_OPCODE, 0),
+                ]
+                .concat(),
+                TypeError::NotScalar(xxh3_64(b"Point")),
+            ),
+        ];
+        for (name, code, expected) in procedures {
+            load(&mut executor, SectionType::Procedure, name, &code);
+            assert_eq!(
+                type_error(executor.call(name, &[1, 1])),
+                expected,
+                "{}",
+                name
+            );
+        }
+
+        let not_struct = [
+            index(LOCAL_OPCODE, 2),
+            index(ITYLAL_OPCODE, 0),
+            ityl(1, "u64"),
+            ftyll(0, "x", 1),
+        ]
+        .concat();
+        load(
+            &mut executor,
+            SectionType::Procedure,
+            "not_struct",
+            &not_struct,
+        );
+        assert_eq!(
+            type_error(executor.call("not_struct", &[1])),
+            TypeError::NotStruct(U64_TYPE)
+        );
+    }
+}
+
+#[test]
+fn type_layouts() {
+    let mut point = TypeEntry::new(xxh3_64(b"Point"), 16, 8);
+    point.push_field(xxh3_64(b"x"), 0, U64_TYPE);
+    point.push_field(xxh3_64(b"y"), 8, U64_TYPE);
+    let mut types = TypeInfo::new();
+    types.push(point.clone());
+
+    let procedures: [(&str, Vec<u8>); 3] = [
+        (
+            "fields",
+            [
+                index(LOCAL_OPCODE, 2),
+                ityl(0, "Point"),
+                index(ITYLAL_OPCODE, 1),
+                ftyll(0, "x", 1),
+                ftyll(0, "y", 1),
+                index(RL_OPCODE, 1),
+            ]
+            .concat(),
+        ),
+        (
+            "undefined_field",
+            [
+                index(LOCAL_OPCODE, 2),
+                ityl(0, "Point"),
+                index(ITYLAL_OPCODE, 1),
+                ftyll(0, "z", 1),
+            ]
+            .concat(),
+        ),
+        (
+            "mismatch",
+            [
+                index(LOCAL_OPCODE, 2),
+                ityl(0, "Point"),
+                ityl(1, "Point"),
+                ftyll(0, "x", 1),
+            ]
+            .concat(),
+        ),
+    ];
+    let mut data = Vec::new();
+    let mut sections = Vec::new();
+    for (name, code) in procedures {
+        let start = data.len() as u64;
+        data.extend_from_slice(&code);
+        sections.push(SinSection::new(
+            SectionType::Procedure,
+            xxh3_64(name.as_bytes()),
+            start,
+            data.len() as u64,
+        ));
+    }
+    let start = data.len() as u64;
+    data.extend_from_slice(&types.to_bytes());
+    sections.push(SinSection::new(
+        SectionType::Types,
+        xxh3_64(TYPE_SECTION_NAME.as_bytes()),
+        start,
+        data.len() as u64,
+    ));
+    let bytes = Sin::new(sections, &data).to_bytes();
+
+    for threshold in [None, Some(0)] {
+        let mut builder = ExecutorBuilder::new().bytes(&bytes).entry("fields");
+        if let Some(threshold) = threshold {
+            builder = builder.jit(threshold);
+        }
+        let mut executor = builder.build().unwrap();
+
+        assert_eq!(executor.types().get(xxh3_64(b"Point")), Some(&point));
+        assert_eq!(executor.call("fields", &[0, 3]).unwrap(), 3);
+        assert_eq!(
+            type_error(executor.call("undefined_field", &[0, 3])),
+            TypeError::UndefinedField {
+                ty: xxh3_64(b"Point"),
+                field: xxh3_64(b"z"),
+            }
+        );
+        assert_eq!(
+            type_error(executor.call("mismatch", &[])),
+            TypeError::Mismatch {
+                expected: U64_TYPE,
+                found: xxh3_64(b"Point"),
+            }
+        );
+    }
+}
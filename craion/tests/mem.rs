@@ -0,0 +1,130 @@
+use common::{
+    constants::{MCMP_OPCODE, MCPY_OPCODE, MSET_OPCODE, RET_OPCODE},
+    register::RegisterType,
+    sin::sections::SectionType,
+};
+use craion::{
+    decoder::instruction::InstructionError,
+    executor::{ExecutionError, Executor},
+    memory::{address::Address, MemoryError},
+};
+
+mod modes;
+
+use modes::{instruction, larg};
+
+const REGISTERS: [RegisterType; 3] = [RegisterType::A64, RegisterType::B64, RegisterType::C64];
+
+/// Loads a procedure that runs `opcode` on its three arguments
+fn load(executor: &mut Executor, name: &str, opcode: u16) {
+    let mut code = Vec::new();
+    for (i, register) in REGISTERS.iter().enumerate() {
+        code.extend(larg(*register, i as u32));
+    }
+    code.extend(instruction(opcode, &REGISTERS.map(|e| e.to_byte())));
+    code.extend(instruction(RET_OPCODE, &[]));
+    modes::load(executor, SectionType::Procedure, name, &code);
+}
+
+fn out_of_range(result: Result<u64, ExecutionError>) -> bool {
+    return matches!(
+        result,
+        Err(ExecutionError::Instruction {
+            error: InstructionError::AccessingMemoryError(MemoryError::OutOfRange(..)),
+            ..
+        })
+    );
+}
+
+#[test]
+fn mcpy_mset() {
+    for mut executor in modes::executors(0x10000) {
+        load(&mut executor, "copy", MCPY_OPCODE);
+        load(&mut executor, "fill", MSET_OPCODE);
+
+        assert!(executor.call("fill", &[0x8000, 0x1AB, 0x1800]).is_ok());
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0x7FFF), 3)
+                .as_deref(),
+            Ok([0, 0xAB, 0xAB].as_slice())
+        );
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0x97FF), 2)
+                .as_deref(),
+            Ok([0xAB, 0].as_slice())
+        );
+
+        executor
+            .memory()
+            .mem_sets(Address::new(0x100), &[1, 2, 3, 4, 5])
+            .unwrap();
+        assert!(executor.call("copy", &[0x102, 0x100, 5]).is_ok());
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0x100), 7)
+                .as_deref(),
+            Ok([1, 2, 1, 2, 3, 4, 5].as_slice())
+        );
+        assert!(executor.call("copy", &[0x100, 0x102, 5]).is_ok());
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0x100), 7)
+                .as_deref(),
+            Ok([1, 2, 3, 4, 5, 4, 5].as_slice())
+        );
+        assert!(executor.call("copy", &[0x9000, 0x7000, 0x2000]).is_ok());
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0x9FFF), 3)
+                .as_deref(),
+            Ok([0, 0xAB, 0xAB].as_slice())
+        );
+
+        assert!(out_of_range(executor.call("fill", &[0xFFFF, 0, 2])));
+        assert!(out_of_range(executor.call("copy", &[0, 0xFFFF, 2])));
+        assert!(out_of_range(executor.call("copy", &[0xFFFF, 0, 2])));
+        assert!(out_of_range(executor.call("fill", &[1, 0, u64::MAX])));
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0xFFFF), 1)
+                .as_deref(),
+            Ok([0].as_slice())
+        );
+    }
+}
+
+#[test]
+fn mcmp() {
+    for mut executor in modes::executors(0x10000) {
+        load(&mut executor, "compare", MCMP_OPCODE);
+        executor
+            .memory()
+            .mem_sets(Address::new(0x100), b"apple")
+            .unwrap();
+        executor
+            .memory()
+            .mem_sets(Address::new(0x200), b"apply")
+            .unwrap();
+
+        for (args, zero, carry) in [
+            ([0x100, 0x200, 4], true, false),
+            ([0x100, 0x200, 5], false, true),
+            ([0x200, 0x100, 5], false, false),
+            ([0x100, 0x200, 0], true, false),
+        ] {
+            assert!(executor.call("compare", &args).is_ok());
+            assert_eq!(executor.registers().get_zero(), zero, "{:?}", args);
+            assert_eq!(executor.registers().get_carry(), carry, "{:?}", args);
+            assert_eq!(executor.registers().get_negative(), carry, "{:?}", args);
+        }
+        assert!(out_of_range(executor.call("compare", &[0x100, 0xFFFE, 5])));
+    }
+}
@@ -0,0 +1,104 @@
+// Test files include this module for some of its helpers, not all of them
+#![allow(dead_code)]
+
+use std::{path::Path, sync::Arc};
+
+use common::{
+    constants::{LARG_OPCODE, MOV_NUM2REG, MOV_OPCODE},
+    register::RegisterType,
+    sin::sections::{SectionType, SinSection},
+};
+use craion::{executor::Executor, memory::address::Address};
+use raion::{
+    compiler::{asm_compiler::ASMCompiler, CompilerError},
+    lexer::asm_lexer::ASMLexer,
+    token::asm_token::ASMToken,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// An interpreter and a jit that compiles every trace on its first run, tests run their
+/// program on both and expect the same results
+pub fn executors(mem_size: usize) -> [Executor; 2] {
+    let interpreter = Executor::new(mem_size);
+    let mut jit = Executor::new(mem_size);
+    jit.enable_jit(0).unwrap();
+    return [interpreter, jit];
+}
+
+/// Encode an instruction as the decoder reads it: the length, the opcode and `args`
+pub fn instruction(opcode: u16, args: &[u8]) -> Vec<u8> {
+    let mut buffer = vec![args.len() as u8 + 3];
+    buffer.extend_from_slice(&opcode.to_le_bytes());
+    buffer.extend_from_slice(args);
+    return buffer;
+}
+
+/// `mov register, value`, the value is sized to the register
+pub fn mov_num(register: RegisterType, value: u64) -> Vec<u8> {
+    let mut args = vec![MOV_NUM2REG, register.to_byte()];
+    args.extend_from_slice(&value.to_le_bytes()[..register.size().byte()]);
+    return instruction(MOV_OPCODE, &args);
+}
+
+/// `larg register, index`
+pub fn larg(register: RegisterType, index: u32) -> Vec<u8> {
+    let mut args = vec![register.to_byte()];
+    args.extend_from_slice(&index.to_le_bytes());
+    return instruction(LARG_OPCODE, &args);
+}
+
+/// A jump instruction to `offset` in `procedure`
+pub fn jump(opcode: u16, procedure: &str, offset: usize) -> Vec<u8> {
+    let mut args = xxh3_64(procedure.as_bytes()).to_le_bytes().to_vec();
+    args.extend_from_slice(&(offset as u16).to_le_bytes());
+    return instruction(opcode, &args);
+}
+
+/// Load `data` as a section named `name`, returns where it was placed
+pub fn load(executor: &mut Executor, ty: SectionType, name: &str, data: &[u8]) -> Address {
+    let section = SinSection::new(ty, xxh3_64(name.as_bytes()), 0, data.len() as u64);
+    executor.load_section(&section, data).unwrap();
+    return executor
+        .section_manager()
+        .get_section(name)
+        .unwrap()
+        .mem_start();
+}
+
+/// Load procedures from one sin data buffer and point ip at the one named `start`
+pub fn load_procedures(executor: &mut Executor, procedures: &[(&str, Vec<u8>)]) {
+    let mut data = Vec::new();
+    let mut sections = Vec::new();
+    for (name, code) in procedures {
+        let start = data.len() as u64;
+        data.extend_from_slice(code);
+        sections.push(SinSection::new(
+            SectionType::Procedure,
+            xxh3_64(name.as_bytes()),
+            start,
+            data.len() as u64,
+        ));
+    }
+    for section in sections.iter() {
+        executor.load_section(section, &data).unwrap();
+    }
+    let entry = executor
+        .section_manager()
+        .get_section("start")
+        .unwrap()
+        .mem_start();
+    executor.registers().set_ip(entry);
+}
+
+/// Load `code` as the procedure `start` and point ip at it
+pub fn load_start(executor: &mut Executor, code: &[u8]) {
+    let entry = load(executor, SectionType::Procedure, "start", code);
+    executor.registers().set_ip(entry);
+}
+
+/// Compile raion asm into sections and their data
+pub fn assemble(source: &str) -> Result<(Vec<SinSection>, Vec<u8>), CompilerError<ASMToken>> {
+    let path: Arc<Path> = Path::new("test.asm").into();
+    let tokens = ASMLexer::new(source, path).tokenize().unwrap();
+    return ASMCompiler::new(tokens).compile();
+}
@@ -0,0 +1,75 @@
+use common::{
+    constants::{MOV_OPCODE, MOV_SECTION_ADDR_2REG, OUTS_OPCODE, RET_OPCODE},
+    register::RegisterType,
+    sin::sections::SectionType,
+};
+use craion::{decoder::instruction::InstructionError, executor::ExecutionError};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, load};
+
+/// Prints the constant `name` and returns
+fn print(name: &str) -> Vec<u8> {
+    let mut mov = vec![MOV_SECTION_ADDR_2REG, RegisterType::A64.to_byte()];
+    mov.extend_from_slice(&xxh3_64(name.as_bytes()).to_le_bytes());
+    return [
+        instruction(MOV_OPCODE, &mov),
+        instruction(OUTS_OPCODE, &[RegisterType::A64.to_byte()]),
+        instruction(RET_OPCODE, &[]),
+    ]
+    .concat();
+}
+
+fn string(len: u32, bytes: &[u8]) -> Vec<u8> {
+    return [len.to_le_bytes().as_slice(), bytes].concat();
+}
+
+#[test]
+fn outs() {
+    for mut executor in modes::executors(0xFFFF) {
+        load(
+            &mut executor,
+            SectionType::Constant,
+            "hello",
+            &string(6, b"hello\n"),
+        );
+        load(
+            &mut executor,
+            SectionType::Constant,
+            "invalid",
+            &string(2, &[0xC3, 0x28]),
+        );
+        load(
+            &mut executor,
+            SectionType::Constant,
+            "long",
+            &string(u32::MAX, b"x"),
+        );
+        for name in ["hello", "invalid", "long"] {
+            load(
+                &mut executor,
+                SectionType::Procedure,
+                &format!("print_{name}"),
+                &print(name),
+            );
+        }
+
+        assert!(executor.call("print_hello", &[]).is_ok());
+        assert!(matches!(
+            executor.call("print_invalid", &[]),
+            Err(ExecutionError::Instruction {
+                error: InstructionError::InvalidUTF8,
+                ..
+            })
+        ));
+        assert!(matches!(
+            executor.call("print_long", &[]),
+            Err(ExecutionError::Instruction {
+                error: InstructionError::AccessingMemoryError(_),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+use common::{
+    constants::{MOV_DEREF_REG2REG, MOV_OPCODE, MOV_REG2DEREF_REG},
+    register::RegisterType,
+};
+use craion::{instruction_helper::InstructionHelper, memory::address::Address};
+
+mod modes;
+
+#[test]
+fn access_across_pages() {
+    for mut executor in modes::executors(1 << 40) {
+        InstructionHelper::new(&mut executor.memory())
+            .encode(MOV_OPCODE)
+            .encode_sub_opcode(MOV_REG2DEREF_REG)
+            .encode_register(RegisterType::A64)
+            .encode_register(RegisterType::B64)
+            .end()
+            .encode(MOV_OPCODE)
+            .encode_sub_opcode(MOV_DEREF_REG2REG)
+            .encode_register(RegisterType::C64)
+            .encode_register(RegisterType::B64)
+            .end()
+            .halt();
+        executor
+            .registers()
+            .set_general(&RegisterType::A64, 0x0807060504030201)
+            .unwrap();
+        executor
+            .registers()
+            .set_general(&RegisterType::B64, 0x80_0000_0FFC)
+            .unwrap();
+        executor.execute();
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::C64)
+                .unwrap(),
+            0x0807060504030201
+        );
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0x80_0000_1000), 4)
+                .as_deref(),
+            Ok([5, 6, 7, 8].as_slice())
+        );
+        assert_eq!(executor.memory().allocated_pages(), 3);
+    }
+}
@@ -0,0 +1,178 @@
+use common::{
+    constants::{CALL_OPCODE, HALT_OPCODE, JMP_OPCODE, MOV_NUM2DEREF_REG, MOV_OPCODE, RET_OPCODE},
+    register::RegisterType,
+    sin::sections::{SectionType, SinSection},
+};
+use craion::{
+    executor::{ExecutionError, LoadError},
+    memory::{address::Address, watchpoint::WatchKind},
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, jump, load, load_start, mov_num};
+
+/// Returns `value` in a64
+fn answer(value: u64) -> Vec<u8> {
+    return [
+        mov_num(RegisterType::A64, value),
+        instruction(RET_OPCODE, &[]),
+    ]
+    .concat();
+}
+
+#[test]
+fn reload_between_calls() {
+    for mut executor in modes::executors(0xFFFF) {
+        load_start(&mut executor, &instruction(HALT_OPCODE, &[]));
+        load(&mut executor, SectionType::Procedure, "answer", &answer(1));
+        let old = executor
+            .section_manager()
+            .get_section("answer")
+            .unwrap()
+            .mem_start();
+
+        assert_eq!(executor.call("answer", &[]).unwrap(), 1);
+        load(&mut executor, SectionType::Procedure, "answer", &answer(2));
+        assert_eq!(executor.call("answer", &[]).unwrap(), 2);
+        assert_eq!(executor.section_manager().retired().count(), 1);
+        assert_eq!(executor.reclaim_sections(), answer(1).len());
+        assert_eq!(executor.section_manager().retired().count(), 0);
+
+        load(&mut executor, SectionType::Procedure, "answer", &answer(3));
+        assert_eq!(
+            executor
+                .section_manager()
+                .get_section("answer")
+                .unwrap()
+                .mem_start(),
+            old
+        );
+        assert_eq!(executor.call("answer", &[]).unwrap(), 3);
+    }
+}
+
+#[test]
+fn running_code_survives_reload() {
+    for mut executor in modes::executors(0xFFFF) {
+        let mut store = vec![MOV_NUM2DEREF_REG, RegisterType::D64.to_byte()];
+        store.extend_from_slice(&1u64.to_le_bytes());
+        let store = instruction(MOV_OPCODE, &store);
+        let pointer = mov_num(RegisterType::D64, 0x8000);
+        let skip = pointer.len() + store.len() + jump(JMP_OPCODE, "patched", 0).len() + 10;
+        let old = [
+            pointer,
+            store,
+            jump(JMP_OPCODE, "patched", skip),
+            vec![0; 10],
+            mov_num(RegisterType::A64, 1),
+            instruction(RET_OPCODE, &[]),
+        ]
+        .concat();
+        let start = [
+            instruction(CALL_OPCODE, &xxh3_64(b"patched").to_le_bytes()),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        load(&mut executor, SectionType::Procedure, "patched", &old);
+        load_start(&mut executor, &start);
+        let watchpoint = executor.add_watchpoint(Address::new(0x8000), 8, WatchKind::Write);
+        executor.execute();
+        assert!(executor.watchpoint_stop().is_some());
+        executor.remove_watchpoint(watchpoint);
+
+        load(&mut executor, SectionType::Procedure, "patched", &answer(2));
+        assert_eq!(executor.reclaim_sections(), 0);
+        executor.execute();
+
+        assert!(executor.registers_ref().get_halt());
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            1
+        );
+        assert_eq!(executor.reclaim_sections(), old.len());
+        assert_eq!(executor.call("patched", &[]).unwrap(), 2);
+    }
+}
+
+#[test]
+fn unload() {
+    for mut executor in modes::executors(0xFFFF) {
+        load_start(&mut executor, &instruction(HALT_OPCODE, &[]));
+        load(&mut executor, SectionType::Procedure, "answer", &answer(1));
+        let hash = xxh3_64(b"answer");
+        let start = executor
+            .section_manager()
+            .get_section("answer")
+            .unwrap()
+            .mem_start();
+
+        executor.unload_section(hash).unwrap();
+        assert!(matches!(
+            executor.call("answer", &[]),
+            Err(ExecutionError::UndefinedProcedure(_))
+        ));
+        assert!(matches!(
+            executor.unload_section(hash),
+            Err(LoadError::UndefinedSection(unloaded)) if unloaded == hash
+        ));
+        assert_eq!(executor.section_manager().retired().count(), 0);
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(start, answer(1).len())
+                .as_deref(),
+            Ok(vec![0; answer(1).len()].as_slice())
+        );
+        load(&mut executor, SectionType::Procedure, "other", &answer(2));
+        assert_eq!(
+            executor
+                .section_manager()
+                .get_section("other")
+                .unwrap()
+                .mem_start(),
+            start
+        );
+    }
+}
+
+#[test]
+fn constants_are_kept_until_freed() {
+    for mut executor in modes::executors(0xFFFF) {
+        load_start(&mut executor, &instruction(HALT_OPCODE, &[]));
+        let constant = |data: &[u8]| {
+            SinSection::new(
+                SectionType::Constant,
+                xxh3_64(b"data"),
+                0,
+                data.len() as u64,
+            )
+        };
+        executor.load_section(&constant(b"old"), b"old").unwrap();
+        let old = executor
+            .section_manager()
+            .get_section("data")
+            .unwrap()
+            .mem_start();
+
+        executor.load_section(&constant(b"new"), b"new").unwrap();
+        executor.unload_section(xxh3_64(b"data")).unwrap();
+        assert_eq!(executor.reclaim_sections(), 0);
+        assert_eq!(executor.section_manager().retired().count(), 2);
+        assert_eq!(
+            executor.memory().mem_gets(old, 3).as_deref(),
+            Ok(b"old".as_slice())
+        );
+
+        assert_eq!(executor.free_retired_constants(), 6);
+        assert_eq!(executor.section_manager().retired().count(), 0);
+        assert_eq!(
+            executor.memory().mem_gets(old, 3).as_deref(),
+            Ok([0; 3].as_slice())
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use common::{
+    constants::{
+        ADD_OPCODE, ADD_REG_W_NUM, ADD_REG_W_REG, ARG_NUM, ARG_OPCODE, HALT_OPCODE, INC_OPCODE,
+        JOIN_OPCODE, LARG_OPCODE, MOV_DEREF_REG2REG, MOV_NUM2REG, MOV_OPCODE, MOV_REG2DEREF_REG,
+        RET_OPCODE, SPAWN_OPCODE, YIELD_OPCODE,
+    },
+    register::RegisterType,
+};
+use craion::{
+    assembler::Assembler,
+    decoder::instruction::InstructionError,
+    executor::{thread::ThreadError, ExecutionError},
+    memory::address::Address,
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::{instruction, load_procedures};
+
+fn arg(index: u32, value: u64) -> Vec<u8> {
+    let mut args = vec![ARG_NUM];
+    args.extend_from_slice(&index.to_le_bytes());
+    args.extend_from_slice(&value.to_le_bytes());
+    return instruction(ARG_OPCODE, &args);
+}
+
+fn spawn(register: RegisterType, name: &str) -> Vec<u8> {
+    let mut args = vec![register.to_byte()];
+    args.extend_from_slice(&xxh3_64(name.as_bytes()).to_le_bytes());
+    return instruction(SPAWN_OPCODE, &args);
+}
+
+fn join(register: RegisterType, id: RegisterType) -> Vec<u8> {
+    return instruction(JOIN_OPCODE, &[register.to_byte(), id.to_byte()]);
+}
+
+fn mov(sub_opcode: u8, a: RegisterType, b: RegisterType) -> Vec<u8> {
+    return instruction(MOV_OPCODE, &[sub_opcode, a.to_byte(), b.to_byte()]);
+}
+
+/// Append the low byte of a64 to the log whose end is stored at 0x8000, then yield
+fn log_and_yield() -> Vec<u8> {
+    let mut pointer = vec![MOV_NUM2REG, RegisterType::D64.to_byte()];
+    pointer.extend_from_slice(&0x8000u64.to_le_bytes());
+    return [
+        instruction(MOV_OPCODE, &pointer),
+        mov(MOV_DEREF_REG2REG, RegisterType::C64, RegisterType::D64),
+        mov(MOV_REG2DEREF_REG, RegisterType::A8, RegisterType::C64),
+        instruction(INC_OPCODE, &[RegisterType::C64.to_byte()]),
+        mov(MOV_REG2DEREF_REG, RegisterType::C64, RegisterType::D64),
+        instruction(YIELD_OPCODE, &[]),
+    ]
+    .concat();
+}
+
+#[test]
+fn round_robin_and_join() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start = [
+            arg(0, 1),
+            spawn(RegisterType::B64, "worker"),
+            arg(0, 2),
+            spawn(RegisterType::C64, "worker"),
+            join(RegisterType::A64, RegisterType::B64),
+            join(RegisterType::D64, RegisterType::C64),
+            instruction(
+                ADD_OPCODE,
+                &[
+                    ADD_REG_W_REG,
+                    RegisterType::A64.to_byte(),
+                    RegisterType::D64.to_byte(),
+                ],
+            ),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        let mut add = vec![ADD_REG_W_NUM, RegisterType::A64.to_byte()];
+        add.extend_from_slice(&10u64.to_le_bytes());
+        let worker = [
+            instruction(LARG_OPCODE, &[RegisterType::A64.to_byte(), 0, 0, 0, 0]),
+            log_and_yield(),
+            log_and_yield(),
+            log_and_yield(),
+            instruction(ADD_OPCODE, &add),
+            instruction(RET_OPCODE, &[]),
+        ]
+        .concat();
+        load_procedures(&mut executor, &[("start", start), ("worker", worker)]);
+        executor
+            .memory()
+            .mem_sets(Address::new(0x8000), &0x9000u64.to_le_bytes())
+            .unwrap();
+        executor.execute();
+
+        assert!(executor.registers_ref().get_halt());
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::A64)
+                .unwrap(),
+            23
+        );
+        assert_eq!(
+            executor
+                .memory()
+                .mem_gets(Address::new(0x9000), 6)
+                .as_deref(),
+            Ok([1, 2, 1, 2, 1, 2].as_slice())
+        );
+        assert_eq!(executor.threads().live(), 1);
+    }
+}
+
+#[test]
+fn join_self_deadlocks() {
+    for mut executor in modes::executors(0xFFFF) {
+        let start = [
+            join(RegisterType::A64, RegisterType::B64),
+            instruction(HALT_OPCODE, &[]),
+        ]
+        .concat();
+        load_procedures(&mut executor, &[("start", start)]);
+        executor.execute();
+
+        assert!(!executor.registers_ref().get_halt());
+        assert_eq!(executor.registers_ref().get_ip(), Address::new(0));
+    }
+}
+
+/// The main thread gets the top 0x400 bytes of the stack once another thread is live
+#[test]
+fn stack_slots() {
+    for mut executor in modes::executors(0x10000) {
+        Assembler::new()
+            .procedure("alone")
+            .sub_sp_num(0x800)
+            .push(RegisterType::A64)
+            .add_sp_num(0x808)
+            .mov_reg_num(RegisterType::A64, 1)
+            .ret()
+            .procedure("overflow")
+            .spawn(RegisterType::A64, "worker")
+            .sub_sp_num(0x800)
+            .push(RegisterType::A64)
+            .ret()
+            .procedure("worker")
+            .ret()
+            .load(&mut executor)
+            .unwrap();
+
+        assert_eq!(executor.call("alone", &[]).unwrap(), 1);
+        assert!(matches!(
+            executor.call("overflow", &[]),
+            Err(ExecutionError::Thread {
+                error: ThreadError::StackOverflow(0),
+                ..
+            })
+        ));
+    }
+    for mut executor in modes::executors(0x10000) {
+        Assembler::new()
+            .procedure("deep_spawn")
+            .sub_sp_num(0x800)
+            .spawn(RegisterType::A64, "worker")
+            .ret()
+            .procedure("worker")
+            .ret()
+            .load(&mut executor)
+            .unwrap();
+        assert!(matches!(
+            executor.call("deep_spawn", &[]),
+            Err(ExecutionError::Instruction {
+                error: InstructionError::ThreadError(ThreadError::StackOverflow(0)),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+use craion::executor::Executor;
+use raion::compiler::CompilerError;
+use xxhash_rust::xxh3::xxh3_64;
+
+mod modes;
+
+use modes::assemble;
+
+#[test]
+fn type_block() {
+    let (sections, data) = assemble(
+        r#"
+type point -> {
+    .field x, 0, u64
+    .field y, 8, u64
+    .size 16
+    .align 8
+}
+proc start -> {
+    halt
+}
+"#,
+    )
+    .unwrap();
+    let mut executor = Executor::new(0xFFFF);
+    for section in sections.iter() {
+        executor.load_section(section, &data).unwrap();
+    }
+    let point = executor.types().get(xxh3_64(b"point")).unwrap();
+    assert_eq!((point.size(), point.alignment()), (16, 8));
+    let y = point.field(xxh3_64(b"y")).unwrap();
+    assert_eq!((y.offset(), y.type_hash()), (8, xxh3_64(b"u64")));
+}
+
+#[test]
+fn field_outside_type() {
+    let error = assemble(
+        r#"
+type point -> {
+    .size 16
+    .field x, 0, u64
+    .field z, 16, u64
+}
+"#,
+    )
+    .unwrap_err();
+    assert!(matches!(error, CompilerError::FieldOutOfType(field, _) if field == "z"));
+    assert!(matches!(
+        assemble("type empty -> {\n    .field x, 0, u64\n}\n"),
+        Err(CompilerError::FieldOutOfType(..))
+    ));
+}
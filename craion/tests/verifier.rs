@@ -0,0 +1,142 @@
+use common::{
+    constants::{
+        CALL_OPCODE, HALT_OPCODE, INC_OPCODE, JMP_OPCODE, JTAB_OPCODE, MOV_NUM2REG, MOV_OPCODE,
+    },
+    register::RegisterType,
+    sin::{
+        sections::{SectionType, SinSection},
+        Sin,
+    },
+};
+use craion::verifier::{Verifier, VerifierErrorKind};
+
+mod modes;
+
+use modes::instruction;
+
+fn jump(opcode: u16, section: u64, offset: u16) -> Vec<u8> {
+    let mut args = section.to_le_bytes().to_vec();
+    args.extend_from_slice(&offset.to_le_bytes());
+    return instruction(opcode, &args);
+}
+
+fn verify(sections: &[(SectionType, u64, Vec<u8>)]) -> Result<(), Vec<VerifierErrorKind>> {
+    let mut data = Vec::new();
+    let mut sin_sections = Vec::new();
+    for (ty, hash, section) in sections {
+        let start = data.len() as u64;
+        data.extend_from_slice(section);
+        sin_sections.push(SinSection::new(*ty, *hash, start, data.len() as u64));
+    }
+    let sin = Sin::new(sin_sections, &data);
+    return Verifier::new(&sin)
+        .verify()
+        .map_err(|errors| errors.iter().map(|e| *e.kind()).collect());
+}
+
+#[test]
+fn valid_procedure() {
+    let mut code = instruction(
+        MOV_OPCODE,
+        &[MOV_NUM2REG, RegisterType::A16.to_byte(), 5, 0],
+    );
+    code.extend(instruction(INC_OPCODE, &[RegisterType::A16.to_byte()]));
+    code.extend(jump(JMP_OPCODE, 1, 0));
+    code.extend(instruction(CALL_OPCODE, &2u64.to_le_bytes()));
+    code.extend(instruction(HALT_OPCODE, &[]));
+    let result = verify(&[
+        (SectionType::Procedure, 1, code),
+        (SectionType::Procedure, 2, instruction(HALT_OPCODE, &[])),
+    ]);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn unknown_opcode() {
+    let result = verify(&[(SectionType::Procedure, 1, instruction(1234, &[]))]);
+    assert!(matches!(
+        result.unwrap_err()[..],
+        [VerifierErrorKind::UnknownOpCode(1234)]
+    ));
+}
+
+#[test]
+fn trailing_operands() {
+    let result = verify(&[(
+        SectionType::Procedure,
+        1,
+        instruction(MOV_OPCODE, &[MOV_NUM2REG, RegisterType::A8.to_byte(), 5, 0]),
+    )]);
+    assert!(matches!(
+        result.unwrap_err()[..],
+        [VerifierErrorKind::TrailingOperands(MOV_OPCODE, 1)]
+    ));
+}
+
+#[test]
+fn jump_not_on_boundary() {
+    let mut code = instruction(INC_OPCODE, &[RegisterType::A64.to_byte()]);
+    code.extend(jump(JMP_OPCODE, 1, 1));
+    let result = verify(&[(SectionType::Procedure, 1, code)]);
+    assert!(matches!(
+        result.unwrap_err()[..],
+        [VerifierErrorKind::JumpNotOnBoundary(1, 1)]
+    ));
+}
+
+#[test]
+fn jump_table_entries() {
+    let mut args = vec![RegisterType::A64.to_byte()];
+    args.extend_from_slice(&1u64.to_le_bytes());
+    args.extend_from_slice(&3u16.to_le_bytes());
+    for offset in [0u16, 5, 30] {
+        args.extend_from_slice(&offset.to_le_bytes());
+    }
+    let mut code = instruction(INC_OPCODE, &[RegisterType::A64.to_byte()]);
+    code.extend(instruction(JTAB_OPCODE, &args));
+    let result = verify(&[(SectionType::Procedure, 1, code.clone())]);
+    assert!(matches!(
+        result.unwrap_err()[..],
+        [
+            VerifierErrorKind::JumpNotOnBoundary(1, 5),
+            VerifierErrorKind::JumpOutOfSection(1, 30)
+        ]
+    ));
+
+    // The count says 4 but only 3 entries follow
+    let count = code.len() - 3 * 2 - 2;
+    code[count] = 4;
+    let result = verify(&[(SectionType::Procedure, 1, code)]);
+    assert!(matches!(
+        result.unwrap_err()[..],
+        [VerifierErrorKind::TruncatedOperands(JTAB_OPCODE)]
+    ));
+}
+
+#[test]
+fn call_constant_section() {
+    let result = verify(&[
+        (
+            SectionType::Procedure,
+            1,
+            instruction(CALL_OPCODE, &2u64.to_le_bytes()),
+        ),
+        (SectionType::Constant, 2, vec![0; 4]),
+    ]);
+    assert!(matches!(
+        result.unwrap_err()[..],
+        [VerifierErrorKind::NotProcedureSection(2)]
+    ));
+}
+
+#[test]
+fn jump_into_constant_section() {
+    let result = verify(&[
+        (SectionType::Procedure, 1, jump(JMP_OPCODE, 2, 0)),
+        (SectionType::Constant, 2, vec![0; 4]),
+    ]);
+    assert!(matches!(
+        result.unwrap_err()[..],
+        [VerifierErrorKind::NotProcedureSection(2)]
+    ));
+}
@@ -0,0 +1,89 @@
+use common::{
+    constants::{MOV_DEREF_REG2REG, MOV_OPCODE, MOV_REG2DEREF_REG},
+    register::RegisterType,
+};
+use craion::{
+    instruction_helper::InstructionHelper,
+    memory::{
+        address::Address,
+        watchpoint::{Access, WatchKind},
+    },
+};
+
+mod modes;
+
+#[test]
+fn write_watchpoint_stops() {
+    for mut executor in modes::executors(0xFFFF) {
+        InstructionHelper::new(&mut executor.memory())
+            .encode(MOV_OPCODE)
+            .encode_sub_opcode(MOV_REG2DEREF_REG)
+            .encode_register(RegisterType::A16)
+            .encode_register(RegisterType::B64)
+            .end()
+            .encode(MOV_OPCODE)
+            .encode_sub_opcode(MOV_DEREF_REG2REG)
+            .encode_register(RegisterType::C16)
+            .encode_register(RegisterType::B64)
+            .end()
+            .halt();
+        executor
+            .memory()
+            .mem_sets(Address::new(0x101), &[9, 9])
+            .unwrap();
+        executor
+            .registers()
+            .set_general(&RegisterType::A16, 0x0201)
+            .unwrap();
+        executor
+            .registers()
+            .set_general(&RegisterType::B64, 0x100)
+            .unwrap();
+        let id = executor.add_watchpoint(Address::new(0x101), 1, WatchKind::Write);
+        executor.execute();
+
+        let stop = executor.watchpoint_stop().unwrap();
+        assert_eq!(stop.ip(), Address::new(0));
+        assert_eq!(stop.opcode(), MOV_OPCODE);
+        assert_eq!(stop.hits().len(), 1);
+        assert_eq!(stop.hits()[0].id(), id);
+        assert_eq!(stop.hits()[0].access(), Access::Write);
+        assert_eq!(stop.hits()[0].address(), Address::new(0x100));
+        assert_eq!(stop.hits()[0].old_value(), &[0, 9]);
+        assert_eq!(stop.hits()[0].new_value(), &[1, 2]);
+        assert_eq!(executor.registers().get_halt(), false);
+
+        executor.execute();
+        assert!(executor.watchpoint_stop().is_none());
+        assert_eq!(executor.registers().get_halt(), true);
+        assert_eq!(
+            executor
+                .registers()
+                .get_general(&RegisterType::C16)
+                .unwrap(),
+            0x0201
+        );
+    }
+}
+
+#[test]
+fn removed_watchpoint_does_not_stop() {
+    for mut executor in modes::executors(0xFFFF) {
+        InstructionHelper::new(&mut executor.memory())
+            .encode(MOV_OPCODE)
+            .encode_sub_opcode(MOV_DEREF_REG2REG)
+            .encode_register(RegisterType::C64)
+            .encode_register(RegisterType::B64)
+            .end()
+            .halt();
+        executor
+            .registers()
+            .set_general(&RegisterType::B64, 0x100)
+            .unwrap();
+        let id = executor.add_watchpoint(Address::new(0x104), 4, WatchKind::Read);
+        assert!(executor.remove_watchpoint(id));
+        executor.execute();
+        assert!(executor.watchpoint_stop().is_none());
+        assert_eq!(executor.registers().get_halt(), true);
+    }
+}
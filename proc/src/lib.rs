@@ -1,114 +1,291 @@
-use std::sync::Mutex;
+use std::collections::HashSet;
 
 use proc_macro::TokenStream;
-use proc_macro2::TokenTree;
+use proc_macro2::Span;
 use quote::quote;
 use syn::{
+    braced,
+    ext::IdentExt,
+    parenthesized,
     parse::{Parse, ParseStream},
-    parse_macro_input, parse_str,
+    parse_macro_input,
     punctuated::Punctuated,
-    Ident, ItemFn, LitStr, Path, Token,
+    Data, DeriveInput, Fields, Ident, Index, LitInt, Token,
 };
 
 extern crate proc_macro;
 
-struct Instruction {
-    fn_path: String,
-    op_code: String,
+/// `(Operand, ...)` optionally followed by `syntax(index, ...)`, the encoding index of each
+/// operand in the order they are written in assembly
+struct Operands {
+    operands: Vec<Ident>,
+    syntax: Option<Vec<LitInt>>,
 }
 
-static INSTRUCTIONS: Mutex<Vec<Instruction>> = Mutex::new(Vec::new());
+/// `NAME = value (operands)` inside the braces of an op code with sub op codes
+struct SubOpcode {
+    name: Ident,
+    value: LitInt,
+    operands: Operands,
+}
 
-impl Parse for Instruction {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let op_code = Ident::parse(input)?;
-        input.step(
-            |cursor| match cursor.token_tree().ok_or(cursor.error("Expected `,`"))? {
-                (TokenTree::Punct(punct), next) => match punct.to_string().as_str() {
-                    "," => return Ok(((), next)),
-                    unexpected => {
-                        return Err(cursor.error(format!("Expected `,` found `{unexpected}`")))
-                    }
-                },
-                (unexpected, ..) => Err(cursor.error(format!("Expected `,` found `{unexpected}`"))),
-            },
-        )?;
+enum OpcodeBody {
+    Operands(Operands),
+    SubOpcodes(Vec<SubOpcode>),
+}
 
-        let fn_path: LitStr = Parse::parse(input)?;
-        let fn_path = fn_path.parse::<Path>()?;
+/// `name value (operands)` or `name value { sub op codes }`, the name is the handler module
+/// and gives the `NAME_OPCODE` constant
+struct Opcode {
+    name: Ident,
+    value: LitInt,
+    body: OpcodeBody,
+}
 
-        Ok(Instruction {
-            fn_path: quote!(#fn_path).to_string(),
-            op_code: op_code.to_string(),
-        })
+/// One mnemonic and the op codes it assembles to, separated by `|`
+struct Mnemonic {
+    opcodes: Vec<Opcode>,
+}
+
+struct Isa {
+    mnemonics: Vec<Mnemonic>,
+}
+
+impl Parse for Operands {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let operands = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        let syntax = if input.peek(Ident) && input.fork().parse::<Ident>()? == "syntax" {
+            input.parse::<Ident>()?;
+            let content;
+            parenthesized!(content in input);
+            let syntax = Punctuated::<LitInt, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect::<Vec<_>>();
+            if syntax.len() != operands.len() {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "`syntax` needs an index for every operand",
+                ));
+            }
+            Some(syntax)
+        } else {
+            None
+        };
+        Ok(Operands { operands, syntax })
     }
 }
 
-#[proc_macro_attribute]
-pub fn instruction(args: TokenStream, input: TokenStream) -> TokenStream {
-    let input_fn = parse_macro_input!(input as ItemFn);
-    INSTRUCTIONS
-        .lock()
-        .unwrap()
-        .push(parse_macro_input!(args as Instruction));
-    quote!(#input_fn).into()
+impl Parse for SubOpcode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        let operands = input.parse()?;
+        Ok(SubOpcode {
+            name,
+            value,
+            operands,
+        })
+    }
 }
 
-struct VariableArgs {
-    identifiers: Punctuated<Ident, Token![,]>,
+impl Parse for Opcode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let value = input.parse()?;
+        let body = if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            let sub_opcodes = Punctuated::<SubOpcode, Token![,]>::parse_terminated(&content)?;
+            OpcodeBody::SubOpcodes(sub_opcodes.into_iter().collect())
+        } else {
+            OpcodeBody::Operands(input.parse()?)
+        };
+        Ok(Opcode { name, value, body })
+    }
 }
 
-impl Parse for VariableArgs {
+impl Parse for Isa {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let identifiers = input.parse_terminated(Ident::parse, Token![,])?;
+        let mut mnemonics = Vec::new();
+        while !input.is_empty() {
+            let mut opcodes = vec![input.parse::<Opcode>()?];
+            while input.peek(Token![|]) {
+                input.parse::<Token![|]>()?;
+                opcodes.push(input.parse()?);
+            }
+            mnemonics.push(Mnemonic { opcodes });
+        }
+        Ok(Isa { mnemonics })
+    }
+}
 
-        Ok(VariableArgs { identifiers })
+impl Opcode {
+    fn constant(&self) -> Ident {
+        return Ident::new(
+            &format!("{}_OPCODE", self.name.unraw().to_string().to_uppercase()),
+            self.name.span(),
+        );
     }
 }
 
+fn form(
+    opcode: &Ident,
+    sub_opcode: Option<&Ident>,
+    operands: &Operands,
+) -> proc_macro2::TokenStream {
+    let sub_opcode = match sub_opcode {
+        Some(sub_opcode) => quote!(Some(opcodes::#sub_opcode)),
+        None => quote!(None),
+    };
+    let kinds = &operands.operands;
+    let syntax = match &operands.syntax {
+        Some(syntax) => quote!(Some(&[#(#syntax),*])),
+        None => quote!(None),
+    };
+    return quote! {
+        Form::new(opcodes::#opcode, #sub_opcode, &[#(Operand::#kinds),*], #syntax)
+    };
+}
+
+/// Generates the instruction set from one table
+///
+/// Expands to an `opcodes` module with a `NAME_OPCODE` constant for every op code and a
+/// constant for every sub op code, an `INSTRUCTIONS` table of `Instruction`s with the forms
+/// the assembler tries in order, and an exported `instruction_handler!` macro that matches an
+/// op code to the handler function `name::name` at the call site.
 #[proc_macro]
-pub fn collect_instruction(args: TokenStream) -> TokenStream {
-    let instruction = INSTRUCTIONS.lock().unwrap();
-    let input = parse_macro_input!(args as VariableArgs);
-    let op_code_var = input.identifiers.get(0).expect("Invalid argument");
-    let register_var = input.identifiers.get(1).expect("Invalid argument");
-    let memory_var = input.identifiers.get(2).expect("Invalid argument");
-    let argument_var = input.identifiers.get(3).expect("Invalid argument");
-    let instruction_length_var = input.identifiers.get(4).expect("Invalid argument");
-    let ret_stack_var = input.identifiers.get(5).expect("Invalid argument");
-    let section_manager_var = input.identifiers.get(6).expect("Invalid argument");
-    let executor_state_var = input.identifiers.get(7).expect("Invalid argument");
-    let decode_logic = instruction
+pub fn isa(input: TokenStream) -> TokenStream {
+    let isa = parse_macro_input!(input as Isa);
+
+    let mut constants = Vec::new();
+    let mut handlers = Vec::new();
+    let mut instructions = Vec::new();
+    let mut seen = HashSet::new();
+    for mnemonic in isa.mnemonics.iter() {
+        let mut forms = Vec::new();
+        for opcode in mnemonic.opcodes.iter() {
+            let constant = opcode.constant();
+            let value = &opcode.value;
+            if !seen.insert(constant.to_string()) {
+                return syn::Error::new(constant.span(), "op code is defined twice")
+                    .to_compile_error()
+                    .into();
+            }
+            constants.push(quote!(pub const #constant: u16 = #value;));
+            let handler = &opcode.name;
+            handlers.push(quote!($crate::isa::opcodes::#constant => #handler::#handler,));
+            match &opcode.body {
+                OpcodeBody::Operands(operands) => forms.push(form(&constant, None, operands)),
+                OpcodeBody::SubOpcodes(sub_opcodes) => {
+                    for sub_opcode in sub_opcodes {
+                        let name = &sub_opcode.name;
+                        let value = &sub_opcode.value;
+                        constants.push(quote!(pub const #name: u8 = #value;));
+                        forms.push(form(&constant, Some(name), &sub_opcode.operands));
+                    }
+                }
+            }
+        }
+        let name = mnemonic.opcodes[0].name.unraw().to_string();
+        instructions.push(quote!(Instruction::new(#name, &[#(#forms),*])));
+    }
+
+    return quote! {
+        pub mod opcodes {
+            #(#constants)*
+        }
+
+        pub static INSTRUCTIONS: &[Instruction] = &[#(#instructions),*];
+
+        /// Match an op code to its handler, `name::name` has to resolve where this is used
+        #[macro_export]
+        macro_rules! instruction_handler {
+            ($opcode:expr, $other:pat => $fallback:expr) => {
+                match $opcode {
+                    #(#handlers)*
+                    $other => $fallback,
+                }
+            };
+        }
+    }
+    .into();
+}
+
+/// The field accessors of a struct in declaration order, enums and unions are an error
+fn struct_fields(input: &DeriveInput) -> syn::Result<(Vec<proc_macro2::TokenStream>, &Fields)> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "only structs can derive an encoding",
+        ));
+    };
+    let accessors = data
+        .fields
         .iter()
-        .map(|instruction| {
-            let opcode_ts = syn::Ident::new(&instruction.op_code, proc_macro2::Span::call_site());
-            let instruction_ts: Path =
-                parse_str(&instruction.fn_path).expect("Failed to parse path");
-            quote! {
-                common::constants::#opcode_ts => {
-                    return Ok(Self {
-                        instruction_executor: #instruction_ts,
-                        instruction_argument: InstructionArgument {
-                            register: #register_var,
-                            memory: #memory_var,
-                            argument: #argument_var,
-                            instruction_length: #instruction_length_var,
-                            ret_stack: #ret_stack_var,
-                            section_manager: #section_manager_var,
-                            executor_state: #executor_state_var
-                        },
-                        opcode: #op_code_var,
-                    })
-                },
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
             }
         })
-        .collect::<Vec<_>>();
-    let output = quote! {
-        match #op_code_var {
-            #(#decode_logic)*
-            iop_code => return Err(DecoderError::InvalidOpCode(iop_code)),
-        }
+        .collect();
+    return Ok((accessors, &data.fields));
+}
+
+/// Encodes every field in declaration order with `common::encoding::Encode`
+#[proc_macro_derive(Encode)]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (accessors, _) = match struct_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
     };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    return quote! {
+        impl #impl_generics ::common::encoding::Encode for #name #ty_generics #where_clause {
+            fn encode(&self, writer: &mut ::common::memory::buffer_writer::BufferWriter) {
+                #(::common::encoding::Encode::encode(&self.#accessors, writer);)*
+            }
+        }
+    }
+    .into();
+}
 
-    return output.into();
+/// Decodes every field in declaration order with `common::encoding::Decode`
+#[proc_macro_derive(Decode)]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let (accessors, fields) = match struct_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let decode = quote!(::common::encoding::Decode::decode(reader)?);
+    let value = match fields {
+        Fields::Named(_) => quote!(Self { #(#accessors: #decode),* }),
+        Fields::Unnamed(_) => {
+            let decodes = accessors.iter().map(|_| &decode);
+            quote!(Self(#(#decodes),*))
+        }
+        Fields::Unit => quote!(Self),
+    };
+    return quote! {
+        impl #impl_generics ::common::encoding::Decode for #name #ty_generics #where_clause {
+            fn decode(
+                reader: &mut ::common::memory::buffer_reader::BufferReader,
+            ) -> Result<Self, ::common::encoding::EncodingError> {
+                return Ok(#value);
+            }
+        }
+    }
+    .into();
 }
@@ -12,6 +12,9 @@ pub enum CompilerError<T: Token> {
     UndefinedLabel(String, Location),
     MultipleLabel(String, Location),
     InvalidArgument(Location),
+    UnknownDirective(String, Location),
+    /// A `.field` at an offset past the `.size` of its type
+    FieldOutOfType(String, Location),
 }
 
 impl<T: Token> Display for CompilerError<T> {
@@ -54,6 +57,12 @@ impl<T: Token> Display for CompilerError<T> {
             Self::InvalidArgument(line) => {
                 write!(f, "invalid argument on line {line}")
             }
+            Self::UnknownDirective(directive, line) => {
+                write!(f, "unknown directive `.{directive}` on line {line}")
+            }
+            Self::FieldOutOfType(field, line) => {
+                write!(f, "field `{field}` is outside of its type on line {line}")
+            }
         }
     }
 }
@@ -1,22 +1,17 @@
 use std::collections::HashMap;
 
-use argument_parser::{ArgumentParser, ArgumentType, ParsedArguments};
+use argument_parser::{ArgumentParser, ParsedArguments};
 use common::{
-    constants::{
-        ADD_REG_W_NUM, ADD_REG_W_REG, ADD_SP_W_NUM, ARG_NUM, ARG_REG, MOV_ADD2SP,
-        MOV_DEREF_REG2REG, MOV_DEREF_REG_WITH_OFFSET2REG, MOV_NUM2DEREF_REG_WITH_OFFSET,
-        MOV_NUM2REG, MOV_REG2DEREF_REG_WITH_OFFSET, MOV_REG2REG, MOV_REG2SP,
-        MOV_SECTION_ADDR2DEREF_REG_WITH_OFFSET, MOV_SECTION_ADDR_2REG, SUB_REG_W_NUM,
-        SUB_REG_W_REG, SUB_SP_W_NUM,
+    isa::Form,
+    sin::{
+        debug_info::{DebugInfo, ProcedureDebugInfo, DEBUG_SECTION_NAME},
+        sections::{SectionType, SinSection},
+        type_info::{TypeEntry, TypeInfo, TYPE_SECTION_NAME},
     },
-    sin::sections::{SectionType, SinSection},
 };
 use xxhash_rust::xxh3::xxh3_64;
 
-use crate::{
-    token::asm_token::{ASMToken, InstructionType},
-    Location, WithLocation,
-};
+use crate::{token::asm_token::ASMToken, Location, WithLocation};
 
 use super::{CompilerBase, CompilerError};
 
@@ -34,6 +29,9 @@ pub struct ASMCompiler {
     data: Vec<u8>,
     sections: Vec<SinSection>,
     write_pos: usize,
+    debug_info: DebugInfo,
+    section_debug_info: Option<ProcedureDebugInfo>,
+    type_info: TypeInfo,
 }
 
 impl ASMCompiler {
@@ -43,6 +41,9 @@ impl ASMCompiler {
             data: Vec::new(),
             sections: Vec::new(),
             write_pos: 0,
+            debug_info: DebugInfo::new(),
+            section_debug_info: None,
+            type_info: TypeInfo::new(),
         }
     }
 
@@ -64,7 +65,7 @@ impl ASMCompiler {
         labels: HashMap<String, usize>,
     ) -> Result<(), CompilerError<ASMToken>> {
         for label_replace in &label_replaces {
-            let data = &mut self.data[label_replace.pos..(label_replace.pos + 8)];
+            let data = &mut self.data[label_replace.pos..(label_replace.pos + 2)];
             let label_data =
                 labels
                     .get(&label_replace.label)
@@ -72,21 +73,28 @@ impl ASMCompiler {
                         label_replace.label.clone(),
                         label_replace.location.clone(),
                     ))?;
-            data.copy_from_slice(&(*label_data as u64).to_le_bytes());
+            let label_data = u16::try_from(*label_data)
+                .map_err(|_| CompilerError::InvalidArgument(label_replace.location.clone()))?;
+            data.copy_from_slice(&label_data.to_le_bytes());
         }
         return Ok(());
     }
 
-    fn try_parse_argument(&self, arg_types: &[ArgumentType]) -> Option<ParsedArguments> {
-        let mut parser = ArgumentParser::new(&self.base);
-        for arg_type in arg_types {
-            parser = parser.parse(*arg_type);
+    /// Parse the arguments of one form in the order they are written, returns them in the
+    /// order they are encoded after the sub op code
+    fn try_parse_form(&self, form: &Form, procedure_hash: u64) -> Option<ParsedArguments> {
+        let mut parser = ArgumentParser::new(&self.base, procedure_hash);
+        for index in form.syntax_order() {
+            parser = parser.parse(form.operands()[index]);
+        }
+        if !parser.is_valid() {
+            return None;
         }
-        if parser.is_valid() {
-            Some(parser.build())
-        } else {
-            None
+        let mut arguments = parser.build().reorder(form.syntax_order());
+        if let Some(sub_opcode) = form.sub_opcode() {
+            arguments.insert(0, vec![sub_opcode]);
         }
+        return Some(arguments);
     }
 
     fn consume_until_newline(&mut self) {
@@ -99,6 +107,148 @@ impl ASMCompiler {
         }
     }
 
+    fn section_debug_info(&mut self, section_hash: u64) -> &mut ProcedureDebugInfo {
+        return self.section_debug_info.get_or_insert_with(|| {
+            ProcedureDebugInfo::new(section_hash, String::new(), String::new())
+        });
+    }
+
+    fn parse_directive_string(&mut self) -> Result<String, CompilerError<ASMToken>> {
+        match self.base.consume().cloned() {
+            Some(WithLocation {
+                value: ASMToken::String(string),
+                ..
+            }) => return Ok(string),
+            token => return Err(CompilerError::UnexpectedToken(token)),
+        }
+    }
+
+    fn parse_directive_integer(&mut self) -> Result<u32, CompilerError<ASMToken>> {
+        match self.base.consume().cloned() {
+            Some(WithLocation {
+                value: ASMToken::Interger(number),
+                location,
+            }) => {
+                return number
+                    .try_into()
+                    .map_err(|_| CompilerError::InvalidArgument(location))
+            }
+            token => return Err(CompilerError::UnexpectedToken(token)),
+        }
+    }
+
+    /// Parse a directive inside of a section
+    ///
+    /// `.name "path.to.proc"` sets the unmangled name of the section
+    /// `.loc "file.rin", line, column` maps the next instruction to a source location
+    /// `.str "text"` writes the text with its u32 byte length in front, the layout `outs` prints
+    fn parse_directive(
+        &mut self,
+        directive: &str,
+        location: Location,
+        section_hash: u64,
+        section_start: usize,
+    ) -> Result<(), CompilerError<ASMToken>> {
+        let offset = (self.write_pos - section_start) as u32;
+        match directive {
+            "name" => {
+                let name = self.parse_directive_string()?;
+                self.section_debug_info(section_hash).set_name(name);
+            }
+            "loc" => {
+                let file = self.parse_directive_string()?;
+                self.base.expect_token(ASMToken::Comma)?;
+                let line = self.parse_directive_integer()?;
+                self.base.expect_token(ASMToken::Comma)?;
+                let column = self.parse_directive_integer()?;
+                let debug_info = self.section_debug_info(section_hash);
+                debug_info.set_file(file);
+                debug_info.push_line(offset, line, column);
+            }
+            "str" => {
+                let value = self.parse_directive_string()?;
+                self.write(&(value.len() as u32).to_le_bytes());
+                self.write(value.as_bytes());
+            }
+            _ => {
+                return Err(CompilerError::UnknownDirective(
+                    directive.to_string(),
+                    location,
+                ))
+            }
+        }
+        return Ok(());
+    }
+
+    /// Parse the body of a struct layout
+    ///
+    /// `.size 16` and `.align 8` set the size and alignment of the type
+    /// `.field name, offset, type` adds a field of `type` at `offset`
+    /// Parse the layout of a `type` block. Types only come from these blocks, the rin
+    /// compiler doesn't declare structs
+    fn parse_type(&mut self, type_hash: u64) -> Result<TypeEntry, CompilerError<ASMToken>> {
+        let mut entry = TypeEntry::new(type_hash, 0, 1);
+        // `.size` can follow the fields, so they are checked against it at the end
+        let mut fields = Vec::new();
+        self.base.expect_token(ASMToken::LCurly)?;
+        while let Some(token) = self.base.peek(0).cloned() {
+            match token {
+                WithLocation {
+                    value: ASMToken::Directive(directive),
+                    location,
+                } => {
+                    self.base.consume();
+                    match directive.as_str() {
+                        "size" => {
+                            let size = self.parse_directive_integer()?;
+                            entry.set_layout(size.into(), entry.alignment());
+                        }
+                        "align" => {
+                            let alignment = self.parse_directive_integer()?;
+                            entry.set_layout(entry.size(), alignment.into());
+                        }
+                        "field" => {
+                            let field = self.parse_name()?;
+                            self.base.expect_token(ASMToken::Comma)?;
+                            let offset = self.parse_directive_integer()?;
+                            self.base.expect_token(ASMToken::Comma)?;
+                            let ty = self.parse_name()?;
+                            entry.push_field(
+                                xxh3_64(field.as_bytes()),
+                                offset.into(),
+                                xxh3_64(ty.as_bytes()),
+                            );
+                            fields.push((field, offset, location));
+                        }
+                        _ => return Err(CompilerError::UnknownDirective(directive, location)),
+                    }
+                    self.consume_until_newline();
+                }
+                WithLocation {
+                    value: ASMToken::NewLine,
+                    ..
+                } => {
+                    self.base.consume();
+                }
+                WithLocation {
+                    value: ASMToken::RCurly,
+                    ..
+                } => {
+                    break;
+                }
+                unexpected => return Err(CompilerError::UnexpectedToken(Some(unexpected))),
+            }
+        }
+        self.base.expect_token(ASMToken::RCurly)?;
+        if let Some((field, _, location)) = fields
+            .into_iter()
+            .find(|(_, offset, _)| u64::from(*offset) >= entry.size())
+        {
+            return Err(CompilerError::FieldOutOfType(field, location));
+        }
+        return Ok(entry);
+    }
+
     pub fn parse_section(
         &mut self,
         procedure_hash: u64,
@@ -126,198 +276,19 @@ impl ASMCompiler {
                     location,
                 } => {
                     self.base.consume();
-                    let (argument, mut label_replaces) = match instruction {
-                        InstructionType::Mov => {
-                            let mut subopcode = MOV_REG2REG;
-                            let mut args = self
-                                .try_parse_argument(&[
-                                    ArgumentType::Register,
-                                    ArgumentType::Register,
-                                ])
-                                .or_else(|| {
-                                    subopcode = MOV_DEREF_REG2REG;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::Register,
-                                        ArgumentType::DerefRegister,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_NUM2REG;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::Register,
-                                        ArgumentType::U64,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_ADD2SP;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::RegisterSp,
-                                        ArgumentType::U64,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_REG2SP;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::RegisterSp,
-                                        ArgumentType::Register,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_SECTION_ADDR_2REG;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::Register,
-                                        ArgumentType::Section,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_NUM2DEREF_REG_WITH_OFFSET;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::DerefRegisterOffset,
-                                        ArgumentType::U64,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_REG2DEREF_REG_WITH_OFFSET;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::DerefRegisterOffset,
-                                        ArgumentType::Register,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_DEREF_REG_WITH_OFFSET2REG;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::Register,
-                                        ArgumentType::DerefRegisterOffset,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = MOV_SECTION_ADDR2DEREF_REG_WITH_OFFSET;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::DerefRegisterOffset,
-                                        ArgumentType::Section,
-                                    ])
-                                })
-                                .ok_or(CompilerError::InvalidArgument(location.clone()))?;
-                            args.insert(0, vec![subopcode]);
-                            args
-                        }
-                        InstructionType::Cmp | InstructionType::Mul | InstructionType::Div => self
-                            .try_parse_argument(&[ArgumentType::Register, ArgumentType::Register])
-                            .ok_or(CompilerError::InvalidArgument(location.clone()))?,
-                        InstructionType::Add => {
-                            let mut subopcode = ADD_REG_W_REG;
-                            let mut args = self
-                                .try_parse_argument(&[
-                                    ArgumentType::Register,
-                                    ArgumentType::Register,
-                                ])
-                                .or_else(|| {
-                                    subopcode = ADD_REG_W_NUM;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::Register,
-                                        ArgumentType::U64,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = ADD_SP_W_NUM;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::RegisterSp,
-                                        ArgumentType::U64,
-                                    ])
-                                })
-                                .ok_or(CompilerError::InvalidArgument(location.clone()))?;
-                            args.insert(0, vec![subopcode]);
-                            args
-                        }
-                        InstructionType::Sub => {
-                            let mut subopcode = SUB_REG_W_REG;
-                            let mut args = self
-                                .try_parse_argument(&[
-                                    ArgumentType::Register,
-                                    ArgumentType::Register,
-                                ])
-                                .or_else(|| {
-                                    subopcode = SUB_REG_W_NUM;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::Register,
-                                        ArgumentType::U64,
-                                    ])
-                                })
-                                .or_else(|| {
-                                    subopcode = SUB_SP_W_NUM;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::RegisterSp,
-                                        ArgumentType::U64,
-                                    ])
-                                })
-                                .ok_or(CompilerError::InvalidArgument(location.clone()))?;
-                            args.insert(0, vec![subopcode]);
-                            args
-                        }
-                        InstructionType::Inc
-                        | InstructionType::Pop
-                        | InstructionType::Push
-                        | InstructionType::Outc
-                        | InstructionType::Savr
-                        | InstructionType::Restr
-                        | InstructionType::Exit => self
-                            .try_parse_argument(&[ArgumentType::Register])
-                            .ok_or(CompilerError::InvalidArgument(location.clone()))?,
-                        InstructionType::Jmp
-                        | InstructionType::Jmc
-                        | InstructionType::Jmz
-                        | InstructionType::Jme
-                        | InstructionType::Jmn => {
-                            let mut args = self
-                                .try_parse_argument(&[ArgumentType::Label])
-                                .ok_or(CompilerError::InvalidArgument(location.clone()))?;
-                            args.insert(0, procedure_hash.to_le_bytes().to_vec());
-                            args
-                        }
-                        InstructionType::Call => self
-                            .try_parse_argument(&[ArgumentType::Section])
-                            .ok_or(CompilerError::InvalidArgument(location.clone()))?,
-                        InstructionType::Jacn
-                        | InstructionType::Jacc
-                        | InstructionType::Jace
-                        | InstructionType::Jacz => {
-                            let mut args = self
-                                .try_parse_argument(&[
-                                    ArgumentType::Register,
-                                    ArgumentType::Register,
-                                    ArgumentType::Label,
-                                ])
-                                .ok_or(CompilerError::InvalidArgument(location.clone()))?;
-                            args.insert(2, procedure_hash.to_le_bytes().to_vec());
-                            args
-                        }
-                        InstructionType::Enter => self
-                            .try_parse_argument(&[ArgumentType::U64])
-                            .ok_or(CompilerError::InvalidArgument(location.clone()))?,
-                        InstructionType::Arg => {
-                            let mut subopcode = ARG_NUM;
-                            let mut args = self
-                                .try_parse_argument(&[ArgumentType::U32, ArgumentType::U64])
-                                .or_else(|| {
-                                    subopcode = ARG_REG;
-                                    self.try_parse_argument(&[
-                                        ArgumentType::U32,
-                                        ArgumentType::Register,
-                                    ])
-                                })
-                                .ok_or(CompilerError::InvalidArgument(location.clone()))?;
-                            args.insert(0, vec![subopcode]);
-                            args
-                        }
-                        InstructionType::LArg => self
-                            .try_parse_argument(&[ArgumentType::Register, ArgumentType::U32])
-                            .ok_or(CompilerError::InvalidArgument(location.clone()))?,
-                        InstructionType::Ret | InstructionType::Leave | InstructionType::Halt => {
-                            ParsedArguments::default()
-                        }
+                    let (form, arguments) = instruction
+                        .forms()
+                        .iter()
+                        .find_map(|form| {
+                            self.try_parse_form(form, procedure_hash)
+                                .map(|arguments| (form, arguments))
+                        })
+                        .ok_or(CompilerError::InvalidArgument(location.clone()))?;
+                    let (argument, mut label_replaces) = arguments.finalize(location.clone());
+                    if argument.len() + 3 > u8::MAX as usize {
+                        return Err(CompilerError::InvalidArgument(location));
                     }
-                    .finalize(location);
-                    self.write_instruction(instruction.opcode(), &argument);
+                    self.write_instruction(form.opcode(), &argument);
 
                     // Replace the label using the real offset not argument offset
                     for label_replace in label_replaces.iter_mut() {
@@ -326,6 +297,14 @@ impl ASMCompiler {
                     label_replacess.extend_from_slice(&label_replaces);
                     self.consume_until_newline();
                 }
+                WithLocation {
+                    value: ASMToken::Directive(directive),
+                    location,
+                } => {
+                    self.base.consume();
+                    self.parse_directive(&directive, location, procedure_hash, start)?;
+                    self.consume_until_newline();
+                }
                 WithLocation {
                     value: ASMToken::String(string),
                     ..
@@ -353,6 +332,15 @@ impl ASMCompiler {
         return Ok((start as u64, self.write_pos as u64));
     }
 
+    fn finish_section_debug_info(&mut self, section_name: &str) {
+        if let Some(mut debug_info) = self.section_debug_info.take() {
+            if debug_info.name().is_empty() {
+                debug_info.set_name(section_name.to_string());
+            }
+            self.debug_info.push(debug_info);
+        }
+    }
+
     pub fn parse_name(&mut self) -> Result<String, CompilerError<ASMToken>> {
         let name = match self
             .base
@@ -383,6 +371,7 @@ impl ASMCompiler {
                         let procedure_hash = xxh3_64(procedure_name.as_bytes());
                         self.base.expect_token(ASMToken::Arrow)?;
                         let (start, end) = self.parse_section(procedure_hash)?;
+                        self.finish_section_debug_info(&procedure_name);
                         self.sections.push(SinSection::new(
                             SectionType::Procedure,
                             procedure_hash,
@@ -396,6 +385,7 @@ impl ASMCompiler {
                         let const_hash = xxh3_64(const_name.as_bytes());
                         self.base.expect_token(ASMToken::Arrow)?;
                         let (start, end) = self.parse_section(const_hash)?;
+                        self.finish_section_debug_info(&const_name);
                         self.sections.push(SinSection::new(
                             SectionType::Constant,
                             xxh3_64(const_name.as_bytes()),
@@ -403,6 +393,13 @@ impl ASMCompiler {
                             end,
                         ));
                     }
+                    "type" => {
+                        self.base.consume();
+                        let type_name = self.parse_name()?;
+                        self.base.expect_token(ASMToken::Arrow)?;
+                        let entry = self.parse_type(xxh3_64(type_name.as_bytes()))?;
+                        self.type_info.push(entry);
+                    }
                     _ => return Err(CompilerError::UnexpectedToken(Some(token))),
                 },
                 WithLocation {
@@ -414,6 +411,26 @@ impl ASMCompiler {
                 unexpected => return Err(CompilerError::UnexpectedToken(Some(unexpected))),
             }
         }
+        if !self.debug_info.is_empty() {
+            let start = self.write_pos;
+            self.write(&self.debug_info.to_bytes());
+            self.sections.push(SinSection::new(
+                SectionType::Debug,
+                xxh3_64(DEBUG_SECTION_NAME.as_bytes()),
+                start as u64,
+                self.write_pos as u64,
+            ));
+        }
+        if !self.type_info.is_empty() {
+            let start = self.write_pos;
+            self.write(&self.type_info.to_bytes());
+            self.sections.push(SinSection::new(
+                SectionType::Types,
+                xxh3_64(TYPE_SECTION_NAME.as_bytes()),
+                start as u64,
+                self.write_pos as u64,
+            ));
+        }
         return Ok((self.sections, self.data));
     }
 }
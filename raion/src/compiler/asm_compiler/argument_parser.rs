@@ -1,36 +1,31 @@
-use common::{inline_if, register::RegisterType};
+use common::{
+    encoding::Encode, isa::Operand, memory::buffer_writer::BufferWriter, register::RegisterType,
+};
 use xxhash_rust::xxh3::xxh3_64;
 
 use crate::{compiler::CompilerBase, token::asm_token::ASMToken, Location};
 
 use super::LabelReplace;
 
-#[derive(Clone, Copy, Debug)]
-pub enum ArgumentType {
-    Register,
-    RegisterSp,
-    U64,
-    U32,
-    Section,
-    Label,
-    DerefRegister,
-    DerefRegisterOffset,
-}
-
 enum ParsedArgument {
     Register(RegisterType),
     U64(u64),
     U32(u32),
-    Booolean(bool),
+    U16(u16),
+    Immediate(u64, usize),
     Section(u64),
-    Label(String),
+    /// The section the label is in and the label
+    Jump(u64, String),
+    JumpTable(u64, Vec<String>),
+    DerefOffset(RegisterType, u32, bool),
     Buffer(Vec<u8>),
 }
 
 pub struct ArgumentParser<'a> {
     compiler: &'a CompilerBase<ASMToken>,
     current_offset: usize,
-    arguments_parse: Vec<ArgumentType>,
+    procedure_hash: u64,
+    arguments_parse: Vec<Operand>,
 }
 
 #[derive(Default)]
@@ -47,46 +42,80 @@ impl ParsedArguments {
         self.arguments.insert(index, ParsedArgument::Buffer(buffer));
     }
 
+    /// Move the arguments from the order they are written in to the order they are encoded in
+    pub fn reorder(self, syntax_order: impl Iterator<Item = usize>) -> Self {
+        let mut arguments = self
+            .arguments
+            .into_iter()
+            .zip(syntax_order)
+            .collect::<Vec<_>>();
+        arguments.sort_by_key(|(_, index)| *index);
+        return Self::new(
+            arguments
+                .into_iter()
+                .map(|(argument, _)| argument)
+                .collect(),
+        );
+    }
+
     pub fn finalize(self, location: Location) -> (Vec<u8>, Vec<LabelReplace>) {
         let mut label_replaces = Vec::new();
-        let mut buffer = Vec::new();
+        let mut writer = BufferWriter::new();
+        let mut label = |writer: &mut BufferWriter, label: String| {
+            label_replaces.push(LabelReplace {
+                label,
+                pos: writer.get_write_pos(),
+                location: location.clone(),
+            });
+            writer.write_u16(0);
+        };
 
         for arg in self.arguments {
             match arg {
                 ParsedArgument::U64(data) | ParsedArgument::Section(data) => {
-                    buffer.extend_from_slice(&data.to_le_bytes())
-                }
-                ParsedArgument::U32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
-                ParsedArgument::Register(register) => buffer.push(register.to_byte()),
-                ParsedArgument::Label(label) => {
-                    label_replaces.push(LabelReplace {
-                        label,
-                        pos: buffer.len(),
-                        location: location.clone(),
-                    });
-                    buffer.extend_from_slice(&0u64.to_le_bytes())
-                }
-                ParsedArgument::Booolean(value) => {
-                    buffer.push(inline_if!(value, 1, 0));
-                }
-                ParsedArgument::Buffer(data) => buffer.extend_from_slice(&data),
+                    data.encode(&mut writer)
+                }
+                ParsedArgument::U32(value) => value.encode(&mut writer),
+                ParsedArgument::U16(value) => value.encode(&mut writer),
+                ParsedArgument::Immediate(value, size) => {
+                    writer.write_bytes(&value.to_le_bytes()[..size])
+                }
+                ParsedArgument::Register(register) => register.encode(&mut writer),
+                ParsedArgument::Jump(section, target) => {
+                    section.encode(&mut writer);
+                    label(&mut writer, target);
+                }
+                ParsedArgument::JumpTable(section, targets) => {
+                    section.encode(&mut writer);
+                    writer.write_u16(targets.len() as u16);
+                    for target in targets {
+                        label(&mut writer, target);
+                    }
+                }
+                ParsedArgument::DerefOffset(register, offset, is_add) => {
+                    register.encode(&mut writer);
+                    offset.encode(&mut writer);
+                    is_add.encode(&mut writer);
+                }
+                ParsedArgument::Buffer(data) => writer.write_bytes(&data),
             }
         }
 
-        return (buffer, label_replaces);
+        return (writer.into_bytes(), label_replaces);
     }
 }
 
 impl<'a> ArgumentParser<'a> {
-    pub fn new(compiler: &'a CompilerBase<ASMToken>) -> Self {
+    pub fn new(compiler: &'a CompilerBase<ASMToken>, procedure_hash: u64) -> Self {
         Self {
             compiler,
             current_offset: 0,
+            procedure_hash,
             arguments_parse: Vec::new(),
         }
     }
 
-    pub fn parse(mut self, argument_type: ArgumentType) -> Self {
+    pub fn parse(mut self, argument_type: Operand) -> Self {
         self.arguments_parse.push(argument_type);
         return self;
     }
@@ -104,21 +133,61 @@ impl<'a> ArgumentParser<'a> {
             .all(|(i, expected)| self.match_token(self.current_offset + i, expected))
     }
 
+    /// The number of labels in a jump table starting at the current offset
+    fn jump_table_entries(&self) -> usize {
+        let mut entries = 0;
+        while self.match_token(self.current_offset + entries * 2, |e| {
+            matches!(e, ASMToken::Identifier(_))
+        }) && (entries == 0
+            || self.match_token(self.current_offset + entries * 2 - 1, |e| {
+                matches!(e, ASMToken::Comma)
+            }))
+        {
+            entries += 1;
+        }
+        return entries;
+    }
+
     pub fn is_valid(&mut self) -> bool {
         let len = self.arguments_parse.len();
         for (i, argument) in self.arguments_parse.iter().enumerate() {
             let valid = match argument {
-                ArgumentType::U64 | ArgumentType::U32 => {
+                Operand::U64 | Operand::U32 => {
                     self.match_token(self.current_offset, |e| matches!(e, ASMToken::Interger(_)))
                 }
-                ArgumentType::Register => {
+                Operand::U16 => self.match_token(
+                    self.current_offset,
+                    |e| matches!(e, ASMToken::Interger(number) if *number <= u16::MAX.into()),
+                ),
+                Operand::Hash => self.match_token(self.current_offset, |e| {
+                    matches!(e, ASMToken::Interger(_) | ASMToken::Identifier(_))
+                }),
+                Operand::Immediate => {
+                    let size = match self.compiler.peek(self.current_offset.wrapping_sub(2)) {
+                        Some(token) => match token.value() {
+                            ASMToken::Register(register) => register.size().byte(),
+                            _ => return false,
+                        },
+                        None => return false,
+                    };
+                    self.match_token(self.current_offset, |e| match e {
+                        ASMToken::Interger(number) => size == 8 || *number >> (size * 8) == 0,
+                        _ => false,
+                    })
+                }
+                Operand::Register => {
                     self.match_token(self.current_offset, |e| e.is_register_and_general())
                 }
-                ArgumentType::Section | ArgumentType::Label => self
+                Operand::Section | Operand::Procedure | Operand::Jump => self
                     .match_token(self.current_offset, |e| {
                         matches!(e, ASMToken::Identifier(_))
                     }),
-                ArgumentType::DerefRegister => {
+                Operand::JumpTable => {
+                    let entries = self.jump_table_entries();
+                    self.current_offset += (entries * 2).saturating_sub(2);
+                    entries != 0
+                }
+                Operand::Deref => {
                     let value = self
                         .match_token(self.current_offset, |e| matches!(e, ASMToken::LBracket))
                         && self.match_token(self.current_offset + 1, |e| {
@@ -130,7 +199,7 @@ impl<'a> ArgumentParser<'a> {
                     self.current_offset += 2;
                     value
                 }
-                ArgumentType::DerefRegisterOffset => {
+                Operand::DerefOffset => {
                     let value = self.match_token_sequence(&[
                         |e: &ASMToken| matches!(e, ASMToken::LBracket),
                         |e: &ASMToken| matches!(e, ASMToken::Register(_)),
@@ -141,7 +210,7 @@ impl<'a> ArgumentParser<'a> {
                     self.current_offset += 4;
                     value
                 }
-                ArgumentType::RegisterSp => self.match_token(self.current_offset, |e| {
+                Operand::Sp => self.match_token(self.current_offset, |e| {
                     matches!(e, ASMToken::Register(RegisterType::Sp))
                 }),
             };
@@ -170,42 +239,85 @@ impl<'a> ArgumentParser<'a> {
         let len = self.arguments_parse.len();
         for (i, argument) in self.arguments_parse.iter().enumerate() {
             match argument {
-                ArgumentType::Register | ArgumentType::RegisterSp => {
+                Operand::Register | Operand::Sp => {
                     let register = match self.compiler.peek(self.current_offset).unwrap().value() {
                         ASMToken::Register(register) => register,
                         _ => unreachable!(),
                     };
                     arguments.push(ParsedArgument::Register(register.clone()));
                 }
-                ArgumentType::U64 => {
+                Operand::U64 => {
                     let number = match self.compiler.peek(self.current_offset).unwrap().value() {
                         ASMToken::Interger(number) => number,
                         _ => unreachable!(),
                     };
                     arguments.push(ParsedArgument::U64(*number));
                 }
-                ArgumentType::U32 => {
+                Operand::U32 => {
                     let number = match self.compiler.peek(self.current_offset).unwrap().value() {
                         ASMToken::Interger(number) => number,
                         _ => unreachable!(),
                     };
                     arguments.push(ParsedArgument::U32(*number as u32));
                 }
-                ArgumentType::Section => {
+                Operand::U16 => {
+                    let number = match self.compiler.peek(self.current_offset).unwrap().value() {
+                        ASMToken::Interger(number) => number,
+                        _ => unreachable!(),
+                    };
+                    arguments.push(ParsedArgument::U16(*number as u16));
+                }
+                Operand::Hash => {
+                    let hash = match self.compiler.peek(self.current_offset).unwrap().value() {
+                        ASMToken::Interger(number) => *number,
+                        ASMToken::Identifier(ident) => xxh3_64(ident.as_bytes()),
+                        _ => unreachable!(),
+                    };
+                    arguments.push(ParsedArgument::U64(hash));
+                }
+                Operand::Immediate => {
+                    let number = match self.compiler.peek(self.current_offset).unwrap().value() {
+                        ASMToken::Interger(number) => number,
+                        _ => unreachable!(),
+                    };
+                    let size = match arguments.last() {
+                        Some(ParsedArgument::Register(register)) => register.size().byte(),
+                        _ => unreachable!(),
+                    };
+                    arguments.push(ParsedArgument::Immediate(*number, size));
+                }
+                Operand::Section | Operand::Procedure => {
                     let ident = match self.compiler.peek(self.current_offset).unwrap().value() {
                         ASMToken::Identifier(ident) => ident,
                         _ => unreachable!(),
                     };
                     arguments.push(ParsedArgument::Section(xxh3_64(ident.as_bytes())));
                 }
-                ArgumentType::Label => {
+                Operand::Jump => {
                     let ident = match self.compiler.peek(self.current_offset).unwrap().value() {
                         ASMToken::Identifier(ident) => ident,
                         _ => unreachable!(),
                     };
-                    arguments.push(ParsedArgument::Label(ident.clone()));
+                    arguments.push(ParsedArgument::Jump(self.procedure_hash, ident.clone()));
                 }
-                ArgumentType::DerefRegister => {
+                Operand::JumpTable => {
+                    let entries = self.jump_table_entries();
+                    let targets = (0..entries)
+                        .map(
+                            |entry| match self.compiler.peek(self.current_offset + entry * 2) {
+                                Some(token) => match token.value() {
+                                    ASMToken::Identifier(ident) => ident.clone(),
+                                    _ => unreachable!(),
+                                },
+                                None => unreachable!(),
+                            },
+                        )
+                        .collect();
+                    arguments.push(ParsedArgument::JumpTable(self.procedure_hash, targets));
+
+                    self.current_offset += entries * 2 - 2;
+                }
+                Operand::Deref => {
                     let register =
                         match self.compiler.peek(self.current_offset + 1).unwrap().value() {
                             ASMToken::Register(register) => register,
@@ -215,7 +327,7 @@ impl<'a> ArgumentParser<'a> {
 
                     self.current_offset += 2;
                 }
-                ArgumentType::DerefRegisterOffset => {
+                Operand::DerefOffset => {
                     let register =
                         match self.compiler.peek(self.current_offset + 1).unwrap().value() {
                             ASMToken::Register(register) => register,
@@ -226,16 +338,17 @@ impl<'a> ArgumentParser<'a> {
                         ASMToken::Interger(offset) => offset,
                         _ => unreachable!(),
                     };
-
-                    arguments.push(ParsedArgument::Register(register.clone()));
-                    arguments.push(ParsedArgument::U32(*offset as u32));
                     let is_add = match self.compiler.peek(self.current_offset + 2).unwrap().value()
                     {
                         ASMToken::Plus => true,
                         ASMToken::Minus => false,
                         _ => unreachable!(),
                     };
-                    arguments.push(ParsedArgument::Booolean(is_add));
+                    arguments.push(ParsedArgument::DerefOffset(
+                        register.clone(),
+                        *offset as u32,
+                        is_add,
+                    ));
 
                     self.current_offset += 4;
                 }
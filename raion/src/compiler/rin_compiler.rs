@@ -52,6 +52,8 @@ pub enum Type {
     I32,
     I64,
     Bool,
+    /// Address of a u32 byte length followed by UTF-8 bytes
+    Str,
     Void,
     //Struct(String)
 }
@@ -656,7 +658,7 @@ impl Display for Path {
 impl Type {
     fn size(&self) -> TypeSizes {
         match self {
-            Self::U64 | Self::I64 => TypeSizes::SizeU64,
+            Self::U64 | Self::I64 | Self::Str => TypeSizes::SizeU64,
             Self::U32 | Self::I32 => TypeSizes::SizeU32,
             Self::U16 | Self::I16 => TypeSizes::SizeU16,
             Self::U8 | Self::I8 | Self::Bool => TypeSizes::SizeU8,
@@ -690,6 +692,7 @@ impl Display for Type {
             Self::I64 => write!(f, "i64"),
             Self::Void => write!(f, "void"),
             Self::Bool => write!(f, "bool"),
+            Self::Str => write!(f, "str"),
         }
     }
 }
@@ -698,6 +701,7 @@ impl From<PrimitiveType> for Type {
     fn from(primitive_type: PrimitiveType) -> Self {
         match primitive_type {
             PrimitiveType::Bool => Self::Bool,
+            PrimitiveType::Str => Self::Str,
             PrimitiveType::U64 => Self::U64,
             PrimitiveType::U32 => Self::U32,
             PrimitiveType::U16 => Self::U16,
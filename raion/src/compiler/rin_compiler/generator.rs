@@ -3,7 +3,7 @@ use std::{collections::HashMap, error::Error, fmt::Display};
 use block_generator::{BlockGenerator, ReturnDestion};
 use common::register::RegisterType;
 
-use crate::{error::ErrorGenerator, WithLocation};
+use crate::{error::ErrorGenerator, Location, WithLocation};
 
 use super::{Expression, Parameter, Path, Procedure, RinAst, Type};
 use inline_colorization::*;
@@ -240,10 +240,19 @@ struct ProcGenerator<'a> {
     stack_loc: usize,
     variables: Variables,
     body: String,
-    constants: String,
+    constants: Constants,
     callable_procs: &'a Vec<ProcedureHeader>,
 }
 
+/// Constant sections used by a procedure, emitted after it
+#[derive(Default)]
+struct Constants {
+    /// Section name of the procedure, constants are named after it
+    prefix: String,
+    output: String,
+    count: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct ProcedureHeader {
     pub callable_path: Path,
@@ -343,7 +352,7 @@ impl<'a> ProcGenerator<'a> {
             stack_loc: 0,
             variables: HashMap::new(),
             body: String::new(),
-            constants: String::new(),
+            constants: Constants::default(),
             callable_procs,
         }
     }
@@ -353,15 +362,29 @@ impl<'a> ProcGenerator<'a> {
         proc: &'b Procedure,
         header: &ProcedureHeader,
     ) -> Result<String, GeneratorError<'b>> {
+        self.constants.prefix = header.real_path.parse();
         self.gen_argument(&proc.parameters);
-        let (return_type, generated) =
-            BlockGenerator::new(&self.variables, &mut self.stack_loc, &self.callable_procs)
-                .gen_block(&proc.body, ReturnDestion::LeaveReturn)?;
+        let (return_type, generated) = BlockGenerator::new(
+            &self.variables,
+            &mut self.stack_loc,
+            &self.callable_procs,
+            &mut self.constants,
+        )
+        .gen_block(&proc.body, ReturnDestion::LeaveReturn)?;
         self.body.push_str(&generated);
         self.body.push_str("}\n");
-        self.body.push_str(&self.constants);
+        self.body.push_str(&self.constants.output);
         self.body
             .insert_str(0, &format!("   enter {}\n", self.stack_loc));
+        self.body
+            .insert_str(0, &format!("   {}\n", debug_location(proc.name.location())));
+        self.body.insert_str(
+            0,
+            &format!(
+                "   .name \"{}\"\n",
+                escape_string(&header.real_path.to_string())
+            ),
+        );
         self.body
             .insert_str(0, &format!("proc {} -> {{\n", header.real_path.parse()));
         if proc.return_type.value != return_type.value {
@@ -393,3 +416,44 @@ impl<'a> ProcGenerator<'a> {
         self.body.push_str(&format!("   {value}\n"));
     }
 }
+
+impl Constants {
+    /// Add a string constant, returns the name of its section
+    fn add_string(&mut self, value: &str) -> String {
+        let name = format!("{}$str{}", self.prefix, self.count);
+        self.count += 1;
+        self.output.push_str(&format!(
+            "const {name} -> {{\n   .str \"{}\"\n}}\n",
+            escape_string(value)
+        ));
+        return name;
+    }
+}
+
+/// Returns a `.loc` directive mapping the next instruction to `location`
+fn debug_location(location: &Location) -> String {
+    return format!(
+        ".loc \"{}\", {}, {}",
+        escape_string(&location.file().display().to_string()),
+        location.column(),
+        location.row()
+    );
+}
+
+fn escape_string(value: &str) -> String {
+    let mut escaped = String::new();
+    for char in value.chars() {
+        match char {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\u{08}' => escaped.push_str("\\b"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\u{0C}' => escaped.push_str("\\f"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            char => escaped.push(char),
+        }
+    }
+    return escaped;
+}
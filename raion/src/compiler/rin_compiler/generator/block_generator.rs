@@ -12,7 +12,10 @@ use crate::{
     WithLocation,
 };
 
-use super::{ExpressionDestination, GeneratorError, ProcedureHeader, Variable, Variables};
+use super::{
+    debug_location, Constants, ExpressionDestination, GeneratorError, ProcedureHeader, Variable,
+    Variables,
+};
 
 pub struct BlockGenerator<'a> {
     stack_loc: &'a mut usize,
@@ -20,6 +23,7 @@ pub struct BlockGenerator<'a> {
     local_variables: Variables,
     body: String,
     callable_procs: &'a Vec<ProcedureHeader>,
+    constants: &'a mut Constants,
 }
 
 pub enum ReturnDestion {
@@ -35,6 +39,7 @@ impl<'a> BlockGenerator<'a> {
         variables: &'a Variables,
         stack_loc: &'a mut usize,
         callable_procs: &'a Vec<ProcedureHeader>,
+        constants: &'a mut Constants,
     ) -> Self {
         Self {
             variables,
@@ -42,6 +47,7 @@ impl<'a> BlockGenerator<'a> {
             local_variables: Variables::new(),
             body: String::new(),
             callable_procs,
+            constants,
         }
     }
 
@@ -57,6 +63,7 @@ impl<'a> BlockGenerator<'a> {
         let mut return_type = WithLocation::new(Type::Void, location.clone());
         let mut have_return = false;
         for statement in block.body.iter() {
+            self.add_instruction(debug_location(statement.location()));
             if let Some(stmt_type) = self.gen_statement(statement, &return_dst)? {
                 if return_type.value != Type::Void && return_type.value != *stmt_type {
                     return Err(GeneratorError::UnexpectedType {
@@ -94,8 +101,9 @@ impl<'a> BlockGenerator<'a> {
     ) -> Result<Option<WithLocation<Type>>, GeneratorError<'b>> {
         match statement {
             Statement::VariableDecl { name, value } => {
-                let stack_loc = *self.stack_loc;
                 let expr_type = self.gen_expression(value, ExpressionDestination::Stack, &[])?;
+                // The value is stored last, after any temporaries the expression needed
+                let stack_loc = *self.stack_loc - expr_type.size().byte();
                 self.local_variables.insert(
                     name.value.clone(),
                     Variable {
@@ -294,7 +302,12 @@ impl<'a> BlockGenerator<'a> {
             location,
         } = literal;
         let typ = match literal {
-            Literal::String(_value) => todo!(),
+            Literal::String(value) => {
+                self.preserve_registers(preserved_registers, &[RegisterTypeGroup::A]);
+                let name = self.constants.add_string(value);
+                self.add_instruction(format!("mov a64, {name}"));
+                Type::Str
+            }
             Literal::U64(value) => {
                 self.preserve_registers(preserved_registers, &[RegisterTypeGroup::A]);
                 self.add_instruction(format!("mov a64, {value}"));
@@ -360,13 +373,21 @@ impl<'a> BlockGenerator<'a> {
         dst: ExpressionDestination,
         preserved_registers: &[RegisterTypeGroup],
     ) -> Result<WithLocation<Type>, GeneratorError<'b>> {
-        let proc = self
+        let Some(proc) = self
             .callable_procs
             .iter()
             .find(|e| e.callable_path == path.value)
-            .ok_or(GeneratorError::UndefinedProcedure(path))?;
+        else {
+            if path.value == Path::new("print") {
+                return self.gen_print(path, args, dst, preserved_registers);
+            }
+            return Err(GeneratorError::UndefinedProcedure(path));
+        };
         let WithLocation { location, .. } = path;
         self.preserve_registers(preserved_registers, &[RegisterTypeGroup::A]);
+        // Evaluate every argument onto the stack first so nested calls can't clobber
+        // arguments that are already set, then pass them all right before the call
+        let mut arguments = Vec::new();
         for (i, (expr, param_type)) in args
             .iter()
             .map(|p| Some(p))
@@ -380,18 +401,15 @@ impl<'a> BlockGenerator<'a> {
             .enumerate()
         {
             if let (Some(expr), Some(param_type)) = (expr, param_type) {
-                let expr_type = self.gen_expression(
-                    expr,
-                    ExpressionDestination::Register(RegisterType::A64),
-                    &[],
-                )?;
+                let expr_type = self.gen_expression(expr, ExpressionDestination::Stack, &[])?;
                 if expr_type.value != param_type.value {
                     return Err(GeneratorError::UnexpectedType {
                         expected: param_type.clone(),
                         unexpected: expr_type,
                     });
                 }
-                self.add_instruction(format!("arg {i}, a64"));
+                let size = expr_type.size().byte();
+                arguments.push((i, *self.stack_loc - size, size * 8));
             } else if let (Some(expr), None) = (expr, param_type) {
                 return Err(GeneratorError::TooMuchArguments(
                     path,
@@ -409,12 +427,57 @@ impl<'a> BlockGenerator<'a> {
                 ));
             }
         }
+        for (i, stack_loc, bits) in arguments {
+            self.add_instruction(format!("mov a{bits}, [sp + {stack_loc}]"));
+            self.add_instruction(format!("arg {i}, a64"));
+        }
         self.add_instruction(format!("call {}", proc.real_path.parse()));
         let proc_return_type = self.finalize_expression_result(dst, proc.return_type)?;
         self.restore_registers(preserved_registers, &[RegisterTypeGroup::A]);
         return Ok(WithLocation::new(proc_return_type, location.clone()));
     }
 
+    /// `print(value: str): void` writes the string to the output, a procedure named `print`
+    /// takes its place
+    fn gen_print<'b>(
+        &mut self,
+        path: &'b WithLocation<Path>,
+        args: &'b [WithLocation<Expression>],
+        dst: ExpressionDestination,
+        preserved_registers: &[RegisterTypeGroup],
+    ) -> Result<WithLocation<Type>, GeneratorError<'b>> {
+        let WithLocation { location, .. } = path;
+        let value = match args {
+            [value] => value,
+            [] => return Err(GeneratorError::TooFewArguemnts(path, 0, 1, 1)),
+            [_, unexpected, ..] => {
+                return Err(GeneratorError::TooMuchArguments(
+                    path,
+                    unexpected,
+                    args.len(),
+                    1,
+                    2,
+                ))
+            }
+        };
+        self.preserve_registers(preserved_registers, &[RegisterTypeGroup::A]);
+        let value_type = self.gen_expression(
+            value,
+            ExpressionDestination::Register(RegisterType::A64),
+            &[],
+        )?;
+        if value_type.value != Type::Str {
+            return Err(GeneratorError::UnexpectedType {
+                expected: WithLocation::new(Type::Str, location.clone()),
+                unexpected: value_type,
+            });
+        }
+        self.add_instruction("outs a64");
+        let return_type = self.finalize_expression_result(dst, Type::Void)?;
+        self.restore_registers(preserved_registers, &[RegisterTypeGroup::A]);
+        return Ok(WithLocation::new(return_type, location.clone()));
+    }
+
     fn get_variable<'b>(
         &self,
         name: &'b WithLocation<String>,
@@ -106,22 +106,18 @@ impl<'a, T: Token> LexerBase<'a, T> {
     }
 
     fn peek(&self, offset: usize) -> Option<char> {
-        return self
-            .buffer
-            .as_bytes()
-            .get(self.index + offset)
-            .map(|&b| b as char);
+        return self.buffer[self.index..].chars().nth(offset);
     }
 
     fn consume(&mut self) -> Option<char> {
-        if let Some(&byte) = self.buffer.as_bytes().get(self.index) {
-            if byte as char == '\n' {
+        if let Some(charactor) = self.peek(0) {
+            if charactor == '\n' {
                 self.row = 0;
                 self.column += 1;
             }
-            self.index += 1;
+            self.index += charactor.len_utf8();
             self.row += 1;
-            return Some(byte as char);
+            return Some(charactor);
         } else {
             return None;
         }
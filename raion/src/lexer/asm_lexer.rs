@@ -1,11 +1,8 @@
 use std::{path::Path, str::FromStr, sync::Arc};
 
-use common::register::RegisterType;
+use common::{isa, register::RegisterType};
 
-use crate::{
-    token::asm_token::{ASMToken, InstructionType},
-    WithLocation,
-};
+use crate::{token::asm_token::ASMToken, WithLocation};
 
 use super::{LexerBase, LexerError};
 
@@ -38,8 +35,8 @@ impl<'a> ASMLexer<'a> {
                     continue;
                 }
 
-                if let Ok(instruction_type) = InstructionType::from_str(&buffer) {
-                    self.base.push(ASMToken::Instruction(instruction_type));
+                if let Some(instruction) = isa::instruction(&buffer) {
+                    self.base.push(ASMToken::Instruction(instruction));
                     buffer.clear();
                     continue;
                 }
@@ -54,6 +51,18 @@ impl<'a> ASMLexer<'a> {
                 buffer.clear();
                 continue;
             }
+            if value == '.' {
+                self.base.save_location();
+                self.base.consume();
+                self.base.consume_while(
+                    &mut buffer,
+                    |e| e.is_alphanumeric() || e == '_',
+                    |_| false,
+                );
+                self.base.push(ASMToken::Directive(buffer.clone()));
+                buffer.clear();
+                continue;
+            }
             if value.is_digit(10) {
                 self.base.parse_interger()?;
                 continue;
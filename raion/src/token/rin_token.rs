@@ -13,6 +13,7 @@ pub enum PrimitiveType {
     I32,
     I64,
     Bool,
+    Str,
     Void,
 }
 
@@ -122,6 +123,7 @@ impl Display for PrimitiveType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Bool => write!(f, "boolean"),
+            Self::Str => write!(f, "string"),
             Self::U64 => write!(f, "unsigned 64 bit interger"),
             Self::U32 => write!(f, "unsigned 32 bit interger"),
             Self::U16 => write!(f, "unsigned 16 bit interger"),
@@ -152,6 +154,7 @@ impl FromStr for PrimitiveType {
             "i64" => Ok(Self::I64),
             "void" => Ok(Self::Void),
             "bool" => Ok(Self::Bool),
+            "str" => Ok(Self::Str),
             _ => Err(InvalidType),
         }
     }
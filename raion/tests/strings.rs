@@ -0,0 +1,49 @@
+use std::{path::Path, sync::Arc};
+
+use common::sin::sections::SectionType;
+use raion::{
+    compiler::{
+        asm_compiler::ASMCompiler,
+        rin_compiler::{generator::Generator, Path as RinPath, RinCompiler},
+    },
+    lexer::{asm_lexer::ASMLexer, rin_lexer::RinLexer},
+};
+
+/// Assemble `source` and return the data of its constant sections
+fn constants(source: &str) -> Vec<Vec<u8>> {
+    let path: Arc<Path> = Path::new("test.asm").into();
+    let tokens = ASMLexer::new(source, path).tokenize().unwrap();
+    let (sections, data) = ASMCompiler::new(tokens).compile().unwrap();
+    return sections
+        .iter()
+        .filter(|section| section.section_type() == SectionType::Constant)
+        .map(|section| data[section.start() as usize..section.end() as usize].to_vec())
+        .collect();
+}
+
+#[test]
+fn asm_string() {
+    let constants = constants("const s -> {\n   .str \"wörld\"\n}\n");
+    assert_eq!(constants, vec![b"\x06\0\0\0w\xc3\xb6rld".to_vec()]);
+}
+
+#[test]
+fn rin_string() {
+    let path: Arc<Path> = Path::new("main.rin").into();
+    let tokens = RinLexer::new(
+        "proc main(): u32 = {\n  let s = \"hello, wörld\\n\";\n  return 0u32;\n}\n",
+        path,
+    )
+    .tokenize()
+    .unwrap();
+    let mut compiler = RinCompiler::new(tokens);
+    compiler.parse().unwrap();
+    let asm = Generator::new()
+        .generate(compiler.ast(), &RinPath::new("test"), &Vec::new())
+        .unwrap();
+    assert!(asm.contains(".str \"hello, wörld\\n\""));
+    assert_eq!(
+        constants(&asm),
+        vec![b"\x0e\0\0\0hello, w\xc3\xb6rld\n".to_vec()]
+    );
+}